@@ -0,0 +1,91 @@
+use anyhow::Result;
+use cosm_orc::orchestrator::{Key, SigningKey};
+use cosm_orc::{config::cfg::Config, orchestrator::cosm_orc::CosmOrc};
+use cw_abc::msg::{InstantiateMsg, SupplyTokenMode};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Account {
+    name: String,
+    address: String,
+    mnemonic: String,
+}
+
+/// A single commons to deploy, read from the `DEPLOY_CONFIG` TOML file.
+/// `instantiate_msg` is `cw_abc::msg::InstantiateMsg` itself, so every
+/// curve/phase field cw-abc accepts is available here without a second
+/// schema to keep in sync.
+#[derive(Debug, Deserialize)]
+struct DeployConfig {
+    label: String,
+    instantiate_msg: InstantiateMsg,
+}
+
+/// Written to `STATE_FILE` after instantiation, so later runs (or other
+/// environments) can pick the commons back up without re-deploying it.
+#[derive(Debug, Serialize)]
+struct DeployState {
+    code_id: u64,
+    address: String,
+    /// The commons's supply denom, when it's known without a query: a
+    /// token-factory subdenom is deterministic (`factory/<addr>/<subdenom>`),
+    /// but a cw20 supply's address isn't decided until the commons's own
+    /// instantiate reply runs, so this is left unset in that mode.
+    supply_denom: Option<String>,
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let config = env::var("CONFIG").expect("missing cosm-orc yaml CONFIG env var");
+    let deploy_config = env::var("DEPLOY_CONFIG").expect("missing DEPLOY_CONFIG env var");
+    let state_file = env::var("STATE_FILE").unwrap_or_else(|_| "ci/configs/deploy-abc/state.json".to_string());
+
+    let cfg = Config::from_yaml(&config)?;
+    let mut orc = CosmOrc::new(cfg.clone(), false)?;
+    let deploy: DeployConfig = toml::from_str(&fs::read_to_string(&deploy_config)?)?;
+
+    let accounts: Vec<Account> =
+        serde_json::from_slice(&fs::read("ci/configs/test_accounts.json")?)?;
+    let account = accounts[0].clone();
+    let key = SigningKey {
+        name: account.name,
+        key: Key::Mnemonic(account.mnemonic),
+        derivation_path: cfg.chain_cfg.derivation_path.clone(),
+    };
+
+    orc.poll_for_n_blocks(1, Duration::from_millis(20_000), true)?;
+    orc.store_contracts("artifacts", &key, None)?;
+
+    orc.instantiate(
+        "cw_abc",
+        "abc_init",
+        &deploy.instantiate_msg,
+        &key,
+        Some(account.address.parse()?),
+        vec![],
+    )?;
+
+    let code_id = orc.contract_map.code_id("cw_abc")?;
+    let address = orc.contract_map.address("cw_abc")?;
+    let supply_denom = match &deploy.instantiate_msg.supply_token_mode {
+        SupplyTokenMode::TokenFactory { subdenom } => Some(format!("factory/{address}/{subdenom}")),
+        SupplyTokenMode::Cw20 { .. } => None,
+    };
+
+    println!("deployed \"{}\" cw-abc commons at {address}", deploy.label);
+
+    fs::write(
+        &state_file,
+        serde_json::to_string_pretty(&DeployState {
+            code_id,
+            address,
+            supply_denom,
+        })?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,437 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    instantiate2_address, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply,
+    Response, StdResult, SubMsg, WasmMsg,
+};
+
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::state::{
+    abc_registrations, phase_label, AbcRegistration, PendingAbc, ABC_CODE_ID, PENDING_ABC,
+};
+
+pub(crate) const CONTRACT_NAME: &str = "crates.io:cw-abc-factory";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const INSTANTIATE_ABC_REPLY_ID: u64 = 0;
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 50;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw_ownable::initialize_owner(deps.storage, deps.api, msg.owner.as_deref())?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    ABC_CODE_ID.save(deps.storage, &msg.abc_code_id)?;
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("creator", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateAbc {
+            instantiate_msg,
+            label,
+        } => execute_create_abc(deps, env, info, instantiate_msg, label),
+        ExecuteMsg::UpdateOwnership(action) => execute_update_owner(deps, info, env, action),
+        ExecuteMsg::UpdateCodeId { abc_code_id } => execute_update_code_id(deps, info, abc_code_id),
+        ExecuteMsg::SyncAbc { contract } => execute_sync_abc(deps, contract),
+    }
+}
+
+pub fn execute_create_abc(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    instantiate_msg: cw_abc::msg::InstantiateMsg,
+    label: String,
+) -> Result<Response, ContractError> {
+    if PENDING_ABC.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Reentrancy {});
+    }
+    PENDING_ABC.save(
+        deps.storage,
+        &PendingAbc {
+            creator: info.sender.clone(),
+            curve_type: instantiate_msg.curve_type.clone(),
+        },
+    )?;
+
+    let code_id = ABC_CODE_ID.load(deps.storage)?;
+    let wasm_msg = to_binary(&instantiate_msg)?;
+
+    // Only a token-factory commons has a subdenom to derive a salt from, so
+    // only that mode gets a predictable address; a cw20 commons is
+    // instantiated the ordinary (non-deterministic) way.
+    let (instantiate, predicted_contract) = match &instantiate_msg.supply_token_mode {
+        cw_abc::msg::SupplyTokenMode::TokenFactory { subdenom } => {
+            let salt = Binary::from(subdenom.as_bytes());
+            let predicted = predict_abc_address(deps.as_ref(), &env, code_id, &salt)?;
+            (
+                WasmMsg::Instantiate2 {
+                    admin: instantiate_msg.owner.clone(),
+                    code_id,
+                    msg: wasm_msg,
+                    funds: vec![],
+                    label,
+                    salt,
+                },
+                Some(predicted),
+            )
+        }
+        cw_abc::msg::SupplyTokenMode::Cw20 { .. } => (
+            WasmMsg::Instantiate {
+                admin: instantiate_msg.owner.clone(),
+                code_id,
+                msg: wasm_msg,
+                funds: vec![],
+                label,
+            },
+            None,
+        ),
+    };
+
+    let msg = SubMsg::reply_on_success(instantiate, INSTANTIATE_ABC_REPLY_ID);
+
+    let mut response = Response::default()
+        .add_attribute("action", "instantiate_cw_abc")
+        .add_submessage(msg);
+    if let Some(predicted_contract) = predicted_contract {
+        response = response.add_attribute("predicted_contract", predicted_contract);
+    }
+    Ok(response)
+}
+
+/// The address a `code_id` instantiation salted with `salt` by this
+/// contract would be deployed to, computed the same way `wasmd` does for
+/// `MsgInstantiateContract2`.
+fn predict_abc_address(deps: Deps, env: &Env, code_id: u64, salt: &[u8]) -> StdResult<Addr> {
+    let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+    let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+    let predicted = instantiate2_address(&checksum, &creator, salt)
+        .map_err(|err| cosmwasm_std::StdError::generic_err(err.to_string()))?;
+    deps.api.addr_humanize(&predicted)
+}
+
+pub fn execute_update_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+    Ok(Response::default().add_attributes(ownership.into_attributes()))
+}
+
+pub fn execute_update_code_id(
+    deps: DepsMut,
+    info: MessageInfo,
+    abc_code_id: u64,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    ABC_CODE_ID.save(deps.storage, &abc_code_id)?;
+    Ok(Response::default()
+        .add_attribute("action", "update_code_id")
+        .add_attribute("abc_code_id", abc_code_id.to_string()))
+}
+
+pub fn execute_sync_abc(deps: DepsMut, contract: String) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_validate(&contract)?;
+    let existing =
+        abc_registrations()
+            .may_load(deps.storage, contract.as_ref())?
+            .ok_or_else(|| ContractError::NotRegistered {
+                contract: contract.to_string(),
+            })?;
+
+    let curve_info: cw_abc::msg::CurveInfoResponse = deps
+        .querier
+        .query_wasm_smart(contract.clone(), &cw_abc::msg::QueryMsg::CurveInfo {})?;
+    let phase_config: cw_abc::msg::PhaseConfigResponse = deps
+        .querier
+        .query_wasm_smart(contract.clone(), &cw_abc::msg::QueryMsg::PhaseConfig {})?;
+    let phase = phase_label(&phase_config.phase);
+
+    abc_registrations().save(
+        deps.storage,
+        contract.as_ref(),
+        &AbcRegistration {
+            contract: contract.clone(),
+            creator: existing.creator,
+            denom: curve_info.supply_denom,
+            reserve_denom: curve_info.reserve_denom,
+            curve_type: existing.curve_type,
+            phase: phase.to_string(),
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "sync_abc")
+        .add_attribute("contract", contract)
+        .add_attribute("phase", phase))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ListAbcs { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_after.as_deref().map(Bound::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsReverse {
+            start_before,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_before.as_deref().map(Bound::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .range(deps.storage, None, start, Order::Descending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByCreator {
+            creator,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_after.map(Bound::<String>::exclusive);
+
+            deps.api.addr_validate(&creator)?;
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .creator
+                .prefix(creator)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByCreatorReverse {
+            creator,
+            start_before,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_before.map(Bound::<String>::exclusive);
+
+            deps.api.addr_validate(&creator)?;
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .creator
+                .prefix(creator)
+                .range(deps.storage, None, start, Order::Descending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByCurveType {
+            curve_type,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_after.map(Bound::<String>::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .curve_type
+                .prefix(curve_type)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByCurveTypeReverse {
+            curve_type,
+            start_before,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_before.map(Bound::<String>::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .curve_type
+                .prefix(curve_type)
+                .range(deps.storage, None, start, Order::Descending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByReserveDenom {
+            reserve_denom,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_after.map(Bound::<String>::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .reserve_denom
+                .prefix(reserve_denom)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByReserveDenomReverse {
+            reserve_denom,
+            start_before,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_before.map(Bound::<String>::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .reserve_denom
+                .prefix(reserve_denom)
+                .range(deps.storage, None, start, Order::Descending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByPhase {
+            phase,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_after.map(Bound::<String>::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .phase
+                .prefix(phase)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::ListAbcsByPhaseReverse {
+            phase,
+            start_before,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let start = start_before.map(Bound::<String>::exclusive);
+
+            let res: Vec<AbcRegistration> = abc_registrations()
+                .idx
+                .phase
+                .prefix(phase)
+                .range(deps.storage, None, start, Order::Descending)
+                .take(limit)
+                .flat_map(|r| Ok::<AbcRegistration, ContractError>(r?.1))
+                .collect();
+
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::AbcInfo { contract } => {
+            let contract = deps.api.addr_validate(&contract)?;
+            Ok(to_binary(
+                &abc_registrations().may_load(deps.storage, contract.as_ref())?,
+            )?)
+        }
+        QueryMsg::Ownership {} => to_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::AbcCodeId {} => to_binary(&ABC_CODE_ID.load(deps.storage)?),
+        QueryMsg::PredictAbcAddress { subdenom } => {
+            let code_id = ABC_CODE_ID.load(deps.storage)?;
+            let salt = Binary::from(subdenom.as_bytes());
+            Ok(to_binary(&predict_abc_address(deps, &env, code_id, &salt)?)?)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_ABC_REPLY_ID => {
+            let res = parse_reply_instantiate_data(msg)?;
+            let contract_addr = deps.api.addr_validate(&res.contract_address)?;
+
+            let curve_info: cw_abc::msg::CurveInfoResponse = deps
+                .querier
+                .query_wasm_smart(contract_addr.clone(), &cw_abc::msg::QueryMsg::CurveInfo {})?;
+            let phase_config: cw_abc::msg::PhaseConfigResponse = deps.querier.query_wasm_smart(
+                contract_addr.clone(),
+                &cw_abc::msg::QueryMsg::PhaseConfig {},
+            )?;
+
+            let pending = PENDING_ABC.load(deps.storage)?;
+
+            abc_registrations().save(
+                deps.storage,
+                contract_addr.as_ref(),
+                &AbcRegistration {
+                    contract: contract_addr.clone(),
+                    creator: pending.creator,
+                    denom: curve_info.supply_denom,
+                    reserve_denom: curve_info.reserve_denom,
+                    curve_type: pending.curve_type,
+                    phase: phase_label(&phase_config.phase).to_string(),
+                },
+            )?;
+
+            PENDING_ABC.remove(deps.storage);
+
+            Ok(Response::default().add_attribute("new_abc_contract", contract_addr))
+        }
+        _ => Err(ContractError::UnknownReplyId { id: msg.id }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?.version;
+    if version == CONTRACT_VERSION {
+        return Err(ContractError::AlreadyMigrated {});
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
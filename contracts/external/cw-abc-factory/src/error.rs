@@ -0,0 +1,30 @@
+use cosmwasm_std::StdError;
+use cw_utils::ParseReplyError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Ownable(#[from] cw_ownable::OwnershipError),
+
+    #[error(transparent)]
+    ParseReplyError(#[from] ParseReplyError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Got a submessage reply with unknown id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("reentered factory during cw-abc instantiation")]
+    Reentrancy {},
+
+    #[error("{contract} is not a registered cw-abc commons")]
+    NotRegistered { contract: String },
+
+    #[error("Can not migrate. Current version is up to date.")]
+    AlreadyMigrated {},
+}
@@ -0,0 +1,124 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cw_ownable::cw_ownable_execute;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: Option<String>,
+    pub abc_code_id: u64,
+}
+
+/// Empty for now; a future breaking change can add fields here (or turn
+/// this into an enum keyed on the version being migrated from) without
+/// touching the `migrate` handler's version-gating logic.
+#[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_ownable_execute]
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Instantiates a new cw-abc commons from `abc_code_id` and registers
+    /// it under `info.sender` as the creator.
+    CreateAbc {
+        instantiate_msg: cw_abc::msg::InstantiateMsg,
+        label: String,
+    },
+    /// Callable only by the current owner. Updates the code ID used
+    /// while instantiating cw-abc commons.
+    UpdateCodeId { abc_code_id: u64 },
+    /// Refreshes a registered commons's `phase` (and its `denom`/
+    /// `reserve_denom`, defensively) from its current on-chain state.
+    /// Callable by anyone, since the registry only ever reflects what the
+    /// commons itself reports.
+    SyncAbc { contract: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns list of all registered cw-abc commons
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcs {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons in reverse
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsReverse {
+        start_before: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by who created them
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByCreator {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by who created them, in reverse
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByCreatorReverse {
+        creator: String,
+        start_before: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by curve type
+    /// (`"constant"`, `"linear"`, or `"square_root"`)
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByCurveType {
+        curve_type: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by curve type, in reverse
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByCurveTypeReverse {
+        curve_type: String,
+        start_before: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by reserve denom
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByReserveDenom {
+        reserve_denom: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by reserve denom, in reverse
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByReserveDenomReverse {
+        reserve_denom: String,
+        start_before: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by phase
+    /// (`"hatch"`, `"open"`, or `"closed"`, as of each one's last
+    /// registration or `SyncAbc` call)
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByPhase {
+        phase: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns list of all registered cw-abc commons by phase, in reverse
+    #[returns(Vec<crate::state::AbcRegistration>)]
+    ListAbcsByPhaseReverse {
+        phase: String,
+        start_before: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the registration for a single cw-abc commons, if registered
+    #[returns(Option<crate::state::AbcRegistration>)]
+    AbcInfo { contract: String },
+    /// Returns info about the contract ownership, if set
+    #[returns(::cw_ownable::Ownership<::cosmwasm_std::Addr>)]
+    Ownership {},
+    /// Returns the code ID currently being used to instantiate cw-abc commons.
+    #[returns(::std::primitive::u64)]
+    AbcCodeId {},
+    /// Predicts the address a `CreateAbc` instantiating a
+    /// [`cw_abc::msg::SupplyTokenMode::TokenFactory`] commons with this
+    /// `subdenom` would be deployed to, so it (and its resulting token
+    /// factory denom) can be published before the commons exists.
+    #[returns(::cosmwasm_std::Addr)]
+    PredictAbcAddress { subdenom: String },
+}
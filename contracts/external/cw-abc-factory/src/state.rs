@@ -0,0 +1,99 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_abc::curves::CurveType;
+use cw_abc::state::Phase;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+
+/// The code ID instantiated by [`crate::msg::ExecuteMsg::CreateAbc`].
+/// Updatable by the owner via [`crate::msg::ExecuteMsg::UpdateCodeId`].
+pub const ABC_CODE_ID: Item<u64> = Item::new("abc_code_id");
+
+/// Stashed between [`crate::contract::execute_create_abc`] and its reply,
+/// since neither the creator nor the requested curve type are otherwise
+/// recoverable once the instantiate submessage is dispatched.
+#[cw_serde]
+pub struct PendingAbc {
+    pub creator: Addr,
+    pub curve_type: CurveType,
+}
+pub const PENDING_ABC: Item<PendingAbc> = Item::new("pending_abc");
+
+#[cw_serde]
+pub struct AbcRegistration {
+    pub contract: Addr,
+    pub creator: Addr,
+    pub denom: String,
+    pub reserve_denom: String,
+    pub curve_type: CurveType,
+    /// The phase as of the last registration or
+    /// [`crate::msg::ExecuteMsg::SyncAbc`] call. Not pushed by the commons
+    /// itself, so it can go stale between phase transitions until someone
+    /// (typically the front-end reading it) calls `SyncAbc` again.
+    pub phase: String,
+}
+
+pub struct AbcIndexes<'a> {
+    pub creator: MultiIndex<'a, String, AbcRegistration, String>,
+    pub curve_type: MultiIndex<'a, String, AbcRegistration, String>,
+    pub reserve_denom: MultiIndex<'a, String, AbcRegistration, String>,
+    pub phase: MultiIndex<'a, String, AbcRegistration, String>,
+}
+
+impl<'a> IndexList<AbcRegistration> for AbcIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<AbcRegistration>> + '_> {
+        let v: Vec<&dyn Index<AbcRegistration>> = vec![
+            &self.creator,
+            &self.curve_type,
+            &self.reserve_denom,
+            &self.phase,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn abc_registrations<'a>() -> IndexedMap<'a, &'a str, AbcRegistration, AbcIndexes<'a>> {
+    let indexes = AbcIndexes {
+        creator: MultiIndex::new(
+            |_pk: &[u8], d: &AbcRegistration| d.creator.to_string(),
+            "abc_registrations",
+            "abc_registrations__creator",
+        ),
+        curve_type: MultiIndex::new(
+            |_pk: &[u8], d: &AbcRegistration| curve_type_label(&d.curve_type).to_string(),
+            "abc_registrations",
+            "abc_registrations__curve_type",
+        ),
+        reserve_denom: MultiIndex::new(
+            |_pk: &[u8], d: &AbcRegistration| d.reserve_denom.clone(),
+            "abc_registrations",
+            "abc_registrations__reserve_denom",
+        ),
+        phase: MultiIndex::new(
+            |_pk: &[u8], d: &AbcRegistration| d.phase.clone(),
+            "abc_registrations",
+            "abc_registrations__phase",
+        ),
+    };
+    IndexedMap::new("abc_registrations", indexes)
+}
+
+/// A stable, human-readable discriminant for `CurveType`, used to index and
+/// query registrations by curve shape without needing to match on the full
+/// (parameterized) variant.
+pub fn curve_type_label(curve_type: &CurveType) -> &'static str {
+    match curve_type {
+        CurveType::Constant { .. } => "constant",
+        CurveType::Linear { .. } => "linear",
+        CurveType::SquareRoot { .. } => "square_root",
+    }
+}
+
+/// A stable, human-readable discriminant for `Phase`, used the same way as
+/// [`curve_type_label`].
+pub fn phase_label(phase: &Phase) -> &'static str {
+    match phase {
+        Phase::Hatch => "hatch",
+        Phase::Open => "open",
+        Phase::Closed => "closed",
+    }
+}
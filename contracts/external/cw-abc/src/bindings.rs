@@ -0,0 +1,12 @@
+//! Token-factory Cosmos bindings, in one place so the rest of the
+//! contract never imports a bindings crate directly.
+//!
+//! The `osmosis-tokenfactory` and `kujira-tokenfactory` cargo features
+//! pull in each chain's native bindings crate but, for now, still
+//! delegate to the same `token-bindings` message shapes re-exported
+//! below: the token-factory module's core operations
+//! (create/mint/burn/change-admin/set-metadata/before-send-hook/
+//! force-transfer) line up closely enough across chains to share this
+//! contract's business logic. Giving Osmosis and Kujira their own
+//! `CosmosMsg::Custom` payloads is tracked as follow-up work.
+pub use token_bindings::{DenomUnit, Metadata, TokenFactoryMsg, TokenMsg};
@@ -0,0 +1,178 @@
+//! Typed convenience methods for building this contract's messages, so
+//! scripts don't have to hand-construct `ExecuteMsg`/`QueryMsg` and
+//! `Coin`s for every call.
+//!
+//! This workspace has no scripting/deployment client (e.g. an
+//! `cw-orchestrator`-style "boot" environment) to actually broadcast
+//! these against a live chain, so [`CwAbc`] only builds ready-to-send
+//! `CosmosMsg`s; wiring them up to a real sender is left to whatever
+//! client the caller already has. Its query methods take a
+//! [`QuerierWrapper`] and decode straight to the typed response (e.g.
+//! [`CurveInfoResponse`]), the same way a `cw-orch` `#[derive(QueryFns)]`
+//! method would, rather than handing back an undecoded [`QueryMsg`] for
+//! the caller to run and parse itself.
+//!
+//! This module intentionally does *not* implement cw-orch's
+//! `#[interface]`/`Uploadable` derives: this workspace has no `cw-orch`
+//! (nor its predecessor `boot-core`) dependency anywhere, deploys via
+//! [`cosm-orc`](../../../ci/deploy-abc) instead, and this sandbox has no
+//! network access to add a new crates.io dependency. The `boot` Cargo
+//! feature on this crate is kept as a no-op alias so a downstream
+//! script gated on `features = ["boot"]` keeps compiling; it does not
+//! change anything this module exports. Each payable execute method
+//! below takes an explicit `funds` (or amount/denom) parameter, the
+//! same distinction a real `#[payable]`-annotated `ExecuteFns` method
+//! would draw against the non-payable ones, which take none.
+use cosmwasm_std::{
+    coin, to_binary, Addr, Coin, CosmosMsg, QuerierWrapper, StdResult, Uint128, WasmMsg,
+};
+
+use cw_curves::Quote;
+
+use crate::msg::{CurveInfoResponse, ExecuteMsg, PhaseConfigResponse, QueryMsg};
+use crate::state::Phase;
+
+/// A cw-abc commons at `contract`.
+pub struct CwAbc {
+    pub contract: Addr,
+}
+
+impl CwAbc {
+    pub fn new(contract: Addr) -> Self {
+        CwAbc { contract }
+    }
+
+    /// A `Buy` sending `funds` (the reserve coin, in native reserve mode).
+    /// Payable: `funds` is required to carry the payment.
+    pub fn buy(&self, funds: &[Coin]) -> StdResult<CosmosMsg> {
+        self.execute(
+            &ExecuteMsg::Buy {
+                stake: None,
+                referrer: None,
+                ibc_forward: None,
+                idempotency_key: None,
+                permit: None,
+            },
+            funds,
+        )
+    }
+
+    /// An `IbcHooksBuy` sending `funds` (the reserve coin) on behalf of
+    /// `receiver`. Payable, same as [`Self::buy`].
+    pub fn ibc_hooks_buy(&self, receiver: &str, funds: &[Coin]) -> StdResult<CosmosMsg> {
+        self.execute(
+            &ExecuteMsg::IbcHooksBuy {
+                receiver: receiver.to_string(),
+                stake: None,
+                referrer: None,
+            },
+            funds,
+        )
+    }
+
+    /// A `ZapBuy` sending `funds` (whatever denom the configured router
+    /// accepts) requesting at least `min_reserve_out` of the reserve
+    /// denom back before buying with it. Payable.
+    pub fn zap_buy(&self, min_reserve_out: Uint128, funds: &[Coin]) -> StdResult<CosmosMsg> {
+        self.execute(&ExecuteMsg::ZapBuy { min_reserve_out }, funds)
+    }
+
+    /// A `Sell` of `amount` of `supply_denom`, attached as payment (native
+    /// supply mode only; a cw20 supply sells via `Receive` on the cw20
+    /// contract instead, which this doesn't build). Payable: the supply
+    /// amount to sell is attached as funds, not passed as a message field.
+    pub fn sell(&self, supply_denom: &str, amount: Uint128) -> StdResult<CosmosMsg> {
+        self.execute(&ExecuteMsg::Sell {}, &[coin(amount.u128(), supply_denom)])
+    }
+
+    /// A `ZapSell` of `amount` of `supply_denom`, attached as payment,
+    /// routing the released reserve into `output_denom` and requiring at
+    /// least `min_output` back. Payable, same as [`Self::sell`].
+    pub fn zap_sell(
+        &self,
+        supply_denom: &str,
+        amount: Uint128,
+        output_denom: &str,
+        min_output: Uint128,
+    ) -> StdResult<CosmosMsg> {
+        self.execute(
+            &ExecuteMsg::ZapSell { output_denom: output_denom.to_string(), min_output },
+            &[coin(amount.u128(), supply_denom)],
+        )
+    }
+
+    /// A `Grant` authorizing `operator` to spend the caller's pre-deposited
+    /// balance. Not payable: takes no funds.
+    pub fn grant(
+        &self,
+        operator: &str,
+        sell_limit: Option<Uint128>,
+        buy_limit: Option<Uint128>,
+    ) -> StdResult<CosmosMsg> {
+        self.execute(
+            &ExecuteMsg::Grant {
+                operator: operator.to_string(),
+                sell_limit,
+                buy_limit,
+                expires_at: None,
+            },
+            &[],
+        )
+    }
+
+    fn execute(&self, msg: &ExecuteMsg, funds: &[Coin]) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.contract.to_string(),
+            msg: to_binary(msg)?,
+            funds: funds.to_vec(),
+        }
+        .into())
+    }
+
+    /// The `CurveInfo` query, decoded straight to [`CurveInfoResponse`].
+    pub fn curve_info(&self, querier: &QuerierWrapper) -> StdResult<CurveInfoResponse> {
+        querier.query_wasm_smart(&self.contract, &QueryMsg::CurveInfo {})
+    }
+
+    /// The `PhaseConfig` query, decoded straight to [`PhaseConfigResponse`],
+    /// whose `phase` field a caller's polling loop should compare against
+    /// [`wait_for_phase`].
+    pub fn phase_config(&self, querier: &QuerierWrapper) -> StdResult<PhaseConfigResponse> {
+        querier.query_wasm_smart(&self.contract, &QueryMsg::PhaseConfig {})
+    }
+
+    /// The current owner, decoded straight to a `cw_ownable::Ownership`.
+    pub fn ownership(&self, querier: &QuerierWrapper) -> StdResult<cw_ownable::Ownership<Addr>> {
+        querier.query_wasm_smart(&self.contract, &QueryMsg::Ownership {})
+    }
+
+    /// The `SimulateBuy` query for a buy of `net_payment` reserve tokens,
+    /// decoded straight to a [`Quote`], so a caller can preview a buy's
+    /// minted amount before sending it.
+    pub fn buy_quote(&self, querier: &QuerierWrapper, net_payment: Uint128) -> StdResult<Quote> {
+        querier.query_wasm_smart(&self.contract, &QueryMsg::SimulateBuy { net_payment })
+    }
+
+    /// The `SimulateSell` query for a sell of `amount` of the supply token,
+    /// decoded straight to a [`Quote`], so a caller can preview a sell's
+    /// released reserve before sending it.
+    pub fn sell_quote(&self, querier: &QuerierWrapper, amount: Uint128) -> StdResult<Quote> {
+        querier.query_wasm_smart(&self.contract, &QueryMsg::SimulateSell { amount })
+    }
+}
+
+/// Whether a `PhaseConfig` query's `phase` has reached (or passed)
+/// `target`, for a caller polling this contract until it does. Phases
+/// only move forward (`Hatch` -> `Open` -> `Closed`), so this also
+/// catches the commons skipping past `target` entirely.
+pub fn wait_for_phase(current: &Phase, target: &Phase) -> bool {
+    phase_rank(current) >= phase_rank(target)
+}
+
+fn phase_rank(phase: &Phase) -> u8 {
+    match phase {
+        Phase::Hatch => 0,
+        Phase::Open => 1,
+        Phase::Closed => 2,
+    }
+}
@@ -0,0 +1,6457 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Api, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, DistributionMsg,
+    Env, Event, IbcMsg, MessageInfo, Reply, Response, StakingMsg, StdError, StdResult, Timestamp,
+    Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+use cw_ownable::{assert_owner, initialize_owner, is_owner, update_ownership};
+use cw_paginate::paginate_map_values;
+use cw_utils::parse_reply_instantiate_data;
+use sha2::{Digest, Sha256};
+
+use crate::bindings::{DenomUnit, Metadata, TokenFactoryMsg, TokenMsg};
+use crate::curves::{quote_buy, quote_sell, Curve, DecimalPlaces};
+use crate::error::ContractError;
+use crate::ibc;
+use crate::msg::{
+    BackstopConfigMsg, BuyPermit, CandleResponse, CurveInfoResponse, DexPoolExecuteMsg,
+    DexRouterExecuteMsg, DiversificationConfigMsg, ExchangeRateQueryMsg, ExchangeRateResponse,
+    ExecuteMsg, HolderDiscountConfigMsg, IbcForwardMsg, IbcLifecycleComplete, InstantiateMsg,
+    LiquiditySeedConfigMsg,
+    LockupConfigMsg, MigrateMsg, OracleQueryMsg, PermitAuthorizerConfigMsg, PermitPayload,
+    PhaseConfigResponse,
+    PolytoneProxyConfigMsg, PriceOracleConfigMsg, PriceOracleExecuteMsg, PriceResponse,
+    ProposalSellLimitResponse, QueryMsg, ReceiveMsg, ReferralConfigMsg,
+    ReserveExchangeRateSourceMsg, ReserveStakingConfigMsg, ReserveTokenMode,
+    ReserveVaultConfigMsg, StakeExecuteMsg, StakingConfigMsg, SudoMsg, SupplyTokenMode,
+    EmergencyCloseConfigMsg, EmergencyCloseStatusResponse,
+    MaintenanceOperatorMsg, RecoveryConfigMsg, TimelockConfigMsg, TreasuryConfigMsg,
+    VaultAdapterExecuteMsg, VaultAdapterQueryMsg, VaultBalanceResponse, VetoConfigMsg,
+    VolumeResponse, ZapConfigMsg,
+};
+use crate::state::{
+    AccountStats, BackstopConfig, BlockTrade, BuyPool, CurveCheckpoint, CurveState, DailyCandle,
+    DiversificationConfig, BatchAuctionState, HolderDiscountConfig, IdempotencyRecord, LegacyCurve,
+    LimitOrder,
+    LimitOrderSide, LiquiditySeedConfig, Lockup, LockupConfig, OperatorDeposit, OperatorGrant, Role,
+    PendingDiversificationSwap,
+    PendingZapBuy, PendingZapSell, PermitAuthorizerConfig, Phase, PhaseTransition,
+    PolytoneProxyConfig, PriceAccumulator, RecurringOrder,
+    PriceOracleConfig, ReferralConfig, ReserveExchangeRateConfig, ReserveStakingConfig,
+    ReserveStakingState, ReserveToken, ReserveTokenBacking, ReserveVaultConfig, SecondaryReserve,
+    StakingConfig, StreamingBuy, SupplyToken, SupplyTokenBacking, TradeRecord, TreasuryConfig,
+    MaintenanceOperator, PendingTimelock, TimelockConfig, TimelockedAction,
+    VolumeBucket,
+    ZapConfig, ABC_HOOKS, ACCOUNT_STATS,
+    ACTIVE_PROPOSALS, AUX_MINTER_ALLOWANCES, BACKSTOP_BALANCE, BACKSTOP_CONFIG,
+    BEFORE_SEND_HOOK, CANDLES_BY_DAY,
+    CLAWBACK_PERMANENTLY_DISABLED, CLAWBACK_ROLE, CLOSE_PROPOSAL_MODULE,
+    CUMULATIVE_PRICE_SNAPSHOT, CURVE_CHECKPOINTS, CURVE_STATE, CURVE_TYPE, DAO, DENOM_ADMIN,
+    DIVERSIFICATION_CONFIG, DIVERSIFIED_BALANCE, FIRST_ACQUIRED, FROZEN, HARVESTED_TOTAL, HATCHERS,
+    BATCH_AUCTION_STATE, BATCH_CONTRIBUTIONS, BLOCK_TRADES, BLOCK_TRADE_COUNT, BUY_POOLS,
+    BUY_POOL_CONTRIBUTIONS, BUY_POOL_COUNT,
+    HOLDER_DISCOUNT_CONFIG,
+    IDEMPOTENCY_KEYS, IDEMPOTENCY_KEY_TTL_SECONDS,
+    LEGACY_CURVE, LIMIT_ORDERS, LIMIT_ORDER_COUNT, LIQUIDITY_SEED_CONFIG, LOCKUPS, LOCKUP_CONFIG,
+    LOCKUP_COUNT, MIRROR_CHANNELS,
+    OPERATOR_DEPOSITS, OPERATOR_GRANTS,
+    PENDING_DIVERSIFICATION,
+    PENDING_DIVERSIFICATION_SWAP,
+    PENDING_REWARD_WITHDRAWAL_BALANCE,
+    PENDING_SUPPLY_DECIMALS, PENDING_ZAP_BUY, PENDING_ZAP_SELL, PERMIT_AUTHORIZER_CONFIG, PHASE,
+    PHASE_CONFIG, RECURRING_ORDERS,
+    PHASE_TRANSITIONS, PHASE_TRANSITION_COUNT,
+    POLYTONE_PROXY_CONFIG, PRICE_ACCUMULATOR, PRICE_ORACLE_CONFIG, PROPOSAL_SELL_LIMIT,
+    REFERRAL_CONFIG, REFERRAL_EARNED, RESERVE, RESERVE_IBC_TRACE, ROLES,
+    RESERVE_EXCHANGE_RATE_CONFIG, RESERVE_HEALTH_THRESHOLD, RESERVE_MODE, RESERVE_SNAPSHOT,
+    RESERVE_STAKING_CONFIG, RESERVE_STAKING_STATE, RESERVE_VAULT_CONFIG, RESERVE_VAULT_DEPOSITED,
+    SECONDARY_RESERVES, STAKING_CONFIG, STREAMING_BUYS, SUPPLY_DENOM, SUPPLY_MODE, TRADING_PAUSED,
+    SUPPLY_SNAPSHOT, TRADES, TRADE_COUNT, TREASURY_CONFIG, VOLUME_BY_DAY, ZAP_CONFIG,
+    PENDING_TIMELOCKS, TIMELOCK_CONFIG, TIMELOCK_COUNT,
+    MAINTENANCE_OPERATOR, OPERATOR_PERM_UPDATE_DENOM_METADATA, OPERATOR_PERM_UPDATE_PHASE,
+    VetoConfig, VETO_CONFIG, LAST_FEE_UPDATE,
+    EmergencyCloseConfig, EMERGENCY_CLOSE_CONFIG, EMERGENCY_CLOSE_ROUND,
+    EMERGENCY_CLOSE_SIGNALS, EMERGENCY_CLOSE_TOTAL, EMERGENCY_CLOSE_WINDOW_START,
+    RecoveryConfig, RecoveryProposal, PENDING_RECOVERY, RECOVERY_CONFIG,
+    ParamChange, PARAM_CHANGES, PARAM_CHANGE_COUNT,
+};
+
+pub(crate) const CONTRACT_NAME: &str = "crates.io:cw-abc";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const INSTANTIATE_CW20_SUPPLY_REPLY_ID: u64 = 1;
+const WITHDRAW_RESERVE_REWARDS_REPLY_ID: u64 = 2;
+const DIVERSIFICATION_SWAP_REPLY_ID: u64 = 3;
+const ZAP_BUY_REPLY_ID: u64 = 4;
+const ZAP_SELL_REPLY_ID: u64 = 5;
+
+/// Minimum seconds between successive [`execute_update_fees`] calls,
+/// direct or timelocked, so a compromised [`Role::FeeAdmin`] or owner
+/// can't ratchet fees up over several back-to-back transactions faster
+/// than holders can react.
+const MIN_FEE_UPDATE_INTERVAL_SECONDS: u64 = 86_400;
+
+/// Hard ceiling on any single fee field
+/// [`crate::msg::ExecuteMsg::UpdateFees`] can set, regardless of role or
+/// ownership -- protects against a single rug-via-parameters
+/// transaction even from a fully compromised owner key. This fork has
+/// no `UpdateCurve`-equivalent entry point (the bonding curve's shape
+/// is fixed at instantiation and never mutated), so there's no slope
+/// parameter to bound alongside it.
+fn max_fee_rate() -> Decimal {
+    Decimal::percent(20)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    initialize_owner(deps.storage, deps.api, msg.owner.as_deref())?;
+
+    let dao = match msg.dao {
+        Some(dao) => deps.api.addr_validate(&dao)?,
+        None => info.sender.clone(),
+    };
+    DAO.save(deps.storage, &dao)?;
+
+    let (reserve_denom, reserve_mode) = match msg.reserve_token_mode {
+        ReserveTokenMode::Native { denom } => (denom, ReserveTokenBacking::Native),
+        ReserveTokenMode::Cw20 { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            (address.to_string(), ReserveTokenBacking::Cw20 { address })
+        }
+    };
+    RESERVE.save(
+        deps.storage,
+        &ReserveToken {
+            denom: reserve_denom,
+            decimals: msg.reserve_decimals,
+        },
+    )?;
+    RESERVE_MODE.save(deps.storage, &reserve_mode)?;
+
+    let ibc_trace = match ibc::ibc_hash(&RESERVE.load(deps.storage)?.denom) {
+        Ok(hash) => Some(ibc::query_denom_trace(deps.as_ref(), hash)?),
+        Err(_) => None,
+    };
+    RESERVE_IBC_TRACE.save(deps.storage, &ibc_trace)?;
+
+    let decimals = DecimalPlaces::new(msg.supply_decimals, msg.reserve_decimals);
+    CURVE_TYPE.save(deps.storage, &msg.curve_type)?;
+    let mut curve_state = CurveState::new(decimals);
+    if msg.import_legacy_curve.is_some() && msg.initial_curve_state.is_some() {
+        return Err(ContractError::ConflictingCurveImport {});
+    }
+    if let Some(import) = &msg.import_legacy_curve {
+        curve_state.reserve = import.reserve_snapshot;
+        curve_state.supply = import.supply_snapshot;
+        LEGACY_CURVE.save(
+            deps.storage,
+            &LegacyCurve {
+                legacy_cw20: deps.api.addr_validate(&import.legacy_cw20)?,
+                claimable_supply: import.supply_snapshot,
+            },
+        )?;
+    }
+    if let Some(initial) = &msg.initial_curve_state {
+        if !matches!(reserve_mode, ReserveTokenBacking::Native) {
+            return Err(ContractError::InitialCurveStateRequiresNativeReserve {});
+        }
+        let expected = msg.curve_type.to_curve_fn()(decimals).reserve(initial.initial_supply);
+        if initial.initial_reserve != expected {
+            return Err(ContractError::InitialCurveStateMismatch {
+                initial_reserve: initial.initial_reserve,
+                expected,
+            });
+        }
+        let received = cw_utils::must_pay(&info, &RESERVE.load(deps.storage)?.denom)?;
+        if received != initial.initial_reserve {
+            return Err(ContractError::IncorrectInitialReserveDeposit {
+                expected: initial.initial_reserve,
+                received,
+            });
+        }
+        curve_state.reserve = initial.initial_reserve;
+        curve_state.supply = initial.initial_supply;
+    }
+    let total_allocated = msg
+        .allocations
+        .iter()
+        .try_fold(Uint128::zero(), |sum, allocation| sum.checked_add(allocation.amount))?;
+    if !total_allocated.is_zero() {
+        let new_supply = curve_state.supply.checked_add(total_allocated)?;
+        let required = msg.curve_type.to_curve_fn()(decimals).reserve(new_supply);
+        if required > curve_state.reserve {
+            return Err(ContractError::AllocationExceedsCurveReserve {
+                allocated: total_allocated,
+                new_supply,
+                required,
+                available: curve_state.reserve,
+            });
+        }
+        curve_state.supply = new_supply;
+    }
+    CURVE_STATE.save(deps.storage, &curve_state)?;
+    SUPPLY_SNAPSHOT.save(deps.storage, &curve_state.supply, env.block.height)?;
+    RESERVE_SNAPSHOT.save(deps.storage, &curve_state.reserve, env.block.height)?;
+
+    let curve = msg.curve_type.to_curve_fn()(curve_state.decimals);
+    let initial_spot_price = curve.spot_price(curve_state.supply);
+    PRICE_ACCUMULATOR.save(
+        deps.storage,
+        &PriceAccumulator {
+            cumulative_price: Decimal::zero(),
+            last_spot_price: initial_spot_price,
+            last_update_height: env.block.height,
+        },
+    )?;
+    CUMULATIVE_PRICE_SNAPSHOT.save(deps.storage, &Decimal::zero(), env.block.height)?;
+
+    PHASE.save(deps.storage, &Phase::Hatch)?;
+    PHASE_CONFIG.save(deps.storage, &msg.phase_config)?;
+    FROZEN.save(deps.storage, &false)?;
+    DENOM_ADMIN.save(deps.storage, &env.contract.address)?;
+    BEFORE_SEND_HOOK.save(deps.storage, &None)?;
+    CLAWBACK_ROLE.save(deps.storage, &None)?;
+    CLAWBACK_PERMANENTLY_DISABLED.save(deps.storage, &false)?;
+    TRADING_PAUSED.save(deps.storage, &false)?;
+
+    let mut response = Response::new().add_attribute("action", "instantiate").add_attribute("dao", dao);
+
+    match msg.supply_token_mode {
+        SupplyTokenMode::TokenFactory { subdenom } => {
+            validate_subdenom(&subdenom, &RESERVE.load(deps.storage)?.denom)?;
+            let denom = format!("factory/{}/{}", env.contract.address, subdenom);
+            SUPPLY_DENOM.save(
+                deps.storage,
+                &SupplyToken {
+                    denom: denom.clone(),
+                    subdenom: subdenom.clone(),
+                    decimals: msg.supply_decimals,
+                },
+            )?;
+            SUPPLY_MODE.save(deps.storage, &SupplyTokenBacking::TokenFactory)?;
+
+            let metadata =
+                build_denom_metadata(&denom, &subdenom, msg.supply_decimals, msg.denom_metadata)?;
+            let create_denom = CosmosMsg::Custom(TokenMsg::CreateDenom {
+                subdenom,
+                metadata: Some(metadata),
+            });
+            response = response
+                .add_attribute("supply_denom", denom.clone())
+                // Generic `denom` attribute, so tooling that instantiates
+                // this contract as a dao-core module (which doesn't know
+                // about `supply_denom`) can still discover the created
+                // denom from the instantiate event.
+                .add_attribute("denom", denom.clone())
+                .add_message(create_denom);
+            for allocation in &msg.allocations {
+                response = response.add_message(CosmosMsg::Custom(TokenMsg::MintTokens {
+                    denom: denom.clone(),
+                    amount: allocation.amount,
+                    mint_to_address: allocation.address.clone(),
+                }));
+            }
+        }
+        SupplyTokenMode::Cw20 { code_id, label } => {
+            PENDING_SUPPLY_DECIMALS.save(deps.storage, &msg.supply_decimals)?;
+
+            let initial_balances = msg
+                .allocations
+                .iter()
+                .map(|allocation| cw20::Cw20Coin {
+                    address: allocation.address.clone(),
+                    amount: allocation.amount,
+                })
+                .collect();
+            let instantiate_cw20 = WasmMsg::Instantiate {
+                admin: Some(info.sender.to_string()),
+                code_id,
+                msg: to_binary(&cw20_base::msg::InstantiateMsg {
+                    name: label.clone(),
+                    symbol: label.chars().take(12).collect::<String>().to_ascii_uppercase(),
+                    decimals: msg.supply_decimals,
+                    initial_balances,
+                    mint: Some(cw20::MinterResponse {
+                        minter: env.contract.address.to_string(),
+                        cap: None,
+                    }),
+                    marketing: None,
+                })?,
+                funds: vec![],
+                label,
+            };
+            response = response.add_submessage(cosmwasm_std::SubMsg::reply_on_success(
+                instantiate_cw20,
+                INSTANTIATE_CW20_SUPPLY_REPLY_ID,
+            ));
+        }
+    }
+
+    Ok(response)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    match msg.id {
+        INSTANTIATE_CW20_SUPPLY_REPLY_ID => {
+            let res =
+                parse_reply_instantiate_data(msg).map_err(|_| ContractError::Cw20InstantiateError {})?;
+            let address = deps.api.addr_validate(&res.contract_address)?;
+            let decimals = PENDING_SUPPLY_DECIMALS.load(deps.storage)?;
+            PENDING_SUPPLY_DECIMALS.remove(deps.storage);
+
+            SUPPLY_DENOM.save(
+                deps.storage,
+                &SupplyToken {
+                    denom: address.to_string(),
+                    subdenom: String::new(),
+                    decimals,
+                },
+            )?;
+            SUPPLY_MODE.save(
+                deps.storage,
+                &SupplyTokenBacking::Cw20 {
+                    address: address.clone(),
+                },
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "instantiate_cw20_supply")
+                .add_attribute("supply_token", address.clone())
+                .add_attribute("denom", address))
+        }
+        WITHDRAW_RESERVE_REWARDS_REPLY_ID => {
+            let reserve = RESERVE.load(deps.storage)?;
+            let balance_before = PENDING_REWARD_WITHDRAWAL_BALANCE.load(deps.storage)?;
+            PENDING_REWARD_WITHDRAWAL_BALANCE.remove(deps.storage);
+            let balance_after =
+                deps.querier.query_balance(&env.contract.address, reserve.denom)?.amount;
+            let reward = balance_after.saturating_sub(balance_before);
+
+            let mut curve_state = CURVE_STATE.load(deps.storage)?;
+            curve_state.funding = curve_state.funding.checked_add(reward)?;
+            CURVE_STATE.save(deps.storage, &curve_state)?;
+            let harvested = HARVESTED_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+            HARVESTED_TOTAL.save(deps.storage, &harvested.checked_add(reward)?)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "withdraw_reserve_rewards_reply")
+                .add_attribute("reward", reward)
+                .add_event(
+                    Event::new("abc-harvest")
+                        .add_attribute("source", "staking")
+                        .add_attribute("amount", reward),
+                ))
+        }
+        DIVERSIFICATION_SWAP_REPLY_ID => {
+            let pending = PENDING_DIVERSIFICATION_SWAP.load(deps.storage)?;
+            PENDING_DIVERSIFICATION_SWAP.remove(deps.storage);
+            let balance_after = deps
+                .querier
+                .query_balance(&env.contract.address, pending.denom.clone())?
+                .amount;
+            let received = balance_after.saturating_sub(pending.balance_before);
+
+            let diversified = DIVERSIFIED_BALANCE.may_load(deps.storage)?.unwrap_or_default();
+            DIVERSIFIED_BALANCE.save(deps.storage, &diversified.checked_add(received)?)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "diversification_swap_reply")
+                .add_attribute("received", received)
+                .add_event(
+                    Event::new("abc-diversification-swap")
+                        .add_attribute("denom", pending.denom)
+                        .add_attribute("amount", received),
+                ))
+        }
+        ZAP_BUY_REPLY_ID => {
+            let pending = PENDING_ZAP_BUY.load(deps.storage)?;
+            PENDING_ZAP_BUY.remove(deps.storage);
+            let reserve = RESERVE.load(deps.storage)?;
+            let balance_after =
+                deps.querier.query_balance(&env.contract.address, reserve.denom.clone())?.amount;
+            let received = balance_after.saturating_sub(pending.reserve_balance_before);
+            if received < pending.min_reserve_out {
+                return Err(ContractError::ZapSlippage { received, min: pending.min_reserve_out });
+            }
+            let buy_info = MessageInfo {
+                sender: pending.buyer.clone(),
+                funds: vec![cosmwasm_std::coin(received.u128(), reserve.denom)],
+            };
+            let response =
+                buy_impl(deps, env, buy_info, pending.buyer, None, None, None, None, None)?;
+            Ok(response.add_attribute("zap_swapped_in", received))
+        }
+        ZAP_SELL_REPLY_ID => {
+            let pending = PENDING_ZAP_SELL.load(deps.storage)?;
+            PENDING_ZAP_SELL.remove(deps.storage);
+            match msg.result {
+                cosmwasm_std::SubMsgResult::Ok(_) => {
+                    let balance_after = deps
+                        .querier
+                        .query_balance(&env.contract.address, pending.output_denom.clone())?
+                        .amount;
+                    let received = balance_after.saturating_sub(pending.output_balance_before);
+                    if received < pending.min_output {
+                        return Err(ContractError::ZapSlippage {
+                            received,
+                            min: pending.min_output,
+                        });
+                    }
+                    Ok(Response::new()
+                        .add_attribute("action", "zap_sell_reply")
+                        .add_attribute("swapped_out", received)
+                        .add_message(BankMsg::Send {
+                            to_address: pending.seller.to_string(),
+                            amount: vec![cosmwasm_std::coin(received.u128(), pending.output_denom)],
+                        }))
+                }
+                cosmwasm_std::SubMsgResult::Err(err) => {
+                    let reserve = RESERVE.load(deps.storage)?;
+                    let refund_msg = reserve_payout_msg(
+                        deps.storage,
+                        &reserve,
+                        pending.seller.to_string(),
+                        pending.payout,
+                    )?;
+                    Ok(Response::new()
+                        .add_attribute("action", "zap_sell_reply")
+                        .add_attribute("swap_failed", err)
+                        .add_attribute("refunded", pending.payout)
+                        .add_message(refund_msg))
+                }
+            }
+        }
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    match msg {
+        ExecuteMsg::Buy { stake, referrer, ibc_forward, idempotency_key, permit } => {
+            execute_buy(deps, env, info, stake, referrer, ibc_forward, idempotency_key, permit)
+        }
+        ExecuteMsg::IbcHooksBuy { receiver, stake, referrer } => {
+            execute_ibc_hooks_buy(deps, env, info, receiver, stake, referrer)
+        }
+        ExecuteMsg::IbcHooksBuyRaw { memo } => execute_ibc_hooks_buy_raw(deps, env, info, memo),
+        ExecuteMsg::GmpBuy { evm_sender, receiver, stake, referrer } => {
+            execute_gmp_buy(deps, env, info, evm_sender, receiver, stake, referrer)
+        }
+        ExecuteMsg::Sell {} => execute_sell(deps, env, info),
+        ExecuteMsg::UpdatePhaseConfig { phase_config } => {
+            execute_update_phase_config(deps, env, info, phase_config)
+        }
+        ExecuteMsg::SweepUnrelatedFunds { denom, recipient } => {
+            execute_sweep_unrelated_funds(deps, env, info, denom, recipient)
+        }
+        ExecuteMsg::Freeze {} => execute_freeze(deps, env, info),
+        ExecuteMsg::UpdateDenomAdmin { new_admin } => {
+            execute_update_denom_admin(deps, info, new_admin)
+        }
+        ExecuteMsg::UpdateDenomMetadata { metadata } => {
+            execute_update_denom_metadata(deps, info, metadata)
+        }
+        ExecuteMsg::SetBeforeSendHook { contract_addr } => {
+            execute_set_before_send_hook(deps, info, contract_addr)
+        }
+        ExecuteMsg::UpdatePhase { new_phase } => execute_update_phase(deps, env, info, new_phase),
+        ExecuteMsg::SetClawbackRole { address } => execute_set_clawback_role(deps, info, address),
+        ExecuteMsg::DisableClawbackPermanently {} => {
+            execute_disable_clawback_permanently(deps, info)
+        }
+        ExecuteMsg::ForceTransfer { from, to, amount } => {
+            execute_force_transfer(deps, info, from, to, amount)
+        }
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
+        ExecuteMsg::Receive(receive_msg) => execute_receive(deps, env, info, receive_msg),
+        ExecuteMsg::UpdateSecondaryReserve { denom, oracle } => {
+            execute_update_secondary_reserve(deps, info, denom, oracle)
+        }
+        ExecuteMsg::SetAuxMinterAllowance { minter, allowance } => {
+            execute_set_aux_minter_allowance(deps, info, minter, allowance)
+        }
+        ExecuteMsg::AuxMint { recipient, amount } => {
+            execute_aux_mint(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::AddHook { address } => execute_add_hook(deps, info, address),
+        ExecuteMsg::RemoveHook { address } => execute_remove_hook(deps, info, address),
+        ExecuteMsg::SetCloseProposalModule { module } => {
+            execute_set_close_proposal_module(deps, info, module)
+        }
+        ExecuteMsg::SetProposalSellLimit { limit } => {
+            execute_set_proposal_sell_limit(deps, info, limit)
+        }
+        ExecuteMsg::ProposalHook(hook_msg) => execute_proposal_hook(deps, info, hook_msg),
+        ExecuteMsg::SetTreasuryConfig { config } => execute_set_treasury_config(deps, info, config),
+        ExecuteMsg::SetStakingConfig { config } => execute_set_staking_config(deps, info, config),
+        ExecuteMsg::SetPriceOracle { config } => execute_set_price_oracle(deps, info, config),
+        ExecuteMsg::SpendFundingPool { amount, msgs } => {
+            execute_spend_funding_pool(deps, info, amount, msgs)
+        }
+        ExecuteMsg::SetReserveStakingConfig { config } => {
+            execute_set_reserve_staking_config(deps, info, config)
+        }
+        ExecuteMsg::StakeReserve { amount } => execute_stake_reserve(deps, env, amount),
+        ExecuteMsg::UndelegateReserve { amount } => execute_undelegate_reserve(deps, amount),
+        ExecuteMsg::WithdrawReserveRewards {} => execute_withdraw_reserve_rewards(deps, env),
+        ExecuteMsg::SetReserveVaultConfig { config } => {
+            execute_set_reserve_vault_config(deps, info, config)
+        }
+        ExecuteMsg::DepositReserveToVault { amount } => {
+            execute_deposit_reserve_to_vault(deps, amount)
+        }
+        ExecuteMsg::WithdrawReserveFromVault { amount } => {
+            execute_withdraw_reserve_from_vault(deps, amount)
+        }
+        ExecuteMsg::Harvest {} => execute_harvest(deps, env),
+        ExecuteMsg::SetDiversificationConfig { config } => {
+            execute_set_diversification_config(deps, info, config)
+        }
+        ExecuteMsg::SetLiquiditySeedConfig { config } => {
+            execute_set_liquidity_seed_config(deps, info, config)
+        }
+        ExecuteMsg::SetReserveHealthThreshold { threshold } => {
+            execute_set_reserve_health_threshold(deps, info, threshold)
+        }
+        ExecuteMsg::SpendReserve { recipient, amount } => {
+            execute_spend_reserve(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::MigrateReserveDenom {
+            new_denom,
+            new_decimals,
+        } => execute_migrate_reserve_denom(deps, info, new_denom, new_decimals),
+        ExecuteMsg::SetReserveExchangeRateSource { config } => {
+            execute_set_reserve_exchange_rate_source(deps, info, config)
+        }
+        ExecuteMsg::SetBackstopConfig { config } => {
+            execute_set_backstop_config(deps, info, config)
+        }
+        ExecuteMsg::TopUpReserveFromBackstop { amount } => {
+            execute_top_up_reserve_from_backstop(deps, env, amount)
+        }
+        ExecuteMsg::SetReferralConfig { config } => {
+            execute_set_referral_config(deps, info, config)
+        }
+        ExecuteMsg::SetPolytoneProxyConfig { config } => {
+            execute_set_polytone_proxy_config(deps, info, config)
+        }
+        ExecuteMsg::SetZapConfig { config } => execute_set_zap_config(deps, info, config),
+        ExecuteMsg::SetPermitAuthorizerConfig { config } => {
+            execute_set_permit_authorizer_config(deps, info, config)
+        }
+        ExecuteMsg::ZapBuy { min_reserve_out } => {
+            execute_zap_buy(deps, env, info, min_reserve_out)
+        }
+        ExecuteMsg::ZapSell { output_denom, min_output } => {
+            execute_zap_sell(deps, env, info, output_denom, min_output)
+        }
+        ExecuteMsg::DepositForOperator {} => execute_deposit_for_operator(deps, info),
+        ExecuteMsg::WithdrawOperatorDeposit { denom, amount } => {
+            execute_withdraw_operator_deposit(deps, info, denom, amount)
+        }
+        ExecuteMsg::Grant { operator, sell_limit, buy_limit, expires_at } => {
+            execute_grant(deps, info, operator, sell_limit, buy_limit, expires_at)
+        }
+        ExecuteMsg::Revoke { operator } => execute_revoke(deps, info, operator),
+        ExecuteMsg::SellFor { owner, amount } => execute_sell_for(deps, env, info, owner, amount),
+        ExecuteMsg::BuyFor { owner, amount } => execute_buy_for(deps, env, info, owner, amount),
+        ExecuteMsg::SetRecurringPurchase { amount_per_interval, interval_seconds } => {
+            execute_set_recurring_purchase(deps, env, info, amount_per_interval, interval_seconds)
+        }
+        ExecuteMsg::DepositRecurringPurchase {} => execute_deposit_recurring_purchase(deps, info),
+        ExecuteMsg::WithdrawRecurringPurchase { amount } => {
+            execute_withdraw_recurring_purchase(deps, info, amount)
+        }
+        ExecuteMsg::CancelRecurringPurchase {} => execute_cancel_recurring_purchase(deps, info),
+        ExecuteMsg::CrankRecurringPurchases { limit } => {
+            execute_crank_recurring_purchases(deps, env, limit)
+        }
+        ExecuteMsg::PlaceLimitOrder { side, threshold_price, expires_at } => {
+            execute_place_limit_order(deps, info, side, threshold_price, expires_at)
+        }
+        ExecuteMsg::CancelLimitOrder { order_id } => {
+            execute_cancel_limit_order(deps, info, order_id)
+        }
+        ExecuteMsg::CrankLimitOrders { limit } => execute_crank_limit_orders(deps, env, limit),
+        ExecuteMsg::SettleBatchAuction { limit } => {
+            execute_settle_batch_auction(deps, env, limit)
+        }
+        ExecuteMsg::SetStreamingBuy { duration_seconds } => {
+            execute_set_streaming_buy(deps, env, info, duration_seconds)
+        }
+        ExecuteMsg::CancelStreamingBuy {} => execute_cancel_streaming_buy(deps, info),
+        ExecuteMsg::CrankStreamingBuys { limit } => execute_crank_streaming_buys(deps, env, limit),
+        ExecuteMsg::BuyAndDistribute { recipients } => {
+            execute_buy_and_distribute(deps, env, info, recipients)
+        }
+        ExecuteMsg::OpenBuyPool { target } => execute_open_buy_pool(deps, info, target),
+        ExecuteMsg::JoinBuyPool { pool_id } => execute_join_buy_pool(deps, info, pool_id),
+        ExecuteMsg::CancelBuyPoolContribution { pool_id } => {
+            execute_cancel_buy_pool_contribution(deps, info, pool_id)
+        }
+        ExecuteMsg::SettleBuyPool { pool_id, limit } => {
+            execute_settle_buy_pool(deps, env, pool_id, limit)
+        }
+        ExecuteMsg::CommitBlockTrade { side, delay_blocks } => {
+            execute_commit_block_trade(deps, env, info, side, delay_blocks)
+        }
+        ExecuteMsg::CancelBlockTrade { trade_id } => {
+            execute_cancel_block_trade(deps, info, trade_id)
+        }
+        ExecuteMsg::CrankBlockTrades { limit } => execute_crank_block_trades(deps, env, limit),
+        ExecuteMsg::SetLockupConfig { config } => execute_set_lockup_config(deps, info, config),
+        ExecuteMsg::BuyWithLockup { duration_seconds } => {
+            execute_buy_with_lockup(deps, env, info, duration_seconds)
+        }
+        ExecuteMsg::ClaimLockup { lockup_id } => execute_claim_lockup(deps, env, info, lockup_id),
+        ExecuteMsg::SetHolderDiscountConfig { config } => {
+            execute_set_holder_discount_config(deps, info, config)
+        }
+        ExecuteMsg::GrantRole { role, address } => execute_grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
+        ExecuteMsg::SetTradingPaused { paused } => {
+            execute_set_trading_paused(deps, env, info, paused)
+        }
+        ExecuteMsg::UpdateFees { hatch_entry_fee, open_entry_fee, open_exit_fee } => {
+            execute_update_fees(deps, env, info, hatch_entry_fee, open_entry_fee, open_exit_fee)
+        }
+        ExecuteMsg::UpdateHatchAllowlist { add, remove } => {
+            execute_update_hatch_allowlist(deps, info, add, remove)
+        }
+        ExecuteMsg::SetTimelockConfig { config } => {
+            execute_set_timelock_config(deps, env, info, config)
+        }
+        ExecuteMsg::QueueTimelockedAction { action } => {
+            execute_queue_timelocked_action(deps, env, info, action)
+        }
+        ExecuteMsg::ExecuteTimelockedAction { id } => execute_run_timelocked_action(deps, env, id),
+        ExecuteMsg::CancelTimelockedAction { id } => {
+            execute_cancel_timelocked_action(deps, info, id)
+        }
+        ExecuteMsg::SetMaintenanceOperator { operator } => {
+            execute_set_maintenance_operator(deps, env, info, operator)
+        }
+        ExecuteMsg::SetVetoAddress { config } => {
+            execute_set_veto_address(deps, env, info, config)
+        }
+        ExecuteMsg::VetoTimelockedAction { id, reason } => {
+            execute_veto_timelocked_action(deps, env, info, id, reason)
+        }
+        ExecuteMsg::SetEmergencyCloseConfig { config } => {
+            execute_set_emergency_close_config(deps, env, info, config)
+        }
+        ExecuteMsg::SignalEmergencyClose {} => execute_signal_emergency_close(deps, env, info),
+        ExecuteMsg::WithdrawEmergencyCloseSignal { round } => {
+            execute_withdraw_emergency_close_signal(deps, info, round)
+        }
+        ExecuteMsg::SetRecoveryGuardians { config } => {
+            execute_set_recovery_guardians(deps, env, info, config)
+        }
+        ExecuteMsg::ProposeRecovery { new_owner } => {
+            execute_propose_recovery(deps, env, info, new_owner)
+        }
+        ExecuteMsg::ApproveRecovery {} => execute_approve_recovery(deps, info),
+        ExecuteMsg::CancelRecovery {} => execute_cancel_recovery(deps, info),
+        ExecuteMsg::ExecuteRecovery {} => execute_run_recovery(deps, env),
+    }
+}
+
+/// Executes `msgs` (bank sends or wasm executes) and debits `amount` from
+/// [`CurveState::funding`], so a DAO owner can spend proposal-approved
+/// grants from the funding pool with proper accounting. Only bank and
+/// wasm messages are supported, since arbitrary custom messages can't be
+/// converted to this contract's [`TokenFactoryMsg`] response type.
+pub fn execute_spend_funding_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    msgs: Vec<CosmosMsg<cosmwasm_std::Empty>>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+
+    let mut curve_state = CURVE_STATE.load(deps.storage)?;
+    if amount > curve_state.funding {
+        return Err(ContractError::InsufficientFundingPool {
+            amount,
+            funding: curve_state.funding,
+        });
+    }
+    curve_state.funding = curve_state.funding.checked_sub(amount)?;
+    CURVE_STATE.save(deps.storage, &curve_state)?;
+
+    let msgs = msgs
+        .into_iter()
+        .map(convert_funding_pool_msg)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Response::new()
+        .add_attribute("action", "spend_funding_pool")
+        .add_attribute("amount", amount)
+        .add_messages(msgs))
+}
+
+/// Converts a proposal-supplied [`CosmosMsg<Empty>`] into this contract's
+/// [`TokenFactoryMsg`]-flavored response type. Bank and wasm messages
+/// carry no custom payload and convert directly; anything else is
+/// rejected.
+fn convert_funding_pool_msg(
+    msg: CosmosMsg<cosmwasm_std::Empty>,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    match msg {
+        CosmosMsg::Bank(bank_msg) => Ok(bank_msg.into()),
+        CosmosMsg::Wasm(wasm_msg) => Ok(wasm_msg.into()),
+        _ => Err(ContractError::UnsupportedFundingPoolMessage {}),
+    }
+}
+
+/// Inflates the stored [`crate::curves::CurveType`] into its boxed
+/// [`Curve`] implementation, at `decimals`. Every handler that needs to
+/// price against the curve goes through this one function rather than
+/// calling `to_curve_fn` itself, so a contract embedding this crate as a
+/// library and wanting a richer curve than the built-in
+/// constant/linear/square-root ones (stateful, oracle- or
+/// query-backed, ...) has a single choke point to override: swap this
+/// function's body for one that returns its own `Box<dyn Curve>` instead
+/// of reaching into [`CURVE_TYPE`].
+fn load_curve(
+    storage: &dyn cosmwasm_std::Storage,
+    decimals: DecimalPlaces,
+) -> StdResult<Box<dyn Curve>> {
+    let curve_type = CURVE_TYPE.load(storage)?;
+    Ok(curve_type.to_curve_fn()(decimals))
+}
+
+/// How much reserve the curve itself requires to back the current supply,
+/// per the configured [`crate::curves::Curve`]. In the absence of any
+/// reserve leaving the curve outside of `process_buy`/`process_sell` (see
+/// [`reserve_health_factor`]), this tracks [`CurveState::reserve`] exactly.
+fn curve_required_reserve(storage: &dyn cosmwasm_std::Storage) -> StdResult<Uint128> {
+    let curve_state = CURVE_STATE.load(storage)?;
+    let curve = load_curve(storage, curve_state.decimals)?;
+    Ok(curve.reserve(curve_state.supply))
+}
+
+/// The ratio of `reserve` to [`curve_required_reserve`], i.e. how well
+/// [`CurveState::reserve`] (or a hypothetical post-spend value of it)
+/// covers what the curve needs to back the current supply. Reports
+/// [`Decimal::one`] while the curve has no supply yet, since there's
+/// nothing to be under-collateralized against.
+fn reserve_health_factor(
+    storage: &dyn cosmwasm_std::Storage,
+    reserve: Uint128,
+) -> StdResult<Decimal> {
+    let required = curve_required_reserve(storage)?;
+    if required.is_zero() {
+        return Ok(Decimal::one());
+    }
+    Ok(Decimal::from_ratio(reserve, required))
+}
+
+/// Sets (or, with `threshold: None`, clears) the minimum ratio of
+/// `CurveState::reserve` to [`curve_required_reserve`] that
+/// [`execute_spend_reserve`] must leave behind.
+pub fn execute_set_reserve_health_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    threshold: Option<Decimal>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    RESERVE_HEALTH_THRESHOLD.save(deps.storage, &threshold)?;
+    Ok(Response::new().add_attribute("action", "set_reserve_health_threshold"))
+}
+
+/// Pays `amount` of the reserve directly to `recipient`, bypassing the
+/// curve entirely, as long as the resulting [`reserve_health_factor`]
+/// stays at or above [`RESERVE_HEALTH_THRESHOLD`]. Requires a threshold to
+/// be configured first, so the DAO has to explicitly opt into the risk of
+/// under-collateralizing the curve rather than this being available by
+/// default.
+pub fn execute_spend_reserve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let threshold = RESERVE_HEALTH_THRESHOLD
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::ReserveHealthThresholdNotConfigured {})?;
+
+    let mut curve_state = CURVE_STATE.load(deps.storage)?;
+    let new_reserve = curve_state.reserve.checked_sub(amount)?;
+    let effective_reserve = effective_reserve_value(deps.as_ref(), &env, new_reserve)?;
+    let ratio = reserve_health_factor(deps.storage, effective_reserve)?;
+    if ratio < threshold {
+        return Err(ContractError::ReserveHealthFactorTooLow {
+            amount,
+            ratio,
+            threshold,
+        });
+    }
+    curve_state.reserve = new_reserve;
+    CURVE_STATE.save(deps.storage, &curve_state)?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let msg = reserve_payout_msg(deps.storage, &reserve, recipient.clone(), amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "spend_reserve")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount)
+        .add_attribute("health_factor", ratio.to_string())
+        .add_message(msg))
+}
+
+/// Atomically swaps the reserve to a new native denom: the sender attaches
+/// exactly `CurveState::reserve` of `new_denom`, which becomes the reserve
+/// going forward, while the same amount of the old reserve denom is paid
+/// back to the sender in the same message. Only supported when the
+/// reserve is currently native, since a single message can only
+/// bank-attach one native deposit.
+pub fn execute_migrate_reserve_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_denom: String,
+    new_decimals: u8,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::ReserveMigrationRequiresNativeReserve {});
+    }
+    let old_reserve = RESERVE.load(deps.storage)?;
+    let curve_state = CURVE_STATE.load(deps.storage)?;
+
+    let received = cw_utils::must_pay(&info, &new_denom)?;
+    if received != curve_state.reserve {
+        return Err(ContractError::IncorrectReserveMigrationDeposit {
+            expected: curve_state.reserve,
+            received,
+        });
+    }
+
+    RESERVE.save(
+        deps.storage,
+        &ReserveToken {
+            denom: new_denom.clone(),
+            decimals: new_decimals,
+        },
+    )?;
+
+    let payout = BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: vec![cosmwasm_std::coin(curve_state.reserve.u128(), old_reserve.denom.clone())],
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate_reserve_denom")
+        .add_attribute("old_denom", old_reserve.denom)
+        .add_attribute("new_denom", new_denom)
+        .add_attribute("amount", curve_state.reserve)
+        .add_message(payout))
+}
+
+/// Sets (or, with `config: None`, clears) an exchange-rate source for a
+/// reserve that's a yield-bearing derivative rather than its plain
+/// underlying asset.
+pub fn execute_set_reserve_exchange_rate_source(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<ReserveExchangeRateSourceMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<ReserveExchangeRateConfig, ContractError> {
+            Ok(ReserveExchangeRateConfig {
+                oracle: deps.api.addr_validate(&config.oracle)?,
+                max_staleness: config.max_staleness,
+            })
+        })
+        .transpose()?;
+    RESERVE_EXCHANGE_RATE_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_reserve_exchange_rate_source"))
+}
+
+/// The current rate to convert a raw reserve-token amount into its
+/// underlying value, per [`RESERVE_EXCHANGE_RATE_CONFIG`]. Reports
+/// [`Decimal::one`] when no source is configured, i.e. the reserve token
+/// already is the underlying asset. Errors if the configured oracle's
+/// rate is older than its `max_staleness`.
+fn reserve_exchange_rate(deps: Deps, env: &Env) -> StdResult<Decimal> {
+    let Some(config) = RESERVE_EXCHANGE_RATE_CONFIG.may_load(deps.storage)?.flatten() else {
+        return Ok(Decimal::one());
+    };
+    let response: ExchangeRateResponse = deps
+        .querier
+        .query_wasm_smart(config.oracle, &ExchangeRateQueryMsg::ExchangeRate {})?;
+    let age = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(response.last_updated.seconds());
+    if age > config.max_staleness {
+        return Err(cosmwasm_std::StdError::generic_err(format!(
+            "reserve exchange rate is stale: last updated {} seconds ago, max staleness is {} seconds",
+            age, config.max_staleness
+        )));
+    }
+    Ok(response.rate)
+}
+
+/// The underlying value of a raw reserve-token amount, per
+/// [`reserve_exchange_rate`].
+fn effective_reserve_value(deps: Deps, env: &Env, raw_reserve: Uint128) -> StdResult<Uint128> {
+    Ok(raw_reserve * reserve_exchange_rate(deps, env)?)
+}
+
+/// Splits `fee` between [`BACKSTOP_BALANCE`] and `CurveState::funding`
+/// per [`BackstopConfig`], crediting the backstop share directly since
+/// it's tracked outside `CurveState`. Returns the remainder to credit to
+/// `CurveState::funding`; returns `fee` unchanged if no backstop is
+/// configured.
+fn accrue_backstop_fee(
+    storage: &mut dyn cosmwasm_std::Storage,
+    fee: Uint128,
+) -> Result<Uint128, ContractError> {
+    let Some(config) = BACKSTOP_CONFIG.may_load(storage)?.flatten() else {
+        return Ok(fee);
+    };
+    let share = fee * config.percent;
+    let balance = BACKSTOP_BALANCE.may_load(storage)?.unwrap_or_default();
+    BACKSTOP_BALANCE.save(storage, &balance.checked_add(share)?)?;
+    Ok(fee.checked_sub(share)?)
+}
+
+/// Sets (or, with `config: None`, clears) the share of entry/exit fees
+/// diverted into the backstop pool instead of `CurveState::funding`.
+pub fn execute_set_backstop_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<BackstopConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<BackstopConfig, ContractError> {
+            if config.percent.is_zero() || config.percent > Decimal::one() {
+                return Err(ContractError::InvalidBackstopPercent {});
+            }
+            Ok(BackstopConfig {
+                percent: config.percent,
+            })
+        })
+        .transpose()?;
+    BACKSTOP_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_backstop_config"))
+}
+
+/// Tops up `CurveState::reserve` from the backstop pool by `amount`,
+/// bypassing governance entirely, as long as the reserve is currently
+/// under-collateralized. Callable by anyone, since it can only ever
+/// improve solvency: it errors if the curve isn't currently in a
+/// shortfall, or if `amount` exceeds the backstop's balance.
+pub fn execute_top_up_reserve_from_backstop(
+    deps: DepsMut,
+    env: Env,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let mut curve_state = CURVE_STATE.load(deps.storage)?;
+    let effective_reserve = effective_reserve_value(deps.as_ref(), &env, curve_state.reserve)?;
+    let ratio = reserve_health_factor(deps.storage, effective_reserve)?;
+    if ratio >= Decimal::one() {
+        return Err(ContractError::NoReserveShortfall { ratio });
+    }
+
+    let balance = BACKSTOP_BALANCE.may_load(deps.storage)?.unwrap_or_default();
+    if amount > balance {
+        return Err(ContractError::InsufficientBackstopBalance { amount, balance });
+    }
+    BACKSTOP_BALANCE.save(deps.storage, &balance.checked_sub(amount)?)?;
+
+    curve_state.reserve = curve_state.reserve.checked_add(amount)?;
+    CURVE_STATE.save(deps.storage, &curve_state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "top_up_reserve_from_backstop")
+        .add_attribute("amount", amount)
+        .add_event(
+            Event::new("abc-backstop-top-up")
+                .add_attribute("amount", amount)
+                .add_attribute("prior_health_factor", ratio.to_string()),
+        ))
+}
+
+/// Sets (or, with `config: None`, clears) the cut of buy payments
+/// credited to the `referrer` named in [`ExecuteMsg::Buy`], if any.
+pub fn execute_set_referral_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<ReferralConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<ReferralConfig, ContractError> {
+            if config.percent.is_zero() || config.percent > Decimal::one() {
+                return Err(ContractError::InvalidReferralPercent {});
+            }
+            Ok(ReferralConfig {
+                percent: config.percent,
+            })
+        })
+        .transpose()?;
+    REFERRAL_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_referral_config"))
+}
+
+/// Sets (or, with `config: None`, clears) the Polytone proxy allowed to
+/// act as owner. See [`assert_owner_or_polytone_proxy`] for what that
+/// grants; gated the same way (an existing proxy may reconfigure or
+/// clear itself, same as the literal owner could).
+pub fn execute_set_polytone_proxy_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<PolytoneProxyConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<PolytoneProxyConfig, ContractError> {
+            Ok(PolytoneProxyConfig {
+                connection_id: config.connection_id,
+                proxy: deps.api.addr_validate(&config.proxy)?,
+            })
+        })
+        .transpose()?;
+    POLYTONE_PROXY_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_polytone_proxy_config"))
+}
+
+/// Sets (or, with `config: None`, clears) the DEX router
+/// [`ExecuteMsg::ZapBuy`] swaps into the reserve denom.
+pub fn execute_set_zap_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<ZapConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<ZapConfig, ContractError> {
+            Ok(ZapConfig { router: deps.api.addr_validate(&config.router)? })
+        })
+        .transpose()?;
+    ZAP_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_zap_config"))
+}
+
+/// Sets (or, with `config: None`, clears) the key trusted to sign
+/// [`ExecuteMsg::Buy`]'s `permit` vouchers. See [`verify_buy_permit`] for
+/// how a `permit` is checked against it.
+pub fn execute_set_permit_authorizer_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<PermitAuthorizerConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config.map(|config| PermitAuthorizerConfig { pubkey: config.pubkey });
+    PERMIT_AUTHORIZER_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_permit_authorizer_config"))
+}
+
+/// Swaps whatever denom is attached into the reserve denom via
+/// [`ZapConfig::router`], then runs an ordinary buy for `info.sender`
+/// with the proceeds once [`ZAP_BUY_REPLY_ID`]'s reply confirms the swap
+/// met `min_reserve_out`. Only supports a native reserve, same as
+/// [`buy_impl`], since the swap's output has to land as a bank balance
+/// this contract can diff before/after.
+pub fn execute_zap_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    min_reserve_out: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config =
+        ZAP_CONFIG.may_load(deps.storage)?.flatten().ok_or(ContractError::ZapNotConfigured {})?;
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let reserve = RESERVE.load(deps.storage)?;
+    let coin = cw_utils::one_coin(&info)?;
+    let reserve_balance_before =
+        deps.querier.query_balance(&env.contract.address, reserve.denom.clone())?.amount;
+    PENDING_ZAP_BUY.save(
+        deps.storage,
+        &PendingZapBuy { buyer: info.sender, reserve_balance_before, min_reserve_out },
+    )?;
+    let swap_msg = WasmMsg::Execute {
+        contract_addr: config.router.to_string(),
+        msg: to_binary(&DexRouterExecuteMsg::Swap { target_denom: reserve.denom })?,
+        funds: vec![coin],
+    };
+    Ok(Response::new()
+        .add_attribute("action", "zap_buy")
+        .add_submessage(cosmwasm_std::SubMsg::reply_on_success(swap_msg, ZAP_BUY_REPLY_ID)))
+}
+
+/// Sets (or, with `module: None`, clears) the DAO proposal module trusted
+/// to report proposal lifecycle events via [`ExecuteMsg::ProposalHook`].
+pub fn execute_set_close_proposal_module(
+    deps: DepsMut,
+    info: MessageInfo,
+    module: Option<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let module = module.map(|m| deps.api.addr_validate(&m)).transpose()?;
+    CLOSE_PROPOSAL_MODULE.save(deps.storage, &module)?;
+    // Switching modules invalidates whatever proposals we were tracking
+    // for the old one.
+    let stale: Vec<u64> = ACTIVE_PROPOSALS
+        .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for id in stale {
+        ACTIVE_PROPOSALS.remove(deps.storage, id);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_close_proposal_module")
+        .add_attribute(
+            "module",
+            module.map(|a| a.to_string()).unwrap_or_else(|| "None".to_string()),
+        ))
+}
+
+/// Sets (or, with `limit: None`, clears) the sell amount above which
+/// sells are rejected while a proposal from
+/// [`ExecuteMsg::SetCloseProposalModule`] is open for voting.
+pub fn execute_set_proposal_sell_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<Uint128>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    PROPOSAL_SELL_LIMIT.save(deps.storage, &limit)?;
+    Ok(Response::new().add_attribute("action", "set_proposal_sell_limit"))
+}
+
+/// Tracks proposals opened and resolved by [`CLOSE_PROPOSAL_MODULE`], so
+/// that sells can be restricted while one is open for voting. Only
+/// accepted from the configured module.
+pub fn execute_proposal_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    hook_msg: dao_proposal_hooks::ProposalHookMsg,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let close_proposal_module = CLOSE_PROPOSAL_MODULE.may_load(deps.storage)?.flatten();
+    if Some(info.sender) != close_proposal_module {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match hook_msg {
+        dao_proposal_hooks::ProposalHookMsg::NewProposal { id, .. } => {
+            ACTIVE_PROPOSALS.save(deps.storage, id, &cosmwasm_std::Empty {})?;
+        }
+        dao_proposal_hooks::ProposalHookMsg::ProposalStatusChanged {
+            id, new_status, ..
+        } => {
+            if new_status != "open" {
+                ACTIVE_PROPOSALS.remove(deps.storage, id);
+            }
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "proposal_hook"))
+}
+
+/// Sets (or, with `config: None`, clears) the DAO treasury split applied to
+/// every buy by [`process_buy`].
+pub fn execute_set_treasury_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<TreasuryConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<TreasuryConfig, ContractError> {
+            if config.percent.is_zero() || config.percent > Decimal::one() {
+                return Err(ContractError::InvalidTreasuryPercent {});
+            }
+            Ok(TreasuryConfig {
+                treasury: deps.api.addr_validate(&config.treasury)?,
+                percent: config.percent,
+            })
+        })
+        .transpose()?;
+    TREASURY_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_treasury_config"))
+}
+
+/// Sets (or, with `config: None`, clears) the staking contract that a buy
+/// can auto-stake its minted supply into.
+pub fn execute_set_staking_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<StakingConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<StakingConfig, ContractError> {
+            Ok(StakingConfig {
+                staking_contract: deps.api.addr_validate(&config.staking_contract)?,
+                default_stake: config.default_stake,
+            })
+        })
+        .transpose()?;
+    STAKING_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_staking_config"))
+}
+
+/// Sets (or, with `config: None`, clears) the external price oracle pushed
+/// the curve's spot price after a trade. `last_push_height` starts at
+/// zero, so the very next trade pushes regardless of `push_interval`.
+pub fn execute_set_price_oracle(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<PriceOracleConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<PriceOracleConfig, ContractError> {
+            Ok(PriceOracleConfig {
+                oracle: deps.api.addr_validate(&config.oracle)?,
+                push_interval: config.push_interval,
+                last_push_height: 0,
+            })
+        })
+        .transpose()?;
+    PRICE_ORACLE_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_price_oracle"))
+}
+
+/// Pushes `spot_price` to the configured [`PriceOracleConfig::oracle`] as a
+/// fire-and-forget submessage, mirroring `abc_hooks`' dispatch style, if
+/// one is set and at least `push_interval` blocks have passed since the
+/// last push. Advances `last_push_height` when it does.
+fn maybe_push_price_oracle(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    spot_price: Decimal,
+) -> Result<Vec<cosmwasm_std::SubMsg>, ContractError> {
+    let Some(mut config) = PRICE_ORACLE_CONFIG.may_load(storage)?.flatten() else {
+        return Ok(vec![]);
+    };
+    if height.saturating_sub(config.last_push_height) < config.push_interval {
+        return Ok(vec![]);
+    }
+    config.last_push_height = height;
+    PRICE_ORACLE_CONFIG.save(storage, &Some(config.clone()))?;
+
+    Ok(vec![cosmwasm_std::SubMsg::new(WasmMsg::Execute {
+        contract_addr: config.oracle.to_string(),
+        msg: to_binary(&PriceOracleExecuteMsg::UpdatePrice { price: spot_price })?,
+        funds: vec![],
+    })])
+}
+
+/// Sets (or, with `config: None`, clears) the validator and liquidity
+/// floor idle native reserve is staked/undelegated against. Only valid
+/// while the reserve is the chain's staking-bonded native denom, since
+/// [`StakingMsg`] can only ever move that one denom.
+pub fn execute_set_reserve_staking_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<ReserveStakingConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<ReserveStakingConfig, ContractError> {
+            if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+                return Err(ContractError::ReserveNotStakingDenom {});
+            }
+            let reserve = RESERVE.load(deps.storage)?;
+            let bond_denom = deps.querier.query_bonded_denom()?;
+            if reserve.denom != bond_denom {
+                return Err(ContractError::ReserveNotStakingDenom {});
+            }
+            Ok(ReserveStakingConfig {
+                validator: deps.api.addr_validate(&config.validator)?,
+                min_liquid_reserve: config.min_liquid_reserve,
+            })
+        })
+        .transpose()?;
+    RESERVE_STAKING_CONFIG.save(deps.storage, &config)?;
+    if config.is_none() {
+        RESERVE_STAKING_STATE.save(deps.storage, &ReserveStakingState::default())?;
+    } else if RESERVE_STAKING_STATE.may_load(deps.storage)?.is_none() {
+        RESERVE_STAKING_STATE.save(deps.storage, &ReserveStakingState::default())?;
+    }
+    Ok(Response::new().add_attribute("action", "set_reserve_staking_config"))
+}
+
+/// Delegates `amount` of the contract's currently liquid reserve balance
+/// to [`ReserveStakingConfig::validator`]. Anyone may call this; it only
+/// ever moves reserve already sitting idle in the contract, never touches
+/// [`CurveState::reserve`] accounting (which counts staked and liquid
+/// reserve the same), and just records the split in
+/// [`RESERVE_STAKING_STATE`] for [`QueryMsg::ReserveStakingState`].
+pub fn execute_stake_reserve(
+    deps: DepsMut,
+    env: Env,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config = RESERVE_STAKING_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::ReserveStakingNotConfigured {})?;
+    let reserve = RESERVE.load(deps.storage)?;
+    let liquid = deps.querier.query_balance(&env.contract.address, reserve.denom.clone())?.amount;
+    if amount > liquid {
+        return Err(ContractError::InsufficientLiquidReserve { amount, liquid });
+    }
+
+    let mut staking_state = RESERVE_STAKING_STATE.load(deps.storage)?;
+    staking_state.bonded += amount;
+    RESERVE_STAKING_STATE.save(deps.storage, &staking_state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "stake_reserve")
+        .add_attribute("validator", config.validator.clone())
+        .add_attribute("amount", amount)
+        .add_message(StakingMsg::Delegate {
+            validator: config.validator.into_string(),
+            amount: Coin {
+                denom: reserve.denom,
+                amount,
+            },
+        }))
+}
+
+/// Begins unbonding `amount` of reserve previously staked via
+/// [`execute_stake_reserve`], moving it from [`ReserveStakingState::bonded`]
+/// to `unbonding`. The underlying tokens only return to the contract's
+/// spendable balance once the chain's unbonding period elapses; this
+/// contract has no way to track that completion itself, so
+/// [`ReserveStakingState::unbonding`] simply reflects what's in flight.
+pub fn execute_undelegate_reserve(
+    deps: DepsMut,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config = RESERVE_STAKING_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::ReserveStakingNotConfigured {})?;
+    let reserve = RESERVE.load(deps.storage)?;
+
+    let mut staking_state = RESERVE_STAKING_STATE.load(deps.storage)?;
+    staking_state.bonded = staking_state.bonded.checked_sub(amount)?;
+    staking_state.unbonding += amount;
+    RESERVE_STAKING_STATE.save(deps.storage, &staking_state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "undelegate_reserve")
+        .add_attribute("validator", config.validator.clone())
+        .add_attribute("amount", amount)
+        .add_message(StakingMsg::Undelegate {
+            validator: config.validator.into_string(),
+            amount: Coin {
+                denom: reserve.denom,
+                amount,
+            },
+        }))
+}
+
+/// After a sell's `payout` leaves the liquid reserve, tops it back up
+/// toward [`ReserveStakingConfig::min_liquid_reserve`] by undelegating
+/// from the configured validator, bounded by what's actually bonded.
+/// Undelegated reserve only becomes spendable once the chain's unbonding
+/// period elapses, so this is a proactive top-up for future liquidity
+/// needs, not a way to fund the sell that triggered it.
+fn maybe_undelegate_for_liquidity(
+    deps: DepsMut,
+    env: &Env,
+    reserve: &ReserveToken,
+    payout: Uint128,
+) -> Result<Vec<CosmosMsg<TokenFactoryMsg>>, ContractError> {
+    let Some(config) = RESERVE_STAKING_CONFIG.may_load(deps.storage)?.flatten() else {
+        return Ok(vec![]);
+    };
+    let mut staking_state = RESERVE_STAKING_STATE.load(deps.storage)?;
+    if staking_state.bonded.is_zero() {
+        return Ok(vec![]);
+    }
+
+    let balance = deps.querier.query_balance(&env.contract.address, reserve.denom.clone())?.amount;
+    let liquid_after = balance.saturating_sub(payout);
+    if liquid_after >= config.min_liquid_reserve {
+        return Ok(vec![]);
+    }
+    let shortfall = config.min_liquid_reserve - liquid_after;
+    let amount = shortfall.min(staking_state.bonded);
+
+    staking_state.bonded -= amount;
+    staking_state.unbonding += amount;
+    RESERVE_STAKING_STATE.save(deps.storage, &staking_state)?;
+
+    Ok(vec![StakingMsg::Undelegate {
+        validator: config.validator.into_string(),
+        amount: Coin {
+            denom: reserve.denom.clone(),
+            amount,
+        },
+    }
+    .into()])
+}
+
+/// Stashes the contract's current reserve-denom balance and returns a
+/// submessage withdrawing any pending staking rewards from
+/// [`ReserveStakingConfig::validator`], if configured, so [`reply`] can
+/// diff the balance before and after to learn the reward amount (the
+/// staking module doesn't report it directly) and credit it to
+/// [`CurveState::funding`]. Returns `None` if reserve staking isn't
+/// configured, so callers can treat it as a no-op rather than an error.
+fn maybe_harvest_staking_rewards_msg(
+    deps: DepsMut,
+    env: &Env,
+) -> Result<Option<cosmwasm_std::SubMsg<TokenFactoryMsg>>, ContractError> {
+    let Some(config) = RESERVE_STAKING_CONFIG.may_load(deps.storage)?.flatten() else {
+        return Ok(None);
+    };
+    let reserve = RESERVE.load(deps.storage)?;
+    let balance = deps.querier.query_balance(&env.contract.address, reserve.denom)?;
+    PENDING_REWARD_WITHDRAWAL_BALANCE.save(deps.storage, &balance.amount)?;
+
+    Ok(Some(cosmwasm_std::SubMsg::reply_on_success(
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: config.validator.into_string(),
+        },
+        WITHDRAW_RESERVE_REWARDS_REPLY_ID,
+    )))
+}
+
+/// Withdraws any pending staking rewards from
+/// [`ReserveStakingConfig::validator`] and, via [`reply`], credits them to
+/// [`CurveState::funding`]. Errors if reserve staking isn't configured;
+/// see [`execute_harvest`] for a permissionless sweep that skips
+/// unconfigured strategies instead of erroring.
+pub fn execute_withdraw_reserve_rewards(
+    deps: DepsMut,
+    env: Env,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let submsg = maybe_harvest_staking_rewards_msg(deps, &env)?
+        .ok_or(ContractError::ReserveStakingNotConfigured {})?;
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_reserve_rewards")
+        .add_submessage(submsg))
+}
+
+/// Queries [`ReserveVaultConfig::vault`] for this contract's current
+/// balance there and, if it exceeds [`RESERVE_VAULT_DEPOSITED`] (i.e. the
+/// vault has accrued yield on top of the deployed principal), withdraws
+/// exactly the excess and credits it to [`CurveState::funding`]. Unlike
+/// staking rewards, the amount is known upfront from the balance query,
+/// so no reply round-trip is needed; the withdraw message and the funding
+/// credit land in the same response, so a vault that doesn't actually pay
+/// out the requested amount fails the whole transaction instead of
+/// crediting funding for tokens never received.
+fn harvest_vault_yield(
+    deps: DepsMut,
+    env: &Env,
+) -> Result<Option<(CosmosMsg<TokenFactoryMsg>, Uint128)>, ContractError> {
+    let Some(config) = RESERVE_VAULT_CONFIG.may_load(deps.storage)?.flatten() else {
+        return Ok(None);
+    };
+    let deposited = RESERVE_VAULT_DEPOSITED.load(deps.storage)?;
+    let vault_balance: VaultBalanceResponse = deps.querier.query_wasm_smart(
+        config.vault.clone(),
+        &VaultAdapterQueryMsg::Balance {
+            account: env.contract.address.to_string(),
+        },
+    )?;
+    let harvested = vault_balance.balance.saturating_sub(deposited);
+    if harvested.is_zero() {
+        return Ok(None);
+    }
+
+    let mut curve_state = CURVE_STATE.load(deps.storage)?;
+    curve_state.funding = curve_state.funding.checked_add(harvested)?;
+    CURVE_STATE.save(deps.storage, &curve_state)?;
+
+    Ok(Some((
+        WasmMsg::Execute {
+            contract_addr: config.vault.into_string(),
+            msg: to_binary(&VaultAdapterExecuteMsg::Withdraw { amount: harvested })?,
+            funds: vec![],
+        }
+        .into(),
+        harvested,
+    )))
+}
+
+/// Pulls whatever accrued yield is currently available from every
+/// configured reserve-yield strategy and credits it to
+/// [`CurveState::funding`]. Skips any strategy that isn't configured or
+/// has nothing accrued, rather than erroring, so it's safe to call
+/// permissionlessly and often.
+pub fn execute_harvest(
+    mut deps: DepsMut,
+    env: Env,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let mut response = Response::new().add_attribute("action", "harvest");
+
+    if let Some(submsg) = maybe_harvest_staking_rewards_msg(deps.branch(), &env)? {
+        response = response.add_submessage(submsg);
+    }
+
+    if let Some((msg, harvested)) = harvest_vault_yield(deps.branch(), &env)? {
+        let total = HARVESTED_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+        HARVESTED_TOTAL.save(deps.storage, &total.checked_add(harvested)?)?;
+        response = response.add_message(msg).add_event(
+            Event::new("abc-harvest")
+                .add_attribute("source", "vault")
+                .add_attribute("amount", harvested),
+        );
+    }
+
+    Ok(response)
+}
+
+/// Sets (or, with `config: None`, clears) the whitelisted yield vault and
+/// the share of [`CurveState::reserve`] that may be deployed into it.
+pub fn execute_set_reserve_vault_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<ReserveVaultConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<ReserveVaultConfig, ContractError> {
+            if config.max_percent.is_zero() || config.max_percent > Decimal::one() {
+                return Err(ContractError::InvalidReserveVaultPercent {});
+            }
+            Ok(ReserveVaultConfig {
+                vault: deps.api.addr_validate(&config.vault)?,
+                max_percent: config.max_percent,
+            })
+        })
+        .transpose()?;
+    RESERVE_VAULT_CONFIG.save(deps.storage, &config)?;
+    if RESERVE_VAULT_DEPOSITED.may_load(deps.storage)?.is_none() {
+        RESERVE_VAULT_DEPOSITED.save(deps.storage, &Uint128::zero())?;
+    }
+    Ok(Response::new().add_attribute("action", "set_reserve_vault_config"))
+}
+
+/// Deposits `amount` of the reserve into [`ReserveVaultConfig::vault`],
+/// using a bank-attached execute for native reserve or a cw20 `Send` for
+/// cw20 reserve, mirroring [`auto_stake_msg`]'s split. `amount` stays
+/// counted in [`CurveState::reserve`] (this only moves where it's held,
+/// not how much backs the curve), so solvency checks are unaffected;
+/// [`RESERVE_VAULT_DEPOSITED`] tracks how much is out so
+/// [`ReserveVaultConfig::max_percent`] can be enforced against the total.
+pub fn execute_deposit_reserve_to_vault(
+    deps: DepsMut,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config = RESERVE_VAULT_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::ReserveVaultNotConfigured {})?;
+    let curve_state = CURVE_STATE.load(deps.storage)?;
+    let deposited = RESERVE_VAULT_DEPOSITED.load(deps.storage)?;
+    let new_deposited = deposited.checked_add(amount)?;
+    let max_deployable = curve_state.reserve * config.max_percent;
+    if new_deposited > max_deployable {
+        return Err(ContractError::ReserveVaultCapExceeded {
+            amount: new_deposited,
+            max: max_deployable,
+        });
+    }
+    RESERVE_VAULT_DEPOSITED.save(deps.storage, &new_deposited)?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let deposit_msg = to_binary(&VaultAdapterExecuteMsg::Deposit {})?;
+    let msg = match RESERVE_MODE.load(deps.storage)? {
+        ReserveTokenBacking::Native => WasmMsg::Execute {
+            contract_addr: config.vault.to_string(),
+            msg: deposit_msg,
+            funds: vec![cosmwasm_std::coin(amount.u128(), reserve.denom)],
+        },
+        ReserveTokenBacking::Cw20 { address } => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: config.vault.to_string(),
+                amount,
+                msg: deposit_msg,
+            })?,
+            funds: vec![],
+        },
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit_reserve_to_vault")
+        .add_attribute("vault", config.vault)
+        .add_attribute("amount", amount)
+        .add_message(msg))
+}
+
+/// Withdraws `amount` previously deposited via
+/// [`execute_deposit_reserve_to_vault`] back from
+/// [`ReserveVaultConfig::vault`]. Delivered to the vault directly, rather
+/// than through a payout helper, since it's the vault's own reserve
+/// tokens coming back rather than a payout of this contract's balance.
+pub fn execute_withdraw_reserve_from_vault(
+    deps: DepsMut,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config = RESERVE_VAULT_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::ReserveVaultNotConfigured {})?;
+    let deposited = RESERVE_VAULT_DEPOSITED.load(deps.storage)?;
+    RESERVE_VAULT_DEPOSITED.save(deps.storage, &deposited.checked_sub(amount)?)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_reserve_from_vault")
+        .add_attribute("vault", config.vault.clone())
+        .add_attribute("amount", amount)
+        .add_message(WasmMsg::Execute {
+            contract_addr: config.vault.into_string(),
+            msg: to_binary(&VaultAdapterExecuteMsg::Withdraw { amount })?,
+            funds: vec![],
+        }))
+}
+
+/// Sets (or, with `config: None`, clears) the policy for periodically
+/// swapping a capped slice of incoming reserve into
+/// [`DiversificationConfigMsg::target_denom`] via a DEX router.
+/// `last_swap_height` starts at zero, mirroring
+/// [`execute_set_price_oracle`], so the policy is immediately eligible to
+/// swap rather than waiting a full `swap_interval` from the height it was
+/// set at.
+pub fn execute_set_diversification_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<DiversificationConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<DiversificationConfig, ContractError> {
+            if config.percent.is_zero() || config.percent > Decimal::one() {
+                return Err(ContractError::InvalidDiversificationPercent {});
+            }
+            Ok(DiversificationConfig {
+                router: deps.api.addr_validate(&config.router)?,
+                target_denom: config.target_denom,
+                percent: config.percent,
+                swap_interval: config.swap_interval,
+                max_swap_amount: config.max_swap_amount,
+                last_swap_height: 0,
+            })
+        })
+        .transpose()?;
+    DIVERSIFICATION_CONFIG.save(deps.storage, &config)?;
+    if PENDING_DIVERSIFICATION.may_load(deps.storage)?.is_none() {
+        PENDING_DIVERSIFICATION.save(deps.storage, &Uint128::zero())?;
+    }
+    Ok(Response::new().add_attribute("action", "set_diversification_config"))
+}
+
+/// Accrues `amount`, freshly skimmed from a buy's net payment, into
+/// [`PENDING_DIVERSIFICATION`] and, if at least
+/// [`DiversificationConfig::swap_interval`] blocks have passed since the
+/// last swap, carves off up to [`DiversificationConfig::max_swap_amount`]
+/// of the accrued balance to swap now. Returns the router, target denom,
+/// and amount to swap, if any; the caller still needs to query a pre-swap
+/// balance and dispatch the actual submessage, since this only has
+/// `storage` to work with.
+fn accrue_diversification(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    mut config: DiversificationConfig,
+    amount: Uint128,
+) -> Result<Option<(cosmwasm_std::Addr, String, Uint128)>, ContractError> {
+    let pending =
+        PENDING_DIVERSIFICATION.may_load(storage)?.unwrap_or_default().checked_add(amount)?;
+    if height.saturating_sub(config.last_swap_height) < config.swap_interval {
+        PENDING_DIVERSIFICATION.save(storage, &pending)?;
+        return Ok(None);
+    }
+    let swap_amount = pending.min(config.max_swap_amount);
+    if swap_amount.is_zero() {
+        PENDING_DIVERSIFICATION.save(storage, &pending)?;
+        return Ok(None);
+    }
+    config.last_swap_height = height;
+    PENDING_DIVERSIFICATION.save(storage, &pending.checked_sub(swap_amount)?)?;
+    let router = config.router.clone();
+    let target_denom = config.target_denom.clone();
+    DIVERSIFICATION_CONFIG.save(storage, &Some(config))?;
+    Ok(Some((router, target_denom, swap_amount)))
+}
+
+/// Sends `amount` of the reserve to [`DiversificationConfig::router`] with
+/// a [`DexRouterExecuteMsg::Swap`] payload requesting `target_denom` back,
+/// using a bank-attached execute for native reserve or a cw20 `Send` for
+/// cw20 reserve, mirroring [`execute_deposit_reserve_to_vault`]'s split.
+fn dex_swap_msg(
+    storage: &dyn cosmwasm_std::Storage,
+    reserve: &ReserveToken,
+    router: &cosmwasm_std::Addr,
+    amount: Uint128,
+    target_denom: String,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    let swap_msg = to_binary(&DexRouterExecuteMsg::Swap { target_denom })?;
+    Ok(match RESERVE_MODE.load(storage)? {
+        ReserveTokenBacking::Native => WasmMsg::Execute {
+            contract_addr: router.to_string(),
+            msg: swap_msg,
+            funds: vec![cosmwasm_std::coin(amount.u128(), reserve.denom.clone())],
+        }
+        .into(),
+        ReserveTokenBacking::Cw20 { address } => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: router.to_string(),
+                amount,
+                msg: swap_msg,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    })
+}
+
+/// Sets (or, with `config: None`, clears) the pool creator and seed
+/// amounts used at the Hatch->Open transition (see
+/// [`maybe_liquidity_seed_msg`]).
+pub fn execute_set_liquidity_seed_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<LiquiditySeedConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<LiquiditySeedConfig, ContractError> {
+            Ok(LiquiditySeedConfig {
+                pool_creator: deps.api.addr_validate(&config.pool_creator)?,
+                supply_amount: config.supply_amount,
+                reserve_amount: config.reserve_amount,
+            })
+        })
+        .transpose()?;
+    LIQUIDITY_SEED_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_liquidity_seed_config"))
+}
+
+/// If [`LiquiditySeedConfig`] is set, mints `supply_amount` of the supply
+/// token to this contract, debits `reserve_amount` from
+/// [`CurveState::reserve`] (it leaves the curve for good, the same as a
+/// [`TreasuryConfig`] split), and returns the mint message and a single
+/// bank-attached execute against `pool_creator` carrying both amounts, so
+/// the resulting pool exists from the very first `Open`-phase trade. Only
+/// supported when both the supply and reserve tokens are native, since
+/// both need to be attached to one message.
+fn maybe_liquidity_seed_msg(
+    storage: &mut dyn cosmwasm_std::Storage,
+    env: &Env,
+) -> Result<Option<(CosmosMsg<TokenFactoryMsg>, CosmosMsg<TokenFactoryMsg>)>, ContractError> {
+    let Some(config) = LIQUIDITY_SEED_CONFIG.may_load(storage)?.flatten() else {
+        return Ok(None);
+    };
+    let supply_token = SUPPLY_DENOM.load(storage)?;
+    if !matches!(SUPPLY_MODE.load(storage)?, SupplyTokenBacking::TokenFactory) {
+        return Err(ContractError::LiquiditySeedRequiresNativeTokens {});
+    }
+    if !matches!(RESERVE_MODE.load(storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::LiquiditySeedRequiresNativeTokens {});
+    }
+    let reserve = RESERVE.load(storage)?;
+    let mut curve_state = CURVE_STATE.load(storage)?;
+    if config.reserve_amount > curve_state.reserve {
+        return Err(ContractError::InsufficientReserveForSeed {
+            amount: config.reserve_amount,
+            reserve: curve_state.reserve,
+        });
+    }
+    curve_state.reserve = curve_state.reserve.checked_sub(config.reserve_amount)?;
+    CURVE_STATE.save(storage, &curve_state)?;
+
+    let mint_msg = mint_supply_msg(
+        storage,
+        &supply_token,
+        config.supply_amount,
+        env.contract.address.to_string(),
+    )?;
+    let seed_msg = WasmMsg::Execute {
+        contract_addr: config.pool_creator.into_string(),
+        msg: to_binary(&DexPoolExecuteMsg::CreatePool {})?,
+        funds: vec![
+            cosmwasm_std::coin(config.supply_amount.u128(), supply_token.denom),
+            cosmwasm_std::coin(config.reserve_amount.u128(), reserve.denom),
+        ],
+    }
+    .into();
+    Ok(Some((mint_msg, seed_msg)))
+}
+
+/// Resolves whether a buy should auto-stake, per `stake` or (when unset)
+/// [`StakingConfig::default_stake`], and returns the staking contract to
+/// stake into. Errors if staking is requested but none is configured.
+fn resolve_auto_stake(
+    storage: &dyn cosmwasm_std::Storage,
+    stake: Option<bool>,
+) -> Result<Option<cosmwasm_std::Addr>, ContractError> {
+    let staking_config = STAKING_CONFIG.may_load(storage)?.flatten();
+    let should_stake =
+        stake.unwrap_or_else(|| staking_config.as_ref().map_or(false, |c| c.default_stake));
+    if !should_stake {
+        return Ok(None);
+    }
+    staking_config
+        .map(|config| config.staking_contract)
+        .ok_or(ContractError::StakingContractNotConfigured {})
+        .map(Some)
+}
+
+/// Stakes `amount` of the just-minted supply token into `staking_contract`
+/// on behalf of `recipient`, using a bank-attached execute for
+/// token-factory supply or a cw20 `Send` for cw20 supply. The staking
+/// contract must implement [`StakeExecuteMsg`].
+fn auto_stake_msg(
+    storage: &dyn cosmwasm_std::Storage,
+    supply_token: &SupplyToken,
+    staking_contract: &cosmwasm_std::Addr,
+    amount: Uint128,
+    recipient: String,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    let stake_msg = to_binary(&StakeExecuteMsg::StakeFor { recipient })?;
+    match SUPPLY_MODE.load(storage)? {
+        SupplyTokenBacking::TokenFactory => Ok(WasmMsg::Execute {
+            contract_addr: staking_contract.to_string(),
+            msg: stake_msg,
+            funds: vec![cosmwasm_std::coin(amount.u128(), supply_token.denom.clone())],
+        }
+        .into()),
+        SupplyTokenBacking::Cw20 { address } => Ok(WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: staking_contract.to_string(),
+                amount,
+                msg: stake_msg,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
+/// Registers `address` to receive fire-and-forget [`abc_hooks::AbcHookMsg`]
+/// callbacks on every buy, sell, and phase change.
+pub fn execute_add_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    ABC_HOOKS.add_hook(deps.storage, address.clone())?;
+    Ok(Response::new()
+        .add_attribute("action", "add_hook")
+        .add_attribute("address", address))
+}
+
+/// Deregisters a hook added via [`ExecuteMsg::AddHook`].
+pub fn execute_remove_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    ABC_HOOKS.remove_hook(deps.storage, address.clone())?;
+    Ok(Response::new()
+        .add_attribute("action", "remove_hook")
+        .add_attribute("address", address))
+}
+
+/// Sets (or, with `allowance: None`, revokes) `minter`'s remaining
+/// allowance to mint the supply token outside the curve.
+pub fn execute_set_aux_minter_allowance(
+    deps: DepsMut,
+    info: MessageInfo,
+    minter: String,
+    allowance: Option<Uint128>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+
+    let minter = deps.api.addr_validate(&minter)?;
+    match allowance {
+        Some(allowance) => AUX_MINTER_ALLOWANCES.save(deps.storage, &minter, &allowance)?,
+        None => AUX_MINTER_ALLOWANCES.remove(deps.storage, &minter),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_aux_minter_allowance")
+        .add_attribute("minter", minter))
+}
+
+/// Mints `amount` of the supply token to `recipient` against the sender's
+/// remaining allowance, keeping [`CurveState::supply`] consistent with the
+/// true outstanding supply.
+pub fn execute_aux_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let allowance = AUX_MINTER_ALLOWANCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if amount > allowance {
+        return Err(ContractError::InsufficientAuxMinterAllowance { amount, allowance });
+    }
+    AUX_MINTER_ALLOWANCES.save(deps.storage, &info.sender, &allowance.checked_sub(amount)?)?;
+
+    let mut curve_state = CURVE_STATE.load(deps.storage)?;
+    curve_state.supply = curve_state.supply.checked_add(amount)?;
+    CURVE_STATE.save(deps.storage, &curve_state)?;
+    SUPPLY_SNAPSHOT.save(deps.storage, &curve_state.supply, env.block.height)?;
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let mint_msg = mint_supply_msg(deps.storage, &supply_token, amount, recipient.to_string())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "aux_mint")
+        .add_attribute("minter", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount)
+        .add_message(mint_msg))
+}
+
+/// Configures (or, with `oracle: None`, removes) a secondary reserve denom
+/// accepted for buys alongside the canonical reserve.
+pub fn execute_update_secondary_reserve(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    oracle: Option<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    assert_not_frozen(deps.storage)?;
+
+    match oracle {
+        Some(oracle) => {
+            let oracle = deps.api.addr_validate(&oracle)?;
+            SECONDARY_RESERVES.save(deps.storage, &denom, &SecondaryReserve { oracle })?;
+        }
+        None => SECONDARY_RESERVES.remove(deps.storage, &denom),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_secondary_reserve")
+        .add_attribute("denom", denom))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stake: Option<bool>,
+    referrer: Option<String>,
+    ibc_forward: Option<IbcForwardMsg>,
+    idempotency_key: Option<String>,
+    permit: Option<BuyPermit>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let buyer = info.sender.clone();
+    buy_impl(deps, env, info, buyer, stake, referrer, ibc_forward, idempotency_key, permit)
+}
+
+/// An ibc-hooks "wasm" memo on an incoming ICS-20 transfer deserializes
+/// straight into this variant (the chain's ibc-hooks module does the memo
+/// parsing, not this contract), so `info.sender` here is the module's
+/// derived intermediary address rather than the real sender on the source
+/// chain; `receiver` is that source-chain sender's address on this chain,
+/// named explicitly in the memo, and is who the supply tokens are minted
+/// to. If the memo also set `ibc_callback` to this contract's address, the
+/// chain's ibc-hooks module relays this call's `minted` attribute back to
+/// the source chain via [`SudoMsg::IBCLifecycleComplete`]'s ack payload.
+pub fn execute_ibc_hooks_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: String,
+    stake: Option<bool>,
+    referrer: Option<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let buyer = deps.api.addr_validate(&receiver)?;
+    buy_impl(deps, env, info, buyer, stake, referrer, None, None, None)
+}
+
+/// Unwraps `memo` (see [`ibc::unwrap_pfm_buy_memo`]) down to its
+/// [`ExecuteMsg::IbcHooksBuy`] payload and buys with it, for transfers
+/// routed through packet-forward-middleware where the final `wasm` hook
+/// is left nested inside `forward` hops rather than pre-unwrapped by the
+/// receiving chain's ibc-hooks module.
+pub fn execute_ibc_hooks_buy_raw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    memo: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let msg_json = ibc::unwrap_pfm_buy_memo(&memo)?;
+    let msg: ExecuteMsg = cosmwasm_std::from_slice(msg_json.as_bytes())
+        .map_err(|err| ContractError::MalformedIbcMemo { reason: err.to_string() })?;
+    match msg {
+        ExecuteMsg::IbcHooksBuy { receiver, stake, referrer } => {
+            execute_ibc_hooks_buy(deps, env, info, receiver, stake, referrer)
+        }
+        _ => Err(ContractError::MalformedIbcMemo {
+            reason: "memo's wasm.msg is not an IbcHooksBuy payload".to_string(),
+        }),
+    }
+}
+
+/// Buys on behalf of `receiver` for a general-message-passing call
+/// originated on an EVM chain, with the bridged reserve already
+/// credited to this contract as `info.funds` by the gateway before it
+/// invokes this variant. `evm_sender` is only validated for shape (see
+/// [`assert_evm_address`]) and recorded as an attribute; it isn't used
+/// to derive `receiver`, since there's no general EVM-to-Cosmos address
+/// mapping to derive it from.
+pub fn execute_gmp_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    evm_sender: String,
+    receiver: String,
+    stake: Option<bool>,
+    referrer: Option<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_evm_address(&evm_sender)?;
+    let buyer = deps.api.addr_validate(&receiver)?;
+    let response = buy_impl(deps, env, info, buyer, stake, referrer, None, None, None)?;
+    Ok(response.add_attribute("evm_sender", evm_sender))
+}
+
+/// Checks that `address` has the shape of an EVM address (`0x` followed
+/// by 40 hex characters). This is the full extent of what this contract
+/// can verify on its own — the GMP gateway that invokes
+/// [`ExecuteMsg::GmpBuy`] is trusted to have already authenticated the
+/// call against the real source-chain sender.
+fn assert_evm_address(address: &str) -> Result<(), ContractError> {
+    let is_valid = address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit());
+    if is_valid {
+        Ok(())
+    } else {
+        Err(ContractError::InvalidEvmAddress { address: address.to_string() })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn buy_impl(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    buyer: cosmwasm_std::Addr,
+    stake: Option<bool>,
+    referrer: Option<String>,
+    ibc_forward: Option<IbcForwardMsg>,
+    idempotency_key: Option<String>,
+    permit: Option<BuyPermit>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if let Some(key) = &idempotency_key {
+        if let Some(record) = IDEMPOTENCY_KEYS.may_load(deps.storage, key)? {
+            let expires_at = record.recorded_at.plus_seconds(IDEMPOTENCY_KEY_TTL_SECONDS);
+            if env.block.time < expires_at {
+                let mut response = Response::new()
+                    .add_attribute("action", "buy")
+                    .add_attribute("idempotency_key", key.clone())
+                    .add_attribute("replayed", "true")
+                    .add_attribute("minted", record.minted);
+                if !info.funds.is_empty() {
+                    response = response.add_message(BankMsg::Send {
+                        to_address: info.sender.to_string(),
+                        amount: info.funds.clone(),
+                    });
+                }
+                return Ok(response);
+            }
+        }
+    }
+
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let reserve = RESERVE.load(deps.storage)?;
+    let coin = cw_utils::one_coin(&info)?;
+    let payment = if coin.denom == reserve.denom {
+        coin.amount
+    } else {
+        let secondary = SECONDARY_RESERVES
+            .load(deps.storage, &coin.denom)
+            .map_err(|_| ContractError::UnsupportedReserveDenom {
+                denom: coin.denom.clone(),
+            })?;
+        let price: PriceResponse = deps.querier.query_wasm_smart(
+            secondary.oracle,
+            &OracleQueryMsg::Price {
+                denom: coin.denom.clone(),
+            },
+        )?;
+        coin.amount * price.rate
+    };
+
+    let permit_authorized = match &permit {
+        Some(permit) => {
+            verify_buy_permit(deps.storage, deps.api, env.block.time, &buyer, payment, permit)?;
+            true
+        }
+        None => false,
+    };
+
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+    let (minted, fee, treasury_payout, diversification_swap, referral_payout, hatch_events) =
+        process_buy(deps.storage, env.block.height, payment, &buyer, referrer, permit_authorized)?;
+
+    if let Some(key) = &idempotency_key {
+        IDEMPOTENCY_KEYS.save(
+            deps.storage,
+            key,
+            &IdempotencyRecord { minted, recorded_at: env.block.time },
+        )?;
+    }
+
+    let staking_contract = resolve_auto_stake(deps.storage, stake)?;
+    if staking_contract.is_some() && ibc_forward.is_some() {
+        return Err(ContractError::IbcForwardConflictsWithStake {});
+    }
+    if ibc_forward.is_some()
+        && !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory)
+    {
+        return Err(ContractError::IbcForwardRequiresNativeSupply {});
+    }
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let mint_recipient = match (&staking_contract, &ibc_forward) {
+        (Some(_), _) | (_, Some(_)) => env.contract.address.to_string(),
+        (None, None) => buyer.to_string(),
+    };
+    let mint_msg = mint_supply_msg(deps.storage, &supply_token, minted, mint_recipient)?;
+    let hook_msgs =
+        abc_hooks::buy_hook_msgs(ABC_HOOKS, deps.storage, buyer.clone(), minted, fee)?;
+
+    let (trade_event, oracle_msgs) = trade_event(
+        deps.storage,
+        env.block.height,
+        env.block.time,
+        "buy",
+        &buyer,
+        payment,
+        minted,
+        fee,
+    )?;
+    let mut response = Response::new()
+        .add_attribute("action", "buy")
+        .add_attribute("buyer", buyer.clone())
+        .add_attribute("payment", payment)
+        .add_attribute("fee", fee)
+        .add_attribute(
+            "treasury_split",
+            treasury_payout.as_ref().map(|(_, amount)| *amount).unwrap_or_default(),
+        )
+        .add_attribute(
+            "diversification_swap",
+            diversification_swap.as_ref().map(|(_, _, amount)| *amount).unwrap_or_default(),
+        )
+        .add_attribute(
+            "referral_payout",
+            referral_payout.as_ref().map(|(_, amount)| *amount).unwrap_or_default(),
+        )
+        .add_attribute("minted", minted)
+        .add_attribute("staked", staking_contract.is_some().to_string())
+        .add_attribute("ibc_forwarded", ibc_forward.is_some().to_string())
+        .add_attribute("idempotency_key", idempotency_key.clone().unwrap_or_default())
+        .add_event(trade_event)
+        .add_events(hatch_events)
+        .add_message(mint_msg);
+    if let Some(staking_contract) = &staking_contract {
+        response = response.add_message(auto_stake_msg(
+            deps.storage,
+            &supply_token,
+            staking_contract,
+            minted,
+            buyer.to_string(),
+        )?);
+    }
+    if let Some(forward) = ibc_forward {
+        response = response.add_message(CosmosMsg::<TokenFactoryMsg>::Ibc(IbcMsg::Transfer {
+            channel_id: forward.channel,
+            to_address: forward.to_address,
+            amount: Coin { denom: supply_token.denom.clone(), amount: minted },
+            timeout: env.block.time.plus_seconds(forward.timeout).into(),
+        }));
+    }
+    if let Some((treasury, amount)) = treasury_payout {
+        response = response.add_message(reserve_payout_msg(
+            deps.storage,
+            &reserve,
+            treasury.to_string(),
+            amount,
+        )?);
+    }
+    if let Some((referrer, amount)) = referral_payout {
+        response = response.add_message(reserve_payout_msg(
+            deps.storage,
+            &reserve,
+            referrer.to_string(),
+            amount,
+        )?);
+    }
+    if let Some((router, target_denom, amount)) = diversification_swap {
+        let balance_before =
+            deps.querier.query_balance(&env.contract.address, target_denom.clone())?.amount;
+        PENDING_DIVERSIFICATION_SWAP.save(
+            deps.storage,
+            &PendingDiversificationSwap { denom: target_denom.clone(), balance_before },
+        )?;
+        let swap_msg = dex_swap_msg(deps.storage, &reserve, &router, amount, target_denom)?;
+        response = response.add_submessage(cosmwasm_std::SubMsg::reply_on_success(
+            swap_msg,
+            DIVERSIFICATION_SWAP_REPLY_ID,
+        ));
+    }
+
+    Ok(response.add_submessages(hook_msgs).add_submessages(oracle_msgs))
+}
+
+/// Buys once against the curve with `info.funds` and splits the minted
+/// supply across `recipients` by weight (`share = minted * weight /
+/// total_weight`, truncated the same way
+/// [`execute_settle_batch_auction`]'s pro-rata split is) instead of
+/// minting it all to one buyer -- useful for a grant program or payroll
+/// paid in the commons token. Hatch-phase accounting (allowlist,
+/// contribution limits) is tracked against `info.sender`, not the
+/// recipients, since they may not have contributed anything themselves.
+/// Doesn't support staking, a referrer, or IBC forwarding, since those
+/// only make sense for a single buyer; use [`ExecuteMsg::Buy`] for that.
+pub fn execute_buy_and_distribute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<(String, Uint128)>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if recipients.is_empty() {
+        return Err(ContractError::EmptyRecipientList {});
+    }
+    let reserve = RESERVE.load(deps.storage)?;
+    let coin = cw_utils::one_coin(&info)?;
+    if coin.denom != reserve.denom {
+        return Err(ContractError::UnsupportedReserveDenom { denom: coin.denom });
+    }
+    let mut total_weight = Uint128::zero();
+    for (_, weight) in &recipients {
+        total_weight = total_weight.checked_add(*weight)?;
+    }
+    if total_weight.is_zero() {
+        return Err(ContractError::ZeroTotalWeight {});
+    }
+
+    let (minted, fee, treasury_payout, diversification_swap, referral_payout, hatch_events) =
+        process_buy(deps.storage, env.block.height, coin.amount, &info.sender, None, false)?;
+    debug_assert!(referral_payout.is_none());
+
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let (trade_event, oracle_msgs) = trade_event(
+        deps.storage,
+        env.block.height,
+        env.block.time,
+        "buy",
+        &info.sender,
+        coin.amount,
+        minted,
+        fee,
+    )?;
+    let mut response = Response::new()
+        .add_attribute("action", "buy_and_distribute")
+        .add_attribute("buyer", info.sender.clone())
+        .add_attribute("payment", coin.amount)
+        .add_attribute("fee", fee)
+        .add_attribute("minted", minted)
+        .add_event(trade_event)
+        .add_events(hatch_events);
+    for (address, weight) in recipients {
+        let recipient = deps.api.addr_validate(&address)?;
+        let share = minted.multiply_ratio(weight, total_weight);
+        let mint_msg = mint_supply_msg(deps.storage, &supply_token, share, recipient.to_string())?;
+        response = response.add_message(mint_msg).add_event(
+            Event::new("abc-distributed-buy")
+                .add_attribute("recipient", recipient)
+                .add_attribute("weight", weight)
+                .add_attribute("minted", share),
+        );
+    }
+    if let Some((treasury, amount)) = treasury_payout {
+        response = response.add_message(reserve_payout_msg(
+            deps.storage,
+            &reserve,
+            treasury.to_string(),
+            amount,
+        )?);
+    }
+    if let Some((router, target_denom, amount)) = diversification_swap {
+        let balance_before =
+            deps.querier.query_balance(&env.contract.address, target_denom.clone())?.amount;
+        PENDING_DIVERSIFICATION_SWAP.save(
+            deps.storage,
+            &PendingDiversificationSwap { denom: target_denom.clone(), balance_before },
+        )?;
+        let swap_msg = dex_swap_msg(deps.storage, &reserve, &router, amount, target_denom)?;
+        response = response.add_submessage(cosmwasm_std::SubMsg::reply_on_success(
+            swap_msg,
+            DIVERSIFICATION_SWAP_REPLY_ID,
+        ));
+    }
+
+    Ok(response.add_submessages(oracle_msgs))
+}
+
+/// Checks `permit` against [`PERMIT_AUTHORIZER_CONFIG`]: that a key is
+/// configured at all, that `permit` hasn't expired, that `payment` is
+/// within `permit.max_amount`, and that `permit.signature` is a valid
+/// secp256k1 signature over the SHA-256 hash of a [`PermitPayload`] built
+/// from `buyer` and `permit`'s own fields -- so a signed voucher can't be
+/// replayed for a different buyer, a higher amount, or past its expiry.
+fn verify_buy_permit(
+    storage: &dyn cosmwasm_std::Storage,
+    api: &dyn Api,
+    block_time: Timestamp,
+    buyer: &cosmwasm_std::Addr,
+    payment: Uint128,
+    permit: &BuyPermit,
+) -> Result<(), ContractError> {
+    let config = PERMIT_AUTHORIZER_CONFIG
+        .may_load(storage)?
+        .flatten()
+        .ok_or(ContractError::PermitAuthorizerNotConfigured {})?;
+    if block_time > permit.expires_at {
+        return Err(ContractError::PermitExpired { expires_at: permit.expires_at });
+    }
+    if payment > permit.max_amount {
+        return Err(ContractError::PermitAmountExceeded {
+            requested: payment,
+            max_amount: permit.max_amount,
+        });
+    }
+    let payload = PermitPayload {
+        buyer: buyer.to_string(),
+        max_amount: permit.max_amount,
+        expires_at: permit.expires_at,
+    };
+    let hash = Sha256::digest(cosmwasm_std::to_vec(&payload)?);
+    let valid = api
+        .secp256k1_verify(&hash, &permit.signature, &config.pubkey)
+        .map_err(|_| ContractError::InvalidPermitSignature {})?;
+    if !valid {
+        return Err(ContractError::InvalidPermitSignature {});
+    }
+    Ok(())
+}
+
+/// Shared buy-side curve math: applies the entry fee and hatch contribution
+/// limit for `buyer`, splits off the [`TreasuryConfig`] share (if any),
+/// the [`DiversificationConfig`] share (if any), and the
+/// [`ReferralConfig`] share for `referrer` (if both are set) of what
+/// remains, and mints the rest of `payment` worth of reserve into the
+/// curve state. Returns `(minted, fee, treasury_payout,
+/// diversification_swap, referral_payout, hatch_events)`, where
+/// `treasury_payout` is the treasury address and amount to pay out when a
+/// split is configured, `diversification_swap` is the router, target
+/// denom, and amount to swap when a diversification swap is due this buy
+/// (see [`accrue_diversification`]), `referral_payout` is `referrer` and
+/// the amount to pay it, and `hatch_events` carries an
+/// `abc-hatch-progress` event (empty outside the hatch phase) so
+/// dashboards can track the raise without polling state. Callers still
+/// need to issue the actual mint, treasury payout, referral payout, and
+/// swap messages, which differ between token-factory/cw20 supply tokens
+/// and native/cw20 reserve tokens. `permit_authorized` lets a hatch-phase
+/// buy through [`crate::state::HatchConfig::allowlist`] without `buyer`
+/// being on it; see [`verify_buy_permit`]. If
+/// [`crate::state::HatchConfig::batch_auction`] is set, a hatch-phase buy
+/// pools `net_payment` into [`BATCH_AUCTION_STATE`]/[`BATCH_CONTRIBUTIONS`]
+/// instead of minting against the curve immediately, returning `minted:
+/// 0`; see [`execute_settle_batch_auction`].
+fn process_buy(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    payment: Uint128,
+    buyer: &cosmwasm_std::Addr,
+    referrer: Option<cosmwasm_std::Addr>,
+    permit_authorized: bool,
+) -> Result<
+    (
+        Uint128,
+        Uint128,
+        Option<(cosmwasm_std::Addr, Uint128)>,
+        Option<(cosmwasm_std::Addr, String, Uint128)>,
+        Option<(cosmwasm_std::Addr, Uint128)>,
+        Vec<Event>,
+    ),
+    ContractError,
+> {
+    assert_trading_not_paused(storage)?;
+    let phase = PHASE.load(storage)?;
+    let phase_config = PHASE_CONFIG.load(storage)?;
+    let mut curve_state = CURVE_STATE.load(storage)?;
+
+    let mut hatch_contributed = None;
+    let mut batch_pooled = false;
+    let entry_fee = match phase {
+        Phase::Hatch => {
+            if let Some(allowlist) = &phase_config.hatch.allowlist {
+                if !permit_authorized && !allowlist.contains(buyer) {
+                    return Err(ContractError::NotAllowlisted { address: buyer.to_string() });
+                }
+            }
+            batch_pooled = phase_config.hatch.batch_auction;
+            let raised_so_far = if batch_pooled {
+                BATCH_AUCTION_STATE.may_load(storage)?.unwrap_or_default().total_pool
+            } else {
+                curve_state.reserve
+            };
+            let remaining_to_cap =
+                phase_config.hatch.initial_raise.max.saturating_sub(raised_so_far);
+            if payment > remaining_to_cap {
+                return Err(ContractError::HatchCapExceeded { remaining: remaining_to_cap });
+            }
+            let contributed = HATCHERS.may_load(storage, buyer)?.unwrap_or_default();
+            let total = contributed + payment;
+            if !phase_config.hatch.contribution_limits.contains(total) {
+                return Err(ContractError::ContributionLimit { amount: payment });
+            }
+            HATCHERS.save(storage, buyer, &total)?;
+            hatch_contributed = Some(total);
+            phase_config.hatch.entry_fee
+        }
+        Phase::Open => phase_config.open.entry_fee,
+        Phase::Closed => return Err(ContractError::CommonsClosed {}),
+    };
+
+    let fee = payment * entry_fee;
+    let funding_fee = accrue_backstop_fee(storage, fee)?;
+    let mut net_payment = payment.checked_sub(fee)?;
+
+    let treasury_payout = TREASURY_CONFIG
+        .may_load(storage)?
+        .flatten()
+        .map(|treasury_config| -> Result<_, ContractError> {
+            let amount = net_payment * treasury_config.percent;
+            net_payment = net_payment.checked_sub(amount)?;
+            Ok((treasury_config.treasury, amount))
+        })
+        .transpose()?;
+
+    let mut diversification_swap = None;
+    if let Some(config) = DIVERSIFICATION_CONFIG.may_load(storage)?.flatten() {
+        let amount = net_payment * config.percent;
+        net_payment = net_payment.checked_sub(amount)?;
+        diversification_swap = accrue_diversification(storage, height, config, amount)?;
+    }
+
+    let referral_payout = match (referrer, REFERRAL_CONFIG.may_load(storage)?.flatten()) {
+        (Some(referrer), Some(config)) => {
+            let amount = net_payment * config.percent;
+            net_payment = net_payment.checked_sub(amount)?;
+            let earned = REFERRAL_EARNED.may_load(storage, &referrer)?.unwrap_or_default();
+            REFERRAL_EARNED.save(storage, &referrer, &earned.checked_add(amount)?)?;
+            Some((referrer, amount))
+        }
+        _ => None,
+    };
+
+    let (minted, total_raised) = if batch_pooled {
+        let mut batch_state = BATCH_AUCTION_STATE.may_load(storage)?.unwrap_or_default();
+        batch_state.total_pool = batch_state.total_pool.checked_add(net_payment)?;
+        BATCH_AUCTION_STATE.save(storage, &batch_state)?;
+        let contributed = BATCH_CONTRIBUTIONS.may_load(storage, buyer)?.unwrap_or_default();
+        BATCH_CONTRIBUTIONS.save(storage, buyer, &contributed.checked_add(net_payment)?)?;
+        (Uint128::zero(), batch_state.total_pool)
+    } else {
+        let curve = load_curve(storage, curve_state.decimals)?;
+        let quote =
+            quote_buy(curve.as_ref(), curve_state.supply, curve_state.reserve, net_payment)
+                .map_err(StdError::from)?;
+        curve_state.reserve = quote.new_reserve;
+        curve_state.supply = quote.new_supply;
+        (quote.delta, curve_state.reserve)
+    };
+
+    curve_state.funding = curve_state.funding.checked_add(funding_fee)?;
+    CURVE_STATE.save(storage, &curve_state)?;
+    SUPPLY_SNAPSHOT.save(storage, &curve_state.supply, height)?;
+    RESERVE_SNAPSHOT.save(storage, &curve_state.reserve, height)?;
+
+    let hatch_events = match hatch_contributed {
+        Some(contributed) => {
+            let remaining_to_cap =
+                phase_config.hatch.initial_raise.max.saturating_sub(total_raised);
+            vec![Event::new("abc-hatch-progress")
+                .add_attribute("contributor", buyer)
+                .add_attribute("contributed", contributed)
+                .add_attribute("total_raised", total_raised)
+                .add_attribute("remaining_to_cap", remaining_to_cap)]
+        }
+        None => vec![],
+    };
+
+    Ok((minted, fee, treasury_payout, diversification_swap, referral_payout, hatch_events))
+}
+
+/// Mints `amount` of the supply token to `recipient`, using a token-factory
+/// mint or a cw20 `Mint` depending on [`SupplyTokenBacking`].
+fn mint_supply_msg(
+    storage: &dyn cosmwasm_std::Storage,
+    supply_token: &SupplyToken,
+    amount: Uint128,
+    recipient: String,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    match SUPPLY_MODE.load(storage)? {
+        SupplyTokenBacking::TokenFactory => Ok(CosmosMsg::Custom(TokenMsg::MintTokens {
+            denom: supply_token.denom.clone(),
+            amount,
+            mint_to_address: recipient,
+        })),
+        SupplyTokenBacking::Cw20 { address } => Ok(WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Mint { recipient, amount })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
+/// Pays out `amount` of the reserve token to `recipient`, using a bank send
+/// or a cw20 `Transfer` depending on [`ReserveTokenBacking`].
+fn reserve_payout_msg(
+    storage: &dyn cosmwasm_std::Storage,
+    reserve: &ReserveToken,
+    recipient: String,
+    amount: Uint128,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    match RESERVE_MODE.load(storage)? {
+        ReserveTokenBacking::Native => Ok(BankMsg::Send {
+            to_address: recipient,
+            amount: vec![cosmwasm_std::coin(amount.u128(), reserve.denom.clone())],
+        }
+        .into()),
+        ReserveTokenBacking::Cw20 { address } => Ok(WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer { recipient, amount })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
+/// Builds the standardized `abc-trade` event (`wasm-abc-trade` on-chain,
+/// per cosmwasm's automatic event-type prefixing) attached to every
+/// buy/sell response, so an indexer can read off the post-trade reserve,
+/// supply, and spot price without re-deriving curve math from
+/// `CurveInfo`. Also advances [`PRICE_ACCUMULATOR`] and [`VOLUME_BY_DAY`]
+/// with the freshly recomputed spot price and traded amount, and,
+/// alongside the event, returns any submessage needed to push that price
+/// to a configured [`PriceOracleConfig`]. Must be called after the
+/// trade's [`CurveState`] update has already been saved.
+fn trade_event(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    time: cosmwasm_std::Timestamp,
+    kind: &str,
+    trader: &cosmwasm_std::Addr,
+    payment_or_amount: Uint128,
+    minted_or_burned: Uint128,
+    fee: Uint128,
+) -> Result<(Event, Vec<cosmwasm_std::SubMsg>), ContractError> {
+    let phase = PHASE.load(storage)?;
+    let curve_state = CURVE_STATE.load(storage)?;
+    let curve = load_curve(storage, curve_state.decimals)?;
+    let spot_price = curve.spot_price(curve_state.supply);
+
+    update_price_accumulator(storage, height, spot_price)?;
+    let mut oracle_msgs = maybe_push_price_oracle(storage, height, spot_price)?;
+    oracle_msgs.extend(ibc::state_sync_packet_msgs(
+        storage,
+        time,
+        phase.clone(),
+        curve_state.supply,
+        curve_state.reserve,
+        spot_price,
+    )?);
+    record_volume(storage, time, kind, payment_or_amount)?;
+    record_candle(storage, time, spot_price, payment_or_amount)?;
+    record_account_stats(storage, trader, kind, payment_or_amount, minted_or_burned)?;
+    if kind == "buy" && !FIRST_ACQUIRED.has(storage, trader) {
+        FIRST_ACQUIRED.save(storage, trader, &time)?;
+    }
+    CURVE_CHECKPOINTS.save(
+        storage,
+        height,
+        &CurveCheckpoint {
+            height,
+            reserve: curve_state.reserve,
+            supply: curve_state.supply,
+            spot_price,
+        },
+    )?;
+
+    let trade_id = TRADE_COUNT.may_load(storage)?.unwrap_or_default();
+    TRADES.save(
+        storage,
+        trade_id,
+        &TradeRecord {
+            trader: trader.clone(),
+            kind: kind.to_string(),
+            amount: payment_or_amount,
+            minted_or_burned,
+            fee,
+            height,
+            time,
+        },
+    )?;
+    TRADE_COUNT.save(storage, &(trade_id + 1))?;
+
+    let event = Event::new("abc-trade")
+        .add_attribute("kind", kind)
+        .add_attribute("trader", trader)
+        .add_attribute("phase", format!("{phase:?}"))
+        .add_attribute("amount", payment_or_amount)
+        .add_attribute("minted_or_burned", minted_or_burned)
+        .add_attribute("fee", fee)
+        .add_attribute("new_reserve", curve_state.reserve)
+        .add_attribute("new_supply", curve_state.supply)
+        .add_attribute("new_spot_price", spot_price.to_string());
+    Ok((event, oracle_msgs))
+}
+
+/// Advances [`PRICE_ACCUMULATOR`] by `last_spot_price * blocks_elapsed`
+/// and snapshots the running total under [`CUMULATIVE_PRICE_SNAPSHOT`], so
+/// `QueryMsg::Twap` can later diff two heights for a block-weighted
+/// average price. Called after every trade with the curve's freshly
+/// recomputed spot price.
+fn update_price_accumulator(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    new_spot_price: Decimal,
+) -> StdResult<()> {
+    let mut accumulator = PRICE_ACCUMULATOR.load(storage)?;
+    let elapsed = height.saturating_sub(accumulator.last_update_height);
+    if elapsed > 0 {
+        let weighted = accumulator.last_spot_price * Decimal::from_ratio(elapsed, 1u128);
+        accumulator.cumulative_price = accumulator.cumulative_price.checked_add(weighted)?;
+    }
+    accumulator.last_spot_price = new_spot_price;
+    accumulator.last_update_height = height;
+    PRICE_ACCUMULATOR.save(storage, &accumulator)?;
+    CUMULATIVE_PRICE_SNAPSHOT.save(storage, &accumulator.cumulative_price, height)?;
+    Ok(())
+}
+
+/// Adds `amount` to the current epoch day's [`VOLUME_BY_DAY`] bucket, on
+/// the `buy_volume` or `sell_volume` side per `kind`, so `QueryMsg::Volume`
+/// can later sum a fixed number of recent days.
+fn record_volume(
+    storage: &mut dyn cosmwasm_std::Storage,
+    time: cosmwasm_std::Timestamp,
+    kind: &str,
+    amount: Uint128,
+) -> StdResult<()> {
+    let day = time.seconds() / 86400;
+    let mut bucket = VOLUME_BY_DAY.may_load(storage, day)?.unwrap_or_default();
+    match kind {
+        "buy" => bucket.buy_volume += amount,
+        "sell" => bucket.sell_volume += amount,
+        _ => {}
+    }
+    VOLUME_BY_DAY.save(storage, day, &bucket)
+}
+
+/// Updates the current epoch day's [`CANDLES_BY_DAY`] entry with
+/// `spot_price` and `amount`, opening a fresh candle at `spot_price` the
+/// first time a day is touched, so `QueryMsg::Candles` can render a chart
+/// incrementally without replaying trade history.
+fn record_candle(
+    storage: &mut dyn cosmwasm_std::Storage,
+    time: cosmwasm_std::Timestamp,
+    spot_price: Decimal,
+    amount: Uint128,
+) -> StdResult<()> {
+    let day = time.seconds() / 86400;
+    let mut candle = CANDLES_BY_DAY.may_load(storage, day)?.unwrap_or(DailyCandle {
+        open: spot_price,
+        high: spot_price,
+        low: spot_price,
+        close: spot_price,
+        volume: Uint128::zero(),
+    });
+    candle.high = candle.high.max(spot_price);
+    candle.low = candle.low.min(spot_price);
+    candle.close = spot_price;
+    candle.volume += amount;
+    CANDLES_BY_DAY.save(storage, day, &candle)
+}
+
+/// Adds to `trader`'s lifetime [`AccountStats`]: `payment_or_amount` to
+/// `reserve_contributed` and `minted_or_burned` to `tokens_bought` on a
+/// buy, or `minted_or_burned` to `tokens_sold` on a sell.
+fn record_account_stats(
+    storage: &mut dyn cosmwasm_std::Storage,
+    trader: &cosmwasm_std::Addr,
+    kind: &str,
+    payment_or_amount: Uint128,
+    minted_or_burned: Uint128,
+) -> StdResult<()> {
+    let mut stats = ACCOUNT_STATS.may_load(storage, trader)?.unwrap_or_default();
+    match kind {
+        "buy" => {
+            stats.reserve_contributed += payment_or_amount;
+            stats.tokens_bought += minted_or_burned;
+        }
+        "sell" => stats.tokens_sold += minted_or_burned,
+        _ => {}
+    }
+    ACCOUNT_STATS.save(storage, trader, &stats)
+}
+
+/// Appends a [`ParamChange`] to [`PARAM_CHANGES`] so `QueryMsg::History`
+/// can answer for auditors without replaying execute messages. `what`
+/// should name the config being changed (e.g. `"fees"`,
+/// `"timelock_config"`); `old_value`/`new_value` are already-encoded via
+/// `to_binary`, `None` for a value that didn't exist yet or was cleared.
+fn record_param_change(
+    storage: &mut dyn cosmwasm_std::Storage,
+    env: &Env,
+    changed_by: &cosmwasm_std::Addr,
+    what: &str,
+    old_value: Option<Binary>,
+    new_value: Option<Binary>,
+) -> StdResult<()> {
+    let id = PARAM_CHANGE_COUNT.may_load(storage)?.unwrap_or_default();
+    PARAM_CHANGES.save(
+        storage,
+        id,
+        &ParamChange {
+            what: what.to_string(),
+            old_value,
+            new_value,
+            changed_by: changed_by.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        },
+    )?;
+    PARAM_CHANGE_COUNT.save(storage, &(id + 1))
+}
+
+/// Rejects the sell if a proposal is open on [`CLOSE_PROPOSAL_MODULE`] and
+/// `amount` exceeds [`PROPOSAL_SELL_LIMIT`], to guard against front-running
+/// a close-the-commons proposal.
+fn assert_sell_within_proposal_limit(
+    storage: &dyn cosmwasm_std::Storage,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let limit = match PROPOSAL_SELL_LIMIT.may_load(storage)?.flatten() {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+    if amount <= limit {
+        return Ok(());
+    }
+    let any_open = ACTIVE_PROPOSALS
+        .keys(storage, None, None, cosmwasm_std::Order::Ascending)
+        .next()
+        .transpose()?
+        .is_some();
+    if any_open {
+        return Err(ContractError::SellRestrictedDuringProposal { amount, limit });
+    }
+    Ok(())
+}
+
+pub fn execute_sell(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    if !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let amount = cw_utils::must_pay(&info, &supply_token.denom)?;
+    assert_sell_within_proposal_limit(deps.storage, amount)?;
+
+    let (payout, fee) =
+        process_sell(deps.storage, env.block.height, env.block.time, &info.sender, amount)?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let burn_msg = CosmosMsg::Custom(TokenMsg::BurnTokens {
+        denom: supply_token.denom,
+        amount,
+        burn_from_address: env.contract.address.to_string(),
+    });
+    let payout_msg = reserve_payout_msg(deps.storage, &reserve, info.sender.to_string(), payout)?;
+    let hook_msgs =
+        abc_hooks::sell_hook_msgs(ABC_HOOKS, deps.storage, info.sender.clone(), amount, fee)?;
+    let undelegate_msgs = maybe_undelegate_for_liquidity(deps.branch(), &env, &reserve, payout)?;
+
+    let (trade_event, oracle_msgs) = trade_event(
+        deps.storage,
+        env.block.height,
+        env.block.time,
+        "sell",
+        &info.sender,
+        amount,
+        amount,
+        fee,
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "sell")
+        .add_attribute("seller", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("fee", fee)
+        .add_attribute("payout", payout)
+        .add_event(trade_event)
+        .add_message(burn_msg)
+        .add_message(payout_msg)
+        .add_submessages(hook_msgs)
+        .add_submessages(oracle_msgs)
+        .add_messages(undelegate_msgs))
+}
+
+/// Mirror of [`execute_zap_buy`] for selling: same as [`execute_sell`],
+/// except the released reserve is routed through [`ZapConfig::router`]
+/// for `output_denom` instead of paid to the seller directly, with the
+/// swap and refund-on-failure handled by [`ZAP_SELL_REPLY_ID`]'s reply.
+/// Only supports a native reserve, same as [`execute_zap_buy`].
+pub fn execute_zap_sell(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    output_denom: String,
+    min_output: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config =
+        ZAP_CONFIG.may_load(deps.storage)?.flatten().ok_or(ContractError::ZapNotConfigured {})?;
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    if !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let amount = cw_utils::must_pay(&info, &supply_token.denom)?;
+    assert_sell_within_proposal_limit(deps.storage, amount)?;
+
+    let (payout, fee) =
+        process_sell(deps.storage, env.block.height, env.block.time, &info.sender, amount)?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let burn_msg = CosmosMsg::Custom(TokenMsg::BurnTokens {
+        denom: supply_token.denom,
+        amount,
+        burn_from_address: env.contract.address.to_string(),
+    });
+    let hook_msgs =
+        abc_hooks::sell_hook_msgs(ABC_HOOKS, deps.storage, info.sender.clone(), amount, fee)?;
+    let undelegate_msgs = maybe_undelegate_for_liquidity(deps.branch(), &env, &reserve, payout)?;
+
+    let (trade_event, oracle_msgs) = trade_event(
+        deps.storage,
+        env.block.height,
+        env.block.time,
+        "sell",
+        &info.sender,
+        amount,
+        amount,
+        fee,
+    )?;
+
+    let output_balance_before =
+        deps.querier.query_balance(&env.contract.address, output_denom.clone())?.amount;
+    PENDING_ZAP_SELL.save(
+        deps.storage,
+        &PendingZapSell {
+            seller: info.sender.clone(),
+            payout,
+            output_denom: output_denom.clone(),
+            output_balance_before,
+            min_output,
+        },
+    )?;
+    let swap_msg = dex_swap_msg(deps.storage, &reserve, &config.router, payout, output_denom)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "zap_sell")
+        .add_attribute("seller", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("fee", fee)
+        .add_attribute("payout", payout)
+        .add_event(trade_event)
+        .add_message(burn_msg)
+        .add_submessages(hook_msgs)
+        .add_submessages(oracle_msgs)
+        .add_messages(undelegate_msgs)
+        .add_submessage(cosmwasm_std::SubMsg::reply_always(swap_msg, ZAP_SELL_REPLY_ID)))
+}
+
+/// Handles a cw20 `Send` to this contract, dispatching on the embedded
+/// [`ReceiveMsg`]. The `amount`/`sender` come from the cw20 contract
+/// itself, not `info`, so the sending contract must be verified against
+/// the configured reserve or supply cw20 address before trusting them.
+pub fn execute_receive(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive_msg: Cw20ReceiveMsg,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    match cosmwasm_std::from_binary(&receive_msg.msg)? {
+        ReceiveMsg::Buy { stake, referrer, ibc_forward } => {
+            match RESERVE_MODE.load(deps.storage)? {
+                ReserveTokenBacking::Cw20 { address } if address == info.sender => {}
+                _ => return Err(ContractError::Unauthorized {}),
+            }
+            let payment = receive_msg.amount;
+            let buyer = deps.api.addr_validate(&receive_msg.sender)?;
+            let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+
+            // Cw20 buys have no `permit` field (see `ExecuteMsg::Buy`), so
+            // they can never satisfy the hatch allowlist by permit.
+            let (minted, fee, treasury_payout, diversification_swap, referral_payout, hatch_events) =
+                process_buy(deps.storage, env.block.height, payment, &buyer, referrer, false)?;
+
+            let staking_contract = resolve_auto_stake(deps.storage, stake)?;
+            if staking_contract.is_some() && ibc_forward.is_some() {
+                return Err(ContractError::IbcForwardConflictsWithStake {});
+            }
+            if ibc_forward.is_some()
+                && !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory)
+            {
+                return Err(ContractError::IbcForwardRequiresNativeSupply {});
+            }
+            let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+            let mint_recipient = match (&staking_contract, &ibc_forward) {
+                (Some(_), _) | (_, Some(_)) => env.contract.address.to_string(),
+                (None, None) => buyer.to_string(),
+            };
+            let mint_msg = mint_supply_msg(deps.storage, &supply_token, minted, mint_recipient)?;
+            let hook_msgs =
+                abc_hooks::buy_hook_msgs(ABC_HOOKS, deps.storage, buyer.clone(), minted, fee)?;
+
+            let (trade_event, oracle_msgs) = trade_event(
+                deps.storage,
+                env.block.height,
+                env.block.time,
+                "buy",
+                &buyer,
+                payment,
+                minted,
+                fee,
+            )?;
+            let mut response = Response::new()
+                .add_attribute("action", "buy")
+                .add_attribute("buyer", buyer.clone())
+                .add_attribute("payment", payment)
+                .add_attribute("fee", fee)
+                .add_attribute(
+                    "treasury_split",
+                    treasury_payout.as_ref().map(|(_, amount)| *amount).unwrap_or_default(),
+                )
+                .add_attribute(
+                    "diversification_swap",
+                    diversification_swap
+                        .as_ref()
+                        .map(|(_, _, amount)| *amount)
+                        .unwrap_or_default(),
+                )
+                .add_attribute(
+                    "referral_payout",
+                    referral_payout.as_ref().map(|(_, amount)| *amount).unwrap_or_default(),
+                )
+                .add_attribute("minted", minted)
+                .add_attribute("staked", staking_contract.is_some().to_string())
+                .add_attribute("ibc_forwarded", ibc_forward.is_some().to_string())
+                .add_event(trade_event)
+                .add_events(hatch_events)
+                .add_message(mint_msg);
+            if let Some(staking_contract) = &staking_contract {
+                response = response.add_message(auto_stake_msg(
+                    deps.storage,
+                    &supply_token,
+                    staking_contract,
+                    minted,
+                    buyer.to_string(),
+                )?);
+            }
+            if let Some(forward) = ibc_forward {
+                response = response.add_message(CosmosMsg::<TokenFactoryMsg>::Ibc(
+                    IbcMsg::Transfer {
+                        channel_id: forward.channel,
+                        to_address: forward.to_address,
+                        amount: Coin { denom: supply_token.denom.clone(), amount: minted },
+                        timeout: env.block.time.plus_seconds(forward.timeout).into(),
+                    },
+                ));
+            }
+            if let Some((treasury, amount)) = treasury_payout {
+                let reserve = RESERVE.load(deps.storage)?;
+                response = response.add_message(reserve_payout_msg(
+                    deps.storage,
+                    &reserve,
+                    treasury.to_string(),
+                    amount,
+                )?);
+            }
+            if let Some((referrer, amount)) = referral_payout {
+                let reserve = RESERVE.load(deps.storage)?;
+                response = response.add_message(reserve_payout_msg(
+                    deps.storage,
+                    &reserve,
+                    referrer.to_string(),
+                    amount,
+                )?);
+            }
+            if let Some((router, target_denom, amount)) = diversification_swap {
+                let reserve = RESERVE.load(deps.storage)?;
+                let balance_before = deps
+                    .querier
+                    .query_balance(&env.contract.address, target_denom.clone())?
+                    .amount;
+                PENDING_DIVERSIFICATION_SWAP.save(
+                    deps.storage,
+                    &PendingDiversificationSwap { denom: target_denom.clone(), balance_before },
+                )?;
+                let swap_msg =
+                    dex_swap_msg(deps.storage, &reserve, &router, amount, target_denom)?;
+                response = response.add_submessage(cosmwasm_std::SubMsg::reply_on_success(
+                    swap_msg,
+                    DIVERSIFICATION_SWAP_REPLY_ID,
+                ));
+            }
+
+            Ok(response.add_submessages(hook_msgs).add_submessages(oracle_msgs))
+        }
+        ReceiveMsg::Sell {} => {
+            let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+            match SUPPLY_MODE.load(deps.storage)? {
+                SupplyTokenBacking::Cw20 { address } if address == info.sender => {}
+                _ => return Err(ContractError::Unauthorized {}),
+            }
+            let amount = receive_msg.amount;
+            let seller = deps.api.addr_validate(&receive_msg.sender)?;
+            assert_sell_within_proposal_limit(deps.storage, amount)?;
+
+            let (payout, fee) =
+                process_sell(deps.storage, env.block.height, env.block.time, &seller, amount)?;
+
+            let reserve = RESERVE.load(deps.storage)?;
+            let burn_msg = WasmMsg::Execute {
+                contract_addr: supply_token.denom,
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Burn { amount })?,
+                funds: vec![],
+            };
+            let payout_msg =
+                reserve_payout_msg(deps.storage, &reserve, seller.to_string(), payout)?;
+            let hook_msgs =
+                abc_hooks::sell_hook_msgs(ABC_HOOKS, deps.storage, seller.clone(), amount, fee)?;
+            let undelegate_msgs =
+                maybe_undelegate_for_liquidity(deps.branch(), &env, &reserve, payout)?;
+
+            let (trade_event, oracle_msgs) = trade_event(
+                deps.storage,
+                env.block.height,
+                env.block.time,
+                "sell",
+                &seller,
+                amount,
+                amount,
+                fee,
+            )?;
+            Ok(Response::new()
+                .add_attribute("action", "sell")
+                .add_attribute("seller", seller)
+                .add_attribute("amount", amount)
+                .add_attribute("fee", fee)
+                .add_attribute("payout", payout)
+                .add_event(trade_event)
+                .add_message(burn_msg)
+                .add_message(payout_msg)
+                .add_submessages(hook_msgs)
+                .add_submessages(oracle_msgs)
+                .add_messages(undelegate_msgs))
+        }
+        ReceiveMsg::SignalEmergencyClose {} => {
+            match SUPPLY_MODE.load(deps.storage)? {
+                SupplyTokenBacking::Cw20 { address } if address == info.sender => {}
+                _ => return Err(ContractError::Unauthorized {}),
+            }
+            let amount = receive_msg.amount;
+            let signaler = deps.api.addr_validate(&receive_msg.sender)?;
+            apply_signal_emergency_close(deps, env, signaler, amount)
+        }
+        ReceiveMsg::ClaimLegacyBalance {} => {
+            let mut legacy = LEGACY_CURVE
+                .may_load(deps.storage)?
+                .filter(|legacy| legacy.legacy_cw20 == info.sender)
+                .ok_or(ContractError::Unauthorized {})?;
+
+            let claimant = deps.api.addr_validate(&receive_msg.sender)?;
+            let amount = receive_msg.amount;
+            legacy.claimable_supply = legacy
+                .claimable_supply
+                .checked_sub(amount)
+                .map_err(|_| ContractError::InsufficientLegacySupply {})?;
+            LEGACY_CURVE.save(deps.storage, &legacy)?;
+
+            let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+            let mint_msg = mint_supply_msg(deps.storage, &supply_token, amount, claimant.to_string())?;
+
+            Ok(Response::new()
+                .add_attribute("action", "claim_legacy_balance")
+                .add_attribute("claimant", claimant)
+                .add_attribute("amount", amount)
+                .add_message(mint_msg))
+        }
+    }
+}
+
+/// Shaves [`HolderDiscountConfig`]'s largest qualifying tier off
+/// `exit_fee` for `trader`, based on how long ago
+/// [`FIRST_ACQUIRED`] says it first bought in. Returns `exit_fee`
+/// unchanged if no config is set or `trader` has never bought.
+fn holder_discounted_exit_fee(
+    storage: &dyn cosmwasm_std::Storage,
+    trader: &cosmwasm_std::Addr,
+    time: Timestamp,
+    exit_fee: Decimal,
+) -> StdResult<Decimal> {
+    let config = match HOLDER_DISCOUNT_CONFIG.may_load(storage)?.flatten() {
+        Some(config) => config,
+        None => return Ok(exit_fee),
+    };
+    let acquired_at = match FIRST_ACQUIRED.may_load(storage, trader)? {
+        Some(acquired_at) => acquired_at,
+        None => return Ok(exit_fee),
+    };
+    let tenure_seconds = time.seconds().saturating_sub(acquired_at.seconds());
+    let discount = config
+        .tiers
+        .iter()
+        .filter(|tier| tenure_seconds >= tier.min_tenure_seconds)
+        .map(|tier| tier.exit_fee_discount)
+        .max()
+        .unwrap_or_default();
+    Ok(exit_fee * (Decimal::one() - discount))
+}
+
+/// Shared sell-side curve math: burns `amount` of supply out of the curve
+/// state and returns `(payout, fee)` in reserve tokens, discounting the
+/// exit fee for `trader` per [`holder_discounted_exit_fee`]. Callers
+/// still need to issue the actual burn and payout messages, which differ
+/// between token-factory and cw20 supply tokens.
+fn process_sell(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    time: Timestamp,
+    trader: &cosmwasm_std::Addr,
+    amount: Uint128,
+) -> Result<(Uint128, Uint128), ContractError> {
+    assert_trading_not_paused(storage)?;
+    let phase = PHASE.load(storage)?;
+    let exit_fee = match phase {
+        Phase::Hatch => return Err(ContractError::InvalidPhase { current: phase }),
+        Phase::Open => PHASE_CONFIG.load(storage)?.open.exit_fee,
+        Phase::Closed => return Err(ContractError::CommonsClosed {}),
+    };
+    let exit_fee = holder_discounted_exit_fee(storage, trader, time, exit_fee)?;
+
+    let mut curve_state = CURVE_STATE.load(storage)?;
+    let curve = load_curve(storage, curve_state.decimals)?;
+
+    let quote = quote_sell(curve.as_ref(), curve_state.supply, curve_state.reserve, amount)
+        .map_err(StdError::from)?;
+    let released = quote.delta;
+
+    let fee = released * exit_fee;
+    let payout = released.checked_sub(fee)?;
+    let funding_fee = accrue_backstop_fee(storage, fee)?;
+
+    curve_state.supply = quote.new_supply;
+    curve_state.reserve = quote.new_reserve;
+    curve_state.funding = curve_state.funding.checked_add(funding_fee)?;
+    CURVE_STATE.save(storage, &curve_state)?;
+    SUPPLY_SNAPSHOT.save(storage, &curve_state.supply, height)?;
+    RESERVE_SNAPSHOT.save(storage, &curve_state.reserve, height)?;
+
+    Ok((payout, fee))
+}
+
+/// Replaces the whole [`crate::state::PhaseConfig`] wholesale. Callable
+/// by [`Role::CurveAdmin`] or the owner; use
+/// [`execute_update_fees`]/[`execute_update_hatch_allowlist`] instead to
+/// delegate just one slice of it.
+pub fn execute_update_phase_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    phase_config: crate::state::PhaseConfig,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_role_or_owner(deps.storage, Role::CurveAdmin, &info.sender)?;
+    assert_timelock_not_required(deps.storage)?;
+    let sender = info.sender.clone();
+    apply_update_phase_config(deps, &env, &sender, phase_config)
+}
+
+/// The part of [`execute_update_phase_config`] shared with
+/// [`apply_timelocked_action`], which re-checks authorization itself and
+/// must skip [`assert_timelock_not_required`] since it's the timelock
+/// path. This replaces the whole [`crate::state::PhaseConfig`], which
+/// includes the same fee fields [`apply_update_fees`] guards, so it
+/// enforces [`max_fee_rate`] here too and, if any of those fees actually
+/// changed, [`MIN_FEE_UPDATE_INTERVAL_SECONDS`] since the last fee
+/// update -- otherwise a `CurveAdmin` (or the owner) could bypass both
+/// by going through this execute instead of `UpdateFees`.
+fn apply_update_phase_config(
+    deps: DepsMut,
+    env: &Env,
+    changed_by: &cosmwasm_std::Addr,
+    phase_config: crate::state::PhaseConfig,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_frozen(deps.storage)?;
+    let old_phase_config = PHASE_CONFIG.load(deps.storage)?;
+
+    for fee in
+        [phase_config.hatch.entry_fee, phase_config.open.entry_fee, phase_config.open.exit_fee]
+    {
+        if fee > max_fee_rate() {
+            return Err(ContractError::FeeExceedsMax { max: max_fee_rate() });
+        }
+    }
+    let fees_changed = phase_config.hatch.entry_fee != old_phase_config.hatch.entry_fee
+        || phase_config.open.entry_fee != old_phase_config.open.entry_fee
+        || phase_config.open.exit_fee != old_phase_config.open.exit_fee;
+    if fees_changed {
+        if let Some(last_update) = LAST_FEE_UPDATE.may_load(deps.storage)? {
+            let next_allowed = last_update.plus_seconds(MIN_FEE_UPDATE_INTERVAL_SECONDS);
+            if env.block.time < next_allowed {
+                return Err(ContractError::FeeUpdateTooSoon { next_allowed });
+            }
+        }
+        LAST_FEE_UPDATE.save(deps.storage, &env.block.time)?;
+    }
+
+    PHASE_CONFIG.save(deps.storage, &phase_config)?;
+    record_param_change(
+        deps.storage,
+        env,
+        changed_by,
+        "phase_config",
+        Some(to_binary(&old_phase_config)?),
+        Some(to_binary(&phase_config)?),
+    )?;
+    Ok(Response::new().add_attribute("action", "update_phase_config"))
+}
+
+/// Renounces ownership and permanently disables every parameter-changing
+/// execute. There is no way back from this: communities use it to
+/// credibly commit to a fixed curve and fee schedule. Also clears any
+/// guardian-based recovery setup, since a config or proposal left over
+/// from before the freeze would otherwise let guardians hand ownership
+/// to someone after the fact -- reversing the freeze despite the
+/// `assert_not_frozen` guards already on the recovery executes
+/// themselves.
+pub fn execute_freeze(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    assert_not_frozen(deps.storage)?;
+
+    cw_ownable::update_ownership(
+        deps.branch(),
+        &env.block,
+        &info.sender,
+        cw_ownable::Action::RenounceOwnership,
+    )?;
+    FROZEN.save(deps.storage, &true)?;
+    RECOVERY_CONFIG.save(deps.storage, &None)?;
+    PENDING_RECOVERY.save(deps.storage, &None)?;
+
+    Ok(Response::new().add_attribute("action", "freeze"))
+}
+
+/// Transfers token-factory admin rights over the supply denom, e.g. to the
+/// DAO when closing the commons. Without this the denom admin is stuck
+/// with the contract forever, since token factory has no other way to
+/// reassign it.
+pub fn execute_update_denom_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_admin: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+
+    let new_admin = deps.api.addr_validate(&new_admin)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    DENOM_ADMIN.save(deps.storage, &new_admin)?;
+
+    let change_admin = CosmosMsg::Custom(TokenMsg::ChangeAdmin {
+        denom: supply_token.denom,
+        new_admin_address: new_admin.to_string(),
+    });
+
+    Ok(Response::new()
+        .add_attribute("action", "update_denom_admin")
+        .add_attribute("new_admin", new_admin)
+        .add_message(change_admin))
+}
+
+/// The subdenom portion of a token-factory denom cannot exceed this many
+/// characters: the bank module caps the full `factory/<addr>/<subdenom>`
+/// denom at 128 characters, and an instantiator's address alone can run
+/// past 64 of those.
+const MAX_SUBDENOM_LEN: usize = 44;
+
+/// Rejects a `subdenom` that the bank module's token-factory would only
+/// reject later, at `CreateDenom` time (or, for a collision with the
+/// reserve denom, not at all — it would just leave both denoms
+/// indistinguishable in every event and query). Catching this here
+/// returns a specific error instead of an opaque bank-module failure
+/// buried in a submessage.
+fn validate_subdenom(subdenom: &str, reserve_denom: &str) -> Result<(), ContractError> {
+    if subdenom.is_empty() {
+        return Err(ContractError::InvalidSubdenom { reason: "must not be empty".to_string() });
+    }
+    if subdenom.len() > MAX_SUBDENOM_LEN {
+        return Err(ContractError::InvalidSubdenom {
+            reason: format!("must not exceed {MAX_SUBDENOM_LEN} characters"),
+        });
+    }
+    if !subdenom
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return Err(ContractError::InvalidSubdenom {
+            reason: "must only contain ASCII letters, digits, '.', '-', or '_'".to_string(),
+        });
+    }
+    if subdenom == reserve_denom {
+        return Err(ContractError::InvalidSubdenom {
+            reason: "must not collide with the reserve denom".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `metadata`'s `base` matches `expected_base` and that its
+/// `denom_units` are internally consistent: non-empty, and including the
+/// unit named by `display`. Shared by [`execute_update_denom_metadata`]
+/// and instantiation, so user-provided metadata can't reach the bank
+/// module malformed either way.
+fn validate_denom_metadata(metadata: &Metadata, expected_base: &str) -> Result<(), ContractError> {
+    if metadata.base.as_deref() != Some(expected_base) {
+        return Err(ContractError::InvalidDenomMetadata {
+            reason: "base must match the supply denom".to_string(),
+        });
+    }
+    if metadata.denom_units.is_empty() {
+        return Err(ContractError::InvalidDenomMetadata {
+            reason: "at least one denom unit is required".to_string(),
+        });
+    }
+    let display = metadata.display.as_deref().unwrap_or_default();
+    if !metadata.denom_units.iter().any(|u| u.denom == display) {
+        return Err(ContractError::InvalidDenomMetadata {
+            reason: "display unit must be one of the provided denom units".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Corrects the supply denom's bank metadata. Token metadata set at
+/// instantiation can't otherwise be changed, so mistakes (a typo'd
+/// symbol, a missing display unit) would be permanent without this.
+pub fn execute_update_denom_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    metadata: Metadata,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_operator_permission(deps.storage, &info.sender, OPERATOR_PERM_UPDATE_DENOM_METADATA)?;
+    assert_not_frozen(deps.storage)?;
+
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    validate_denom_metadata(&metadata, &supply_token.denom)?;
+
+    let set_metadata = CosmosMsg::Custom(TokenMsg::SetMetadata {
+        denom: supply_token.denom,
+        metadata,
+    });
+
+    Ok(Response::new()
+        .add_attribute("action", "update_denom_metadata")
+        .add_message(set_metadata))
+}
+
+/// The bank metadata to set on the token-factory supply denom at
+/// instantiation: `user_metadata`, validated against `denom` via
+/// [`validate_denom_metadata`], or, if unset, a metadata auto-generated
+/// from `subdenom`/`decimals` — most instantiators otherwise leave
+/// `denom_units` empty, which produces a denom wallets can't display
+/// sensibly.
+fn build_denom_metadata(
+    denom: &str,
+    subdenom: &str,
+    decimals: u8,
+    user_metadata: Option<Metadata>,
+) -> Result<Metadata, ContractError> {
+    match user_metadata {
+        Some(metadata) => {
+            validate_denom_metadata(&metadata, denom)?;
+            Ok(metadata)
+        }
+        None => Ok(Metadata {
+            description: None,
+            base: Some(denom.to_string()),
+            display: Some(subdenom.to_string()),
+            name: Some(subdenom.to_string()),
+            symbol: Some(subdenom.to_ascii_uppercase()),
+            denom_units: vec![
+                DenomUnit { denom: denom.to_string(), exponent: 0, aliases: vec![] },
+                DenomUnit {
+                    denom: subdenom.to_string(),
+                    exponent: decimals as u32,
+                    aliases: vec![],
+                },
+            ],
+        }),
+    }
+}
+
+/// Registers (or clears) the token-factory before-send hook for the
+/// supply denom. Classic ABC hatch tokens should be non-transferable
+/// until the open phase, so hatch-launched commons will typically point
+/// this back at themselves and rely on [`sudo`] to enforce the lock.
+pub fn execute_set_before_send_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract_addr: Option<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    assert_not_frozen(deps.storage)?;
+
+    let contract_addr = contract_addr.map(|a| deps.api.addr_validate(&a)).transpose()?;
+    BEFORE_SEND_HOOK.save(deps.storage, &contract_addr)?;
+
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let set_hook = CosmosMsg::Custom(TokenMsg::SetBeforeSendHook {
+        denom: supply_token.denom,
+        contract_addr: contract_addr.map(|a| a.to_string()).unwrap_or_default(),
+    });
+
+    Ok(Response::new()
+        .add_attribute("action", "set_before_send_hook")
+        .add_message(set_hook))
+}
+
+/// Advances the commons to the next phase. Phases only ever move
+/// forward. Leaving [`Phase::Hatch`] clears any registered before-send
+/// hook, since the transfer lock only applies during the hatch. The
+/// transition is recorded in [`PHASE_TRANSITIONS`] and reported via a
+/// dedicated `abc-phase-transition` event, so callers don't have to infer
+/// phase changes from `Buy`/`Sell` activity.
+pub fn execute_update_phase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_phase: Phase,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if matches!(new_phase, Phase::Closed) {
+        // Closing is always contentious enough to require actual
+        // ownership, never just an [`OPERATOR_PERM_UPDATE_PHASE`] grant.
+        assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+        assert_timelock_not_required(deps.storage)?;
+    } else {
+        assert_operator_permission(deps.storage, &info.sender, OPERATOR_PERM_UPDATE_PHASE)?;
+    }
+    apply_update_phase(deps, env, info, new_phase)
+}
+
+/// The part of [`execute_update_phase`] shared with
+/// [`apply_timelocked_action`], which re-checks authorization itself and
+/// must skip [`assert_timelock_not_required`] since it's the timelock
+/// path.
+fn apply_update_phase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_phase: Phase,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_frozen(deps.storage)?;
+
+    let current = PHASE.load(deps.storage)?;
+    let valid = matches!(
+        (&current, &new_phase),
+        (Phase::Hatch, Phase::Open) | (Phase::Open, Phase::Closed) | (Phase::Hatch, Phase::Closed)
+    );
+    if !valid {
+        return Err(ContractError::InvalidPhaseTransition {
+            current,
+            requested: new_phase,
+        });
+    }
+
+    if matches!(current, Phase::Hatch) {
+        BEFORE_SEND_HOOK.save(deps.storage, &None)?;
+    }
+    PHASE.save(deps.storage, &new_phase)?;
+
+    let transition = PhaseTransition {
+        from: current.clone(),
+        to: new_phase.clone(),
+        trigger: info.sender.clone(),
+        height: env.block.height,
+        time: env.block.time,
+    };
+    let transition_id = PHASE_TRANSITION_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    PHASE_TRANSITIONS.save(deps.storage, transition_id, &transition)?;
+    PHASE_TRANSITION_COUNT.save(deps.storage, &(transition_id + 1))?;
+
+    let hook_msgs = abc_hooks::phase_changed_hook_msgs(
+        ABC_HOOKS,
+        deps.storage,
+        format!("{current:?}"),
+        format!("{new_phase:?}"),
+    )?;
+
+    let transition_event = Event::new("abc-phase-transition")
+        .add_attribute("from", format!("{current:?}"))
+        .add_attribute("to", format!("{new_phase:?}"))
+        .add_attribute("trigger", info.sender)
+        .add_attribute("height", transition.height.to_string())
+        .add_attribute("time", transition.time.seconds().to_string());
+
+    let mut response = Response::new()
+        .add_attribute("action", "update_phase")
+        .add_attribute("new_phase", format!("{new_phase:?}"))
+        .add_event(transition_event)
+        .add_submessages(hook_msgs);
+
+    if matches!((&current, &new_phase), (Phase::Hatch, Phase::Open)) {
+        if let Some((mint_msg, seed_msg)) = maybe_liquidity_seed_msg(deps.storage, &env)? {
+            response = response.add_message(mint_msg).add_message(seed_msg);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Grants or revokes the compliance clawback role. Errors with
+/// [`ContractError::ClawbackDisabled`] if clawback has been permanently
+/// disabled.
+pub fn execute_set_clawback_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    if CLAWBACK_PERMANENTLY_DISABLED.load(deps.storage)? {
+        return Err(ContractError::ClawbackDisabled {});
+    }
+
+    let address = address.map(|a| deps.api.addr_validate(&a)).transpose()?;
+    CLAWBACK_ROLE.save(deps.storage, &address)?;
+
+    Ok(Response::new().add_attribute("action", "set_clawback_role"))
+}
+
+/// Irreversibly gives up the ability to ever enable compliance clawback
+/// again, for communities that want to credibly rule it out.
+pub fn execute_disable_clawback_permanently(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    CLAWBACK_ROLE.save(deps.storage, &None)?;
+    CLAWBACK_PERMANENTLY_DISABLED.save(deps.storage, &true)?;
+    Ok(Response::new().add_attribute("action", "disable_clawback_permanently"))
+}
+
+/// Claws tokens of the supply denom back from a compromised or sanctioned
+/// account. Only usable by the address set via
+/// [`execute_set_clawback_role`], which is unset by default.
+pub fn execute_force_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: String,
+    to: String,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let clawback_role = CLAWBACK_ROLE.load(deps.storage)?;
+    if clawback_role != Some(info.sender) {
+        return Err(ContractError::NotClawbackRole {});
+    }
+
+    let from = deps.api.addr_validate(&from)?;
+    let to = deps.api.addr_validate(&to)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+
+    let force_transfer = CosmosMsg::Custom(TokenMsg::ForceTransfer {
+        denom: supply_token.denom,
+        amount,
+        from_address: from.to_string(),
+        to_address: to.to_string(),
+    });
+
+    Ok(Response::new()
+        .add_attribute("action", "force_transfer")
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("amount", amount)
+        .add_message(force_transfer))
+}
+
+/// Pre-deposits `info.funds` (in the reserve or supply denom) into the
+/// sender's own [`OPERATOR_DEPOSITS`] balance, so a granted operator can
+/// later trade on the sender's behalf via [`execute_sell_for`]/
+/// [`execute_buy_for`]. Only accepts a native reserve/supply denom, since
+/// a cw20 balance can't be credited by a bank-attached deposit.
+pub fn execute_deposit_for_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let coin = cw_utils::one_coin(&info)?;
+    let reserve = RESERVE.load(deps.storage)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+
+    let mut deposit = OPERATOR_DEPOSITS.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    if coin.denom == reserve.denom {
+        if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+            return Err(ContractError::OperatorDepositRequiresNative { denom: coin.denom });
+        }
+        deposit.reserve = deposit.reserve.checked_add(coin.amount)?;
+    } else if coin.denom == supply_token.denom {
+        if !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory) {
+            return Err(ContractError::OperatorDepositRequiresNative { denom: coin.denom });
+        }
+        deposit.supply = deposit.supply.checked_add(coin.amount)?;
+    } else {
+        return Err(ContractError::NotReserveOrSupplyDenom { denom: coin.denom });
+    }
+    OPERATOR_DEPOSITS.save(deps.storage, &info.sender, &deposit)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit_for_operator")
+        .add_attribute("depositor", info.sender)
+        .add_attribute("denom", coin.denom)
+        .add_attribute("amount", coin.amount))
+}
+
+/// Withdraws `amount` of `denom` (the reserve or supply denom) previously
+/// deposited via [`execute_deposit_for_operator`] back to the sender.
+/// Unaffected by any outstanding [`OPERATOR_GRANTS`] limit, which only
+/// caps how much an operator may spend, not how much stays reserved.
+pub fn execute_withdraw_operator_deposit(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let reserve = RESERVE.load(deps.storage)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let mut deposit = OPERATOR_DEPOSITS.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+
+    let balance = if denom == reserve.denom {
+        &mut deposit.reserve
+    } else if denom == supply_token.denom {
+        &mut deposit.supply
+    } else {
+        return Err(ContractError::NotReserveOrSupplyDenom { denom });
+    };
+    if amount > *balance {
+        return Err(ContractError::InsufficientOperatorDeposit { amount, balance: *balance });
+    }
+    *balance -= amount;
+    OPERATOR_DEPOSITS.save(deps.storage, &info.sender, &deposit)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_operator_deposit")
+        .add_attribute("depositor", info.sender.clone())
+        .add_attribute("denom", denom.clone())
+        .add_attribute("amount", amount)
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![cosmwasm_std::coin(amount.u128(), denom)],
+        }))
+}
+
+/// Authorizes `operator` to spend the sender's [`OPERATOR_DEPOSITS`]
+/// balance via [`execute_sell_for`]/[`execute_buy_for`]. Overwrites any
+/// existing grant to the same operator rather than adding to it.
+pub fn execute_grant(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+    sell_limit: Option<Uint128>,
+    buy_limit: Option<Uint128>,
+    expires_at: Option<Timestamp>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    OPERATOR_GRANTS.save(
+        deps.storage,
+        (&info.sender, &operator),
+        &OperatorGrant { sell_limit, buy_limit, expires_at },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "grant")
+        .add_attribute("owner", info.sender)
+        .add_attribute("operator", operator))
+}
+
+/// Revokes a grant previously issued via [`execute_grant`]. A no-op if
+/// `operator` wasn't granted in the first place.
+pub fn execute_revoke(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    OPERATOR_GRANTS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("owner", info.sender)
+        .add_attribute("operator", operator))
+}
+
+/// Which side of [`OPERATOR_GRANTS`]' limits [`debit_operator_grant`]
+/// checks and decrements.
+enum OperatorAction {
+    Sell,
+    Buy,
+}
+
+/// Shared bookkeeping for [`execute_sell_for`]/[`execute_buy_for`]: checks
+/// that `owner` has granted `operator` (and that the grant hasn't
+/// expired), decrements the relevant limit by `amount`, and debits
+/// `amount` from `owner`'s [`OPERATOR_DEPOSITS`] balance.
+fn debit_operator_grant(
+    storage: &mut dyn cosmwasm_std::Storage,
+    time: Timestamp,
+    owner: &cosmwasm_std::Addr,
+    operator: &cosmwasm_std::Addr,
+    action: OperatorAction,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let mut grant = OPERATOR_GRANTS
+        .may_load(storage, (owner, operator))?
+        .ok_or_else(|| ContractError::NotGrantedOperator { owner: owner.to_string() })?;
+    if let Some(expires_at) = grant.expires_at {
+        if time >= expires_at {
+            return Err(ContractError::OperatorGrantExpired { expired_at: expires_at });
+        }
+    }
+    let limit = match action {
+        OperatorAction::Sell => &mut grant.sell_limit,
+        OperatorAction::Buy => &mut grant.buy_limit,
+    };
+    if let Some(remaining) = *limit {
+        if amount > remaining {
+            return Err(ContractError::OperatorLimitExceeded { amount, limit: remaining });
+        }
+        *limit = Some(remaining - amount);
+    }
+    OPERATOR_GRANTS.save(storage, (owner, operator), &grant)?;
+
+    let mut deposit = OPERATOR_DEPOSITS.may_load(storage, owner)?.unwrap_or_default();
+    let balance = match action {
+        OperatorAction::Sell => &mut deposit.supply,
+        OperatorAction::Buy => &mut deposit.reserve,
+    };
+    if amount > *balance {
+        return Err(ContractError::InsufficientOperatorDeposit { amount, balance: *balance });
+    }
+    *balance -= amount;
+    OPERATOR_DEPOSITS.save(storage, owner, &deposit)?;
+    Ok(())
+}
+
+/// Sells `amount` of `owner`'s pre-deposited supply balance on their
+/// behalf, by replaying [`execute_sell`] with a synthetic
+/// [`MessageInfo`] carrying `owner` as sender and `amount` of the supply
+/// denom as funds — the tokens are already held by this contract from
+/// [`execute_deposit_for_operator`], the same way a zap-buy reply
+/// replays [`buy_impl`] with the swap's proceeds.
+pub fn execute_sell_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    debit_operator_grant(
+        deps.storage,
+        env.block.time,
+        &owner,
+        &info.sender,
+        OperatorAction::Sell,
+        amount,
+    )?;
+
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let sell_info = MessageInfo {
+        sender: owner,
+        funds: vec![cosmwasm_std::coin(amount.u128(), supply_token.denom)],
+    };
+    let response = execute_sell(deps, env, sell_info)?;
+    Ok(response.add_attribute("operator", info.sender))
+}
+
+/// Mirror of [`execute_sell_for`] for buying: replays [`buy_impl`] with a
+/// synthetic [`MessageInfo`] carrying `owner` as sender and `amount` of
+/// the reserve denom as funds, drawn from `owner`'s pre-deposited
+/// [`OPERATOR_DEPOSITS`] balance. No auto-stake, referrer, IBC
+/// forwarding, or idempotency key; use [`execute_buy`] directly for
+/// those.
+pub fn execute_buy_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    debit_operator_grant(
+        deps.storage,
+        env.block.time,
+        &owner,
+        &info.sender,
+        OperatorAction::Buy,
+        amount,
+    )?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let buy_info = MessageInfo {
+        sender: owner.clone(),
+        funds: vec![cosmwasm_std::coin(amount.u128(), reserve.denom)],
+    };
+    let response = buy_impl(deps, env, buy_info, owner, None, None, None, None, None)?;
+    Ok(response.add_attribute("operator", info.sender))
+}
+
+/// Validates `info.funds` as an optional single deposit of `denom`,
+/// returning zero if none was attached. Unlike [`cw_utils::one_coin`],
+/// which requires exactly one coin, this lets
+/// [`ExecuteMsg::SetRecurringPurchase`] reconfigure a schedule without
+/// necessarily depositing more funds in the same call.
+fn optional_single_deposit(info: &MessageInfo, denom: &str) -> Result<Uint128, ContractError> {
+    if info.funds.is_empty() {
+        return Ok(Uint128::zero());
+    }
+    let coin = cw_utils::one_coin(info)?;
+    if coin.denom != denom {
+        return Err(ContractError::UnsupportedReserveDenom { denom: coin.denom });
+    }
+    Ok(coin.amount)
+}
+
+/// Sets up (or, if the sender already has one, reconfigures) a recurring
+/// purchase. `info.funds`, if any, top up the deposit funding it via
+/// [`optional_single_deposit`]; an existing order's `deposited` and
+/// `next_due` otherwise carry over unchanged. Only supported for a
+/// native reserve, like [`ExecuteMsg::Buy`].
+pub fn execute_set_recurring_purchase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount_per_interval: Uint128,
+    interval_seconds: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let reserve = RESERVE.load(deps.storage)?;
+    let deposit = optional_single_deposit(&info, &reserve.denom)?;
+
+    let mut order =
+        RECURRING_ORDERS.may_load(deps.storage, &info.sender)?.unwrap_or(RecurringOrder {
+            amount_per_interval,
+            interval_seconds,
+            deposited: Uint128::zero(),
+            next_due: env.block.time.plus_seconds(interval_seconds),
+        });
+    order.amount_per_interval = amount_per_interval;
+    order.interval_seconds = interval_seconds;
+    order.deposited += deposit;
+    RECURRING_ORDERS.save(deps.storage, &info.sender, &order)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_recurring_purchase")
+        .add_attribute("amount_per_interval", amount_per_interval)
+        .add_attribute("interval_seconds", interval_seconds.to_string())
+        .add_attribute("deposited", order.deposited))
+}
+
+/// Tops up the sender's existing recurring purchase deposit with
+/// `info.funds`, without changing its schedule.
+pub fn execute_deposit_recurring_purchase(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let reserve = RESERVE.load(deps.storage)?;
+    let coin = cw_utils::one_coin(&info)?;
+    if coin.denom != reserve.denom {
+        return Err(ContractError::UnsupportedReserveDenom { denom: coin.denom });
+    }
+    let mut order = RECURRING_ORDERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NoRecurringPurchase {})?;
+    order.deposited += coin.amount;
+    RECURRING_ORDERS.save(deps.storage, &info.sender, &order)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit_recurring_purchase")
+        .add_attribute("deposited", order.deposited))
+}
+
+/// Withdraws `amount` of the sender's deposited, not yet spent
+/// recurring-purchase balance, without cancelling the order or touching its schedule.
+pub fn execute_withdraw_recurring_purchase(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let mut order = RECURRING_ORDERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NoRecurringPurchase {})?;
+    if amount > order.deposited {
+        return Err(ContractError::InsufficientRecurringDeposit {
+            amount,
+            deposited: order.deposited,
+        });
+    }
+    order.deposited -= amount;
+    RECURRING_ORDERS.save(deps.storage, &info.sender, &order)?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let payout_msg = reserve_payout_msg(deps.storage, &reserve, info.sender.to_string(), amount)?;
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_recurring_purchase")
+        .add_attribute("amount", amount)
+        .add_message(payout_msg))
+}
+
+/// Cancels the sender's recurring purchase outright and refunds whatever
+/// of its deposit hadn't yet been spent.
+pub fn execute_cancel_recurring_purchase(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let order = RECURRING_ORDERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NoRecurringPurchase {})?;
+    RECURRING_ORDERS.remove(deps.storage, &info.sender);
+
+    let mut response = Response::new()
+        .add_attribute("action", "cancel_recurring_purchase")
+        .add_attribute("refunded", order.deposited);
+    if !order.deposited.is_zero() {
+        let reserve = RESERVE.load(deps.storage)?;
+        response = response.add_message(reserve_payout_msg(
+            deps.storage,
+            &reserve,
+            info.sender.to_string(),
+            order.deposited,
+        )?);
+    }
+    Ok(response)
+}
+
+/// Permissionless: buys `amount_per_interval` for every recurring
+/// purchase whose `next_due` has passed and whose deposit can still
+/// cover it (up to `limit`, in ascending owner-address order), by replaying
+/// [`buy_impl`] with a synthetic [`MessageInfo`] naming the order's owner
+/// as sender, the same trick [`execute_buy_for`] uses. A due order with
+/// too little deposit left is skipped rather than erroring, so one
+/// underfunded order can't block the rest of the crank; it starts being
+/// processed again once topped up via
+/// [`ExecuteMsg::DepositRecurringPurchase`].
+pub fn execute_crank_recurring_purchases(
+    mut deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let limit = limit.unwrap_or(10).min(30) as usize;
+    let due: Vec<(cosmwasm_std::Addr, RecurringOrder)> = RECURRING_ORDERS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, order)| {
+            env.block.time >= order.next_due && order.deposited >= order.amount_per_interval
+        })
+        .take(limit)
+        .collect();
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let mut response = Response::new().add_attribute("action", "crank_recurring_purchases");
+    for (owner, mut order) in due {
+        order.deposited -= order.amount_per_interval;
+        order.next_due = order.next_due.plus_seconds(order.interval_seconds);
+        RECURRING_ORDERS.save(deps.storage, &owner, &order)?;
+
+        let buy_info = MessageInfo {
+            sender: owner.clone(),
+            funds: vec![cosmwasm_std::coin(
+                order.amount_per_interval.u128(),
+                reserve.denom.clone(),
+            )],
+        };
+        let buy_response = buy_impl(
+            deps.branch(),
+            env.clone(),
+            buy_info,
+            owner.clone(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        let minted = buy_response
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "minted")
+            .map(|attr| attr.value.clone())
+            .unwrap_or_default();
+        response = response
+            .add_submessages(buy_response.messages)
+            .add_events(buy_response.events)
+            .add_event(
+                Event::new("abc-recurring-buy")
+                    .add_attribute("owner", owner)
+                    .add_attribute("minted", minted),
+            );
+    }
+    Ok(response)
+}
+
+/// Places a resting order, escrowing `info.funds` until
+/// [`execute_crank_limit_orders`] matches it, [`execute_cancel_limit_order`]
+/// cancels it, or it expires. `side: Buy` escrows the reserve denom
+/// (only supported when the reserve is native, like [`execute_buy`]);
+/// `side: Sell` escrows the supply denom (only supported when the
+/// supply is a token-factory denom, like [`execute_sell`]).
+pub fn execute_place_limit_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    side: LimitOrderSide,
+    threshold_price: Decimal,
+    expires_at: Timestamp,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let amount = match &side {
+        LimitOrderSide::Buy => {
+            if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+                return Err(ContractError::Unauthorized {});
+            }
+            let reserve = RESERVE.load(deps.storage)?;
+            cw_utils::must_pay(&info, &reserve.denom)?
+        }
+        LimitOrderSide::Sell => {
+            if !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory) {
+                return Err(ContractError::Unauthorized {});
+            }
+            let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+            cw_utils::must_pay(&info, &supply_token.denom)?
+        }
+    };
+
+    let order_id = LIMIT_ORDER_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    LIMIT_ORDERS.save(
+        deps.storage,
+        order_id,
+        &LimitOrder { owner: info.sender.clone(), side, threshold_price, amount, expires_at },
+    )?;
+    LIMIT_ORDER_COUNT.save(deps.storage, &(order_id + 1))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "place_limit_order")
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("owner", info.sender)
+        .add_attribute("amount", amount))
+}
+
+/// Refunds a limit order's escrow to its owner: the reserve denom for a
+/// `Buy` order, the supply denom for a `Sell` order. Both are bank
+/// denoms (a native reserve and a token-factory supply, the only modes
+/// [`execute_place_limit_order`] accepts), so this is always a plain
+/// `BankMsg::Send`.
+fn limit_order_refund_msg(
+    storage: &dyn cosmwasm_std::Storage,
+    order: &LimitOrder,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    let denom = match &order.side {
+        LimitOrderSide::Buy => RESERVE.load(storage)?.denom,
+        LimitOrderSide::Sell => SUPPLY_DENOM.load(storage)?.denom,
+    };
+    Ok(BankMsg::Send {
+        to_address: order.owner.to_string(),
+        amount: vec![cosmwasm_std::coin(order.amount.u128(), denom)],
+    }
+    .into())
+}
+
+/// Cancels `order_id`, refunding its escrow to the owner. Only callable
+/// by the order's owner.
+pub fn execute_cancel_limit_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let order =
+        LIMIT_ORDERS.may_load(deps.storage, order_id)?.ok_or(ContractError::LimitOrderNotFound {
+            order_id,
+        })?;
+    if order.owner != info.sender {
+        return Err(ContractError::NotLimitOrderOwner { order_id });
+    }
+    LIMIT_ORDERS.remove(deps.storage, order_id);
+
+    let refund_msg = limit_order_refund_msg(deps.storage, &order)?;
+    Ok(Response::new()
+        .add_attribute("action", "cancel_limit_order")
+        .add_attribute("order_id", order_id.to_string())
+        .add_message(refund_msg))
+}
+
+/// Permissionless: matches every resting order (up to `limit`, lowest
+/// id first) whose side condition the current spot price satisfies,
+/// replaying [`buy_impl`]/[`execute_sell`] with the escrowed funds and
+/// crediting the result to the order's owner the same way
+/// [`execute_buy_for`]/[`execute_sell_for`] do. An expired order is
+/// dropped and refunded instead of matched, whether or not its price
+/// condition is met, so a stale order can't be sniped after it should
+/// have lapsed.
+pub fn execute_crank_limit_orders(
+    mut deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let limit = limit.unwrap_or(10).min(30) as usize;
+    let curve_state = CURVE_STATE.load(deps.storage)?;
+    let curve = load_curve(deps.storage, curve_state.decimals)?;
+    let spot_price = curve.spot_price(curve_state.supply);
+
+    let candidates: Vec<(u64, LimitOrder)> = LIMIT_ORDERS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, order)| {
+            env.block.time >= order.expires_at
+                || match &order.side {
+                    LimitOrderSide::Buy => spot_price <= order.threshold_price,
+                    LimitOrderSide::Sell => spot_price >= order.threshold_price,
+                }
+        })
+        .take(limit)
+        .collect();
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let mut response = Response::new().add_attribute("action", "crank_limit_orders");
+    for (order_id, order) in candidates {
+        LIMIT_ORDERS.remove(deps.storage, order_id);
+
+        if env.block.time >= order.expires_at {
+            let refund_msg = limit_order_refund_msg(deps.storage, &order)?;
+            response = response.add_message(refund_msg).add_event(
+                Event::new("abc-limit-order-expired")
+                    .add_attribute("order_id", order_id.to_string())
+                    .add_attribute("owner", order.owner),
+            );
+            continue;
+        }
+
+        match order.side {
+            LimitOrderSide::Buy => {
+                let buy_info = MessageInfo {
+                    sender: order.owner.clone(),
+                    funds: vec![cosmwasm_std::coin(order.amount.u128(), reserve.denom.clone())],
+                };
+                let buy_response = buy_impl(
+                    deps.branch(),
+                    env.clone(),
+                    buy_info,
+                    order.owner.clone(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )?;
+                let minted = buy_response
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == "minted")
+                    .map(|attr| attr.value.clone())
+                    .unwrap_or_default();
+                response = response
+                    .add_submessages(buy_response.messages)
+                    .add_events(buy_response.events)
+                    .add_event(
+                        Event::new("abc-limit-order-matched")
+                            .add_attribute("order_id", order_id.to_string())
+                            .add_attribute("owner", order.owner)
+                            .add_attribute("minted", minted),
+                    );
+            }
+            LimitOrderSide::Sell => {
+                let sell_info = MessageInfo {
+                    sender: order.owner.clone(),
+                    funds: vec![cosmwasm_std::coin(
+                        order.amount.u128(),
+                        supply_token.denom.clone(),
+                    )],
+                };
+                let sell_response = execute_sell(deps.branch(), env.clone(), sell_info)?;
+                let payout = sell_response
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == "payout")
+                    .map(|attr| attr.value.clone())
+                    .unwrap_or_default();
+                response = response
+                    .add_submessages(sell_response.messages)
+                    .add_events(sell_response.events)
+                    .add_event(
+                        Event::new("abc-limit-order-matched")
+                            .add_attribute("order_id", order_id.to_string())
+                            .add_attribute("owner", order.owner)
+                            .add_attribute("payout", payout),
+                    );
+            }
+        }
+    }
+    Ok(response)
+}
+
+/// Permissionless: once the phase has moved past `Hatch`, clears
+/// [`BATCH_AUCTION_STATE`]'s pooled hatch-phase contributions at a
+/// single clearing price and mints each contributor's pro-rata share.
+/// The first call to settle a given pool quotes the curve once for
+/// [`BatchAuctionState::total_pool`] as if it were a single buy — the
+/// same math [`process_buy`] would otherwise run per-buy — and records
+/// [`BatchAuctionState::total_minted`]; every call after that (this one
+/// included) mints up to `limit` contributors their
+/// `total_minted * contributed / total_pool` share and removes them
+/// from [`BATCH_CONTRIBUTIONS`], so a hatch with more contributors than
+/// fit in one transaction can be settled over several calls. A no-op,
+/// not an error, once the pool is empty or was never enabled.
+pub fn execute_settle_batch_auction(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_trading_not_paused(deps.storage)?;
+    if matches!(PHASE.load(deps.storage)?, Phase::Hatch) {
+        return Err(ContractError::BatchAuctionStillOpen {});
+    }
+    let limit = limit.unwrap_or(30).min(100) as usize;
+    let mut batch_state = BATCH_AUCTION_STATE.may_load(deps.storage)?.unwrap_or_default();
+    let mut response = Response::new().add_attribute("action", "settle_batch_auction");
+
+    if batch_state.total_minted.is_none() {
+        let total_minted = if batch_state.total_pool.is_zero() {
+            Uint128::zero()
+        } else {
+            let mut curve_state = CURVE_STATE.load(deps.storage)?;
+            let curve = load_curve(deps.storage, curve_state.decimals)?;
+            let quote = quote_buy(
+                curve.as_ref(),
+                curve_state.supply,
+                curve_state.reserve,
+                batch_state.total_pool,
+            )
+            .map_err(StdError::from)?;
+            curve_state.reserve = quote.new_reserve;
+            curve_state.supply = quote.new_supply;
+            CURVE_STATE.save(deps.storage, &curve_state)?;
+            SUPPLY_SNAPSHOT.save(deps.storage, &curve_state.supply, env.block.height)?;
+            RESERVE_SNAPSHOT.save(deps.storage, &curve_state.reserve, env.block.height)?;
+            quote.delta
+        };
+        batch_state.total_minted = Some(total_minted);
+        BATCH_AUCTION_STATE.save(deps.storage, &batch_state)?;
+        response = response
+            .add_attribute("clearing_pool", batch_state.total_pool)
+            .add_attribute("clearing_minted", total_minted);
+    }
+    let total_minted = batch_state.total_minted.unwrap_or_default();
+
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let contributions: Vec<(cosmwasm_std::Addr, Uint128)> = BATCH_CONTRIBUTIONS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .take(limit)
+        .collect();
+    for (contributor, contributed) in contributions {
+        BATCH_CONTRIBUTIONS.remove(deps.storage, &contributor);
+        let share = total_minted.multiply_ratio(contributed, batch_state.total_pool);
+        let mint_msg =
+            mint_supply_msg(deps.storage, &supply_token, share, contributor.to_string())?;
+        response = response.add_message(mint_msg).add_event(
+            Event::new("abc-batch-auction-settled")
+                .add_attribute("contributor", contributor)
+                .add_attribute("contributed", contributed)
+                .add_attribute("minted", share),
+        );
+    }
+    Ok(response)
+}
+
+/// Starts a streaming buy: `info.funds` (validated with
+/// [`cw_utils::must_pay`]) vest into supply tokens linearly over
+/// `duration_seconds`, cranked in slices by
+/// [`execute_crank_streaming_buys`]. Only one streaming buy per address
+/// at a time. Only supported for a native reserve, like
+/// [`execute_buy`].
+pub fn execute_set_streaming_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    duration_seconds: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if STREAMING_BUYS.has(deps.storage, &info.sender) {
+        return Err(ContractError::StreamingBuyAlreadyActive {});
+    }
+    let reserve = RESERVE.load(deps.storage)?;
+    let total_amount = cw_utils::must_pay(&info, &reserve.denom)?;
+
+    let order = StreamingBuy {
+        total_amount,
+        start_time: env.block.time,
+        duration_seconds,
+        converted: Uint128::zero(),
+    };
+    STREAMING_BUYS.save(deps.storage, &info.sender, &order)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_streaming_buy")
+        .add_attribute("total_amount", total_amount)
+        .add_attribute("duration_seconds", duration_seconds.to_string()))
+}
+
+/// Cancels the sender's streaming buy outright and refunds whatever of
+/// its deposit hasn't vested yet.
+pub fn execute_cancel_streaming_buy(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let order = STREAMING_BUYS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NoStreamingBuy {})?;
+    STREAMING_BUYS.remove(deps.storage, &info.sender);
+
+    let remaining = order.total_amount.saturating_sub(order.converted);
+    let mut response = Response::new()
+        .add_attribute("action", "cancel_streaming_buy")
+        .add_attribute("refunded", remaining);
+    if !remaining.is_zero() {
+        let reserve = RESERVE.load(deps.storage)?;
+        response = response.add_message(reserve_payout_msg(
+            deps.storage,
+            &reserve,
+            info.sender.to_string(),
+            remaining,
+        )?);
+    }
+    Ok(response)
+}
+
+/// Permissionless: for every streaming buy (up to `limit`, in
+/// ascending owner-address order) that hasn't fully vested and whose
+/// `start_time` has passed, buys `total_amount * elapsed /
+/// duration_seconds`, less whatever has already converted, by replaying
+/// [`buy_impl`] with a synthetic [`MessageInfo`] naming the order's
+/// owner as sender, the same trick [`execute_crank_recurring_purchases`]
+/// uses. A stream that reaches `total_amount` is removed instead of
+/// left in storage.
+pub fn execute_crank_streaming_buys(
+    mut deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let limit = limit.unwrap_or(10).min(30) as usize;
+    let due: Vec<(cosmwasm_std::Addr, StreamingBuy)> = STREAMING_BUYS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, order)| {
+            env.block.time >= order.start_time && order.converted < order.total_amount
+        })
+        .take(limit)
+        .collect();
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let mut response = Response::new().add_attribute("action", "crank_streaming_buys");
+    for (owner, mut order) in due {
+        let elapsed = env.block.time.seconds().saturating_sub(order.start_time.seconds());
+        let vested = if elapsed >= order.duration_seconds {
+            order.total_amount
+        } else {
+            order.total_amount.multiply_ratio(elapsed, order.duration_seconds)
+        };
+        let amount = vested.saturating_sub(order.converted);
+        if amount.is_zero() {
+            continue;
+        }
+        order.converted += amount;
+        if order.converted >= order.total_amount {
+            STREAMING_BUYS.remove(deps.storage, &owner);
+        } else {
+            STREAMING_BUYS.save(deps.storage, &owner, &order)?;
+        }
+
+        let buy_info = MessageInfo {
+            sender: owner.clone(),
+            funds: vec![cosmwasm_std::coin(amount.u128(), reserve.denom.clone())],
+        };
+        let buy_response = buy_impl(
+            deps.branch(),
+            env.clone(),
+            buy_info,
+            owner.clone(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        let minted = buy_response
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "minted")
+            .map(|attr| attr.value.clone())
+            .unwrap_or_default();
+        response = response
+            .add_submessages(buy_response.messages)
+            .add_events(buy_response.events)
+            .add_event(
+                Event::new("abc-streaming-buy")
+                    .add_attribute("owner", owner)
+                    .add_attribute("amount", amount)
+                    .add_attribute("minted", minted),
+            );
+    }
+    Ok(response)
+}
+
+/// Opens a crowdfunded buy pool with a `target` amount of the native
+/// reserve, keyed by an incrementing counter the same way
+/// [`execute_place_limit_order`] keys resting orders. Only supported
+/// for a native reserve, since [`execute_settle_buy_pool`] replays the
+/// same curve math [`execute_buy`] does.
+pub fn execute_open_buy_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    target: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let pool_id = BUY_POOL_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    BUY_POOLS.save(
+        deps.storage,
+        pool_id,
+        &BuyPool {
+            initiator: info.sender.clone(),
+            target,
+            total_pooled: Uint128::zero(),
+            total_minted: None,
+        },
+    )?;
+    BUY_POOL_COUNT.save(deps.storage, &(pool_id + 1))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "open_buy_pool")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("initiator", info.sender)
+        .add_attribute("target", target))
+}
+
+/// Contributes `info.funds` to `pool_id`, tracked per-address in
+/// [`BUY_POOL_CONTRIBUTIONS`] so [`execute_settle_buy_pool`] knows how
+/// to split the eventual mint.
+pub fn execute_join_buy_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_id: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let mut pool = BUY_POOLS
+        .may_load(deps.storage, pool_id)?
+        .ok_or(ContractError::BuyPoolNotFound { pool_id })?;
+    if pool.total_minted.is_some() {
+        return Err(ContractError::BuyPoolAlreadySettled { pool_id });
+    }
+    let reserve = RESERVE.load(deps.storage)?;
+    let amount = cw_utils::must_pay(&info, &reserve.denom)?;
+
+    pool.total_pooled = pool.total_pooled.checked_add(amount)?;
+    BUY_POOLS.save(deps.storage, pool_id, &pool)?;
+    let contributed = BUY_POOL_CONTRIBUTIONS
+        .may_load(deps.storage, (pool_id, &info.sender))?
+        .unwrap_or_default();
+    BUY_POOL_CONTRIBUTIONS.save(
+        deps.storage,
+        (pool_id, &info.sender),
+        &contributed.checked_add(amount)?,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "join_buy_pool")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("contributor", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("total_pooled", pool.total_pooled))
+}
+
+/// Withdraws the sender's contribution to `pool_id` and refunds it, as
+/// long as the pool hasn't been settled yet.
+pub fn execute_cancel_buy_pool_contribution(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_id: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let mut pool = BUY_POOLS
+        .may_load(deps.storage, pool_id)?
+        .ok_or(ContractError::BuyPoolNotFound { pool_id })?;
+    if pool.total_minted.is_some() {
+        return Err(ContractError::BuyPoolAlreadySettled { pool_id });
+    }
+    let contributed = BUY_POOL_CONTRIBUTIONS
+        .may_load(deps.storage, (pool_id, &info.sender))?
+        .ok_or(ContractError::NoBuyPoolContribution { pool_id })?;
+    BUY_POOL_CONTRIBUTIONS.remove(deps.storage, (pool_id, &info.sender));
+    pool.total_pooled = pool.total_pooled.checked_sub(contributed)?;
+    BUY_POOLS.save(deps.storage, pool_id, &pool)?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let payout_msg =
+        reserve_payout_msg(deps.storage, &reserve, info.sender.to_string(), contributed)?;
+    Ok(Response::new()
+        .add_attribute("action", "cancel_buy_pool_contribution")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("refunded", contributed)
+        .add_message(payout_msg))
+}
+
+/// Permissionless: once `pool_id`'s `total_pooled` has reached its
+/// `target`, quotes the curve once for the whole pool (fixing a single
+/// clearing price for every contributor, computed the first time this
+/// is called) and mints each contributor's pro-rata share, up to
+/// `limit` contributors per call so a pool with more contributors than
+/// fit in one transaction can be settled over several, the same way
+/// [`execute_settle_batch_auction`] settles a pooled hatch.
+pub fn execute_settle_buy_pool(
+    deps: DepsMut,
+    env: Env,
+    pool_id: u64,
+    limit: Option<u32>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_trading_not_paused(deps.storage)?;
+    let mut pool = BUY_POOLS
+        .may_load(deps.storage, pool_id)?
+        .ok_or(ContractError::BuyPoolNotFound { pool_id })?;
+    if pool.total_minted.is_none() && pool.total_pooled < pool.target {
+        return Err(ContractError::BuyPoolTargetNotMet {
+            pool_id,
+            target: pool.target,
+            total_pooled: pool.total_pooled,
+        });
+    }
+    let limit = limit.unwrap_or(30).min(100) as usize;
+    let mut response = Response::new()
+        .add_attribute("action", "settle_buy_pool")
+        .add_attribute("pool_id", pool_id.to_string());
+
+    if pool.total_minted.is_none() {
+        let mut curve_state = CURVE_STATE.load(deps.storage)?;
+        let curve = load_curve(deps.storage, curve_state.decimals)?;
+        let quote =
+            quote_buy(curve.as_ref(), curve_state.supply, curve_state.reserve, pool.total_pooled)
+                .map_err(StdError::from)?;
+        curve_state.reserve = quote.new_reserve;
+        curve_state.supply = quote.new_supply;
+        CURVE_STATE.save(deps.storage, &curve_state)?;
+        SUPPLY_SNAPSHOT.save(deps.storage, &curve_state.supply, env.block.height)?;
+        RESERVE_SNAPSHOT.save(deps.storage, &curve_state.reserve, env.block.height)?;
+        pool.total_minted = Some(quote.delta);
+        BUY_POOLS.save(deps.storage, pool_id, &pool)?;
+        response = response
+            .add_attribute("clearing_pool", pool.total_pooled)
+            .add_attribute("clearing_minted", quote.delta);
+    }
+    let total_minted = pool.total_minted.unwrap_or_default();
+
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let contributions: Vec<(cosmwasm_std::Addr, Uint128)> = BUY_POOL_CONTRIBUTIONS
+        .prefix(pool_id)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .take(limit)
+        .collect();
+    for (contributor, contributed) in contributions {
+        BUY_POOL_CONTRIBUTIONS.remove(deps.storage, (pool_id, &contributor));
+        let share = total_minted.multiply_ratio(contributed, pool.total_pooled);
+        let mint_msg =
+            mint_supply_msg(deps.storage, &supply_token, share, contributor.to_string())?;
+        response = response.add_message(mint_msg).add_event(
+            Event::new("abc-buy-pool-settled")
+                .add_attribute("pool_id", pool_id.to_string())
+                .add_attribute("contributor", contributor)
+                .add_attribute("contributed", contributed)
+                .add_attribute("minted", share),
+        );
+    }
+    Ok(response)
+}
+
+/// Commits an OTC block trade: escrows `info.funds` and records it for
+/// [`execute_crank_block_trades`] to execute later at the TWAP over
+/// `delay_blocks`, instead of the curve's marginal price right now.
+/// Restricted to the `Open` phase, like [`execute_sell`], since the
+/// hatch phase's allowlist/cap/contribution-limit checks don't have an
+/// obvious meaning for a trade that settles later at an unknown price.
+pub fn execute_commit_block_trade(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    side: LimitOrderSide,
+    delay_blocks: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(PHASE.load(deps.storage)?, Phase::Open) {
+        return Err(ContractError::BlockTradesRequireOpenPhase {});
+    }
+    let amount = match &side {
+        LimitOrderSide::Buy => {
+            if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+                return Err(ContractError::Unauthorized {});
+            }
+            let reserve = RESERVE.load(deps.storage)?;
+            cw_utils::must_pay(&info, &reserve.denom)?
+        }
+        LimitOrderSide::Sell => {
+            if !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory) {
+                return Err(ContractError::Unauthorized {});
+            }
+            let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+            cw_utils::must_pay(&info, &supply_token.denom)?
+        }
+    };
+
+    let trade_id = BLOCK_TRADE_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    BLOCK_TRADES.save(
+        deps.storage,
+        trade_id,
+        &BlockTrade {
+            owner: info.sender.clone(),
+            side,
+            amount,
+            committed_at_height: env.block.height,
+            delay_blocks,
+        },
+    )?;
+    BLOCK_TRADE_COUNT.save(deps.storage, &(trade_id + 1))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit_block_trade")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("owner", info.sender)
+        .add_attribute("amount", amount))
+}
+
+/// Refunds a committed block trade's escrow to its owner, the same way
+/// [`limit_order_refund_msg`] does for a limit order.
+fn block_trade_refund_msg(
+    storage: &dyn cosmwasm_std::Storage,
+    trade: &BlockTrade,
+) -> Result<CosmosMsg<TokenFactoryMsg>, ContractError> {
+    let denom = match &trade.side {
+        LimitOrderSide::Buy => RESERVE.load(storage)?.denom,
+        LimitOrderSide::Sell => SUPPLY_DENOM.load(storage)?.denom,
+    };
+    Ok(BankMsg::Send {
+        to_address: trade.owner.to_string(),
+        amount: vec![cosmwasm_std::coin(trade.amount.u128(), denom)],
+    }
+    .into())
+}
+
+/// Cancels `trade_id`, refunding its escrow to the owner. Only callable
+/// before it's cranked, and only by the trade's owner.
+pub fn execute_cancel_block_trade(
+    deps: DepsMut,
+    info: MessageInfo,
+    trade_id: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let trade = BLOCK_TRADES
+        .may_load(deps.storage, trade_id)?
+        .ok_or(ContractError::BlockTradeNotFound { trade_id })?;
+    if trade.owner != info.sender {
+        return Err(ContractError::NotBlockTradeOwner { trade_id });
+    }
+    BLOCK_TRADES.remove(deps.storage, trade_id);
+
+    let refund_msg = block_trade_refund_msg(deps.storage, &trade)?;
+    Ok(Response::new()
+        .add_attribute("action", "cancel_block_trade")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_message(refund_msg))
+}
+
+/// Converts `payment` reserve atomics into supply atomics at the
+/// whole-unit price `twap`, normalizing atomics to whole units and back
+/// through [`DecimalPlaces`] the same way
+/// [`crate::curves::ConstantCurve::supply`] does for its own (marginal,
+/// not TWAP) price.
+fn twap_to_supply(decimals: &DecimalPlaces, payment: Uint128, twap: Decimal) -> Uint128 {
+    let normalized = Decimal::new(payment) / Decimal::new(decimals.to_reserve(twap));
+    decimals.to_supply(normalized)
+}
+
+/// Converts `amount` supply atomics into reserve atomics at the
+/// whole-unit price `twap`, the sell-side mirror of [`twap_to_supply`].
+fn twap_to_reserve(decimals: &DecimalPlaces, amount: Uint128, twap: Decimal) -> Uint128 {
+    let normalized = Decimal::new(amount) / Decimal::new(decimals.to_supply(Decimal::one()));
+    decimals.to_reserve(normalized * twap)
+}
+
+/// The buy side of [`execute_crank_block_trades`]: applies the `Open`
+/// phase's entry fee and [`accrue_backstop_fee`] like [`process_buy`]
+/// does, but mints `payment` at the TWAP price `twap` instead of
+/// quoting [`crate::curves::quote_buy`] against the curve's current
+/// state.
+fn process_block_buy(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    payment: Uint128,
+    twap: Decimal,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let phase_config = PHASE_CONFIG.load(storage)?;
+    let fee = payment * phase_config.open.entry_fee;
+    let funding_fee = accrue_backstop_fee(storage, fee)?;
+    let net_payment = payment.checked_sub(fee)?;
+
+    let mut curve_state = CURVE_STATE.load(storage)?;
+    let minted = twap_to_supply(&curve_state.decimals, net_payment, twap);
+    curve_state.reserve = curve_state.reserve.checked_add(net_payment)?;
+    curve_state.supply = curve_state.supply.checked_add(minted)?;
+    curve_state.funding = curve_state.funding.checked_add(funding_fee)?;
+    CURVE_STATE.save(storage, &curve_state)?;
+    SUPPLY_SNAPSHOT.save(storage, &curve_state.supply, height)?;
+    RESERVE_SNAPSHOT.save(storage, &curve_state.reserve, height)?;
+
+    Ok((minted, fee))
+}
+
+/// The sell side of [`execute_crank_block_trades`]: applies the `Open`
+/// phase's exit fee and [`accrue_backstop_fee`] like [`process_sell`]
+/// does, but releases `amount` at the TWAP price `twap` instead of
+/// quoting [`crate::curves::quote_sell`] against the curve's current
+/// state.
+fn process_block_sell(
+    storage: &mut dyn cosmwasm_std::Storage,
+    height: u64,
+    amount: Uint128,
+    twap: Decimal,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let phase_config = PHASE_CONFIG.load(storage)?;
+    let mut curve_state = CURVE_STATE.load(storage)?;
+
+    let released = twap_to_reserve(&curve_state.decimals, amount, twap);
+    let fee = released * phase_config.open.exit_fee;
+    let payout = released.checked_sub(fee)?;
+    let funding_fee = accrue_backstop_fee(storage, fee)?;
+
+    curve_state.supply = curve_state.supply.checked_sub(amount)?;
+    curve_state.reserve = curve_state.reserve.checked_sub(released)?;
+    curve_state.funding = curve_state.funding.checked_add(funding_fee)?;
+    CURVE_STATE.save(storage, &curve_state)?;
+    SUPPLY_SNAPSHOT.save(storage, &curve_state.supply, height)?;
+    RESERVE_SNAPSHOT.save(storage, &curve_state.reserve, height)?;
+
+    Ok((payout, fee))
+}
+
+/// Permissionless: executes every committed block trade (up to `limit`,
+/// lowest id first) whose `delay_blocks` have elapsed since
+/// `committed_at_height`, pricing it at [`query_twap`] over the last
+/// `delay_blocks` blocks up to the crank's own height rather than the
+/// curve's marginal price. That is the window right after the commit
+/// only when the trade is cranked as soon as it comes due; a later
+/// crank averages over a later window of the same length. The whole point of
+/// the feature is settling a large trade at an averaged price it can't
+/// move by itself, so [`process_block_buy`]/[`process_block_sell`]
+/// deliberately bypass [`crate::curves::quote_buy`]/
+/// [`crate::curves::quote_sell`] and mutate [`CURVE_STATE`] straight
+/// from the TWAP quote. Still recorded through [`trade_event`], so it
+/// shows up in volume/candle/account-stats history and future TWAP
+/// windows like any other trade. If the phase has moved past `Open` by
+/// the time a trade comes due, it's refunded instead of executed.
+pub fn execute_crank_block_trades(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_trading_not_paused(deps.storage)?;
+    let limit = limit.unwrap_or(10).min(30) as usize;
+
+    let candidates: Vec<(u64, BlockTrade)> = BLOCK_TRADES
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, trade)| env.block.height >= trade.committed_at_height + trade.delay_blocks)
+        .take(limit)
+        .collect();
+
+    let mut response = Response::new().add_attribute("action", "crank_block_trades");
+    for (trade_id, trade) in candidates {
+        BLOCK_TRADES.remove(deps.storage, trade_id);
+
+        if !matches!(PHASE.load(deps.storage)?, Phase::Open) {
+            let refund_msg = block_trade_refund_msg(deps.storage, &trade)?;
+            response = response.add_message(refund_msg).add_event(
+                Event::new("abc-block-trade-refunded")
+                    .add_attribute("trade_id", trade_id.to_string())
+                    .add_attribute("owner", trade.owner),
+            );
+            continue;
+        }
+
+        let twap = query_twap(deps.as_ref(), env.clone(), trade.delay_blocks)?;
+        let (settle_msg, kind, minted_or_burned, fee) = match trade.side {
+            LimitOrderSide::Buy => {
+                let (minted, fee) =
+                    process_block_buy(deps.storage, env.block.height, trade.amount, twap)?;
+                let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+                let mint_msg =
+                    mint_supply_msg(deps.storage, &supply_token, minted, trade.owner.to_string())?;
+                (mint_msg, "buy", minted, fee)
+            }
+            LimitOrderSide::Sell => {
+                let (payout, fee) =
+                    process_block_sell(deps.storage, env.block.height, trade.amount, twap)?;
+                let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+                let burn_msg = CosmosMsg::Custom(TokenMsg::BurnTokens {
+                    denom: supply_token.denom,
+                    amount: trade.amount,
+                    burn_from_address: env.contract.address.to_string(),
+                });
+                response = response.add_message(burn_msg);
+                let reserve = RESERVE.load(deps.storage)?;
+                let payout_msg =
+                    reserve_payout_msg(deps.storage, &reserve, trade.owner.to_string(), payout)?;
+                (payout_msg, "sell", trade.amount, fee)
+            }
+        };
+
+        let (trade_evt, oracle_msgs) = trade_event(
+            deps.storage,
+            env.block.height,
+            env.block.time,
+            kind,
+            &trade.owner,
+            trade.amount,
+            minted_or_burned,
+            fee,
+        )?;
+        response = response
+            .add_message(settle_msg)
+            .add_submessages(oracle_msgs)
+            .add_event(trade_evt)
+            .add_event(
+                Event::new("abc-block-trade-executed")
+                    .add_attribute("trade_id", trade_id.to_string())
+                    .add_attribute("owner", trade.owner)
+                    .add_attribute("twap", twap.to_string()),
+            );
+    }
+    Ok(response)
+}
+
+/// Sets (or, with `config: None`, clears) the bonus offered on
+/// [`ExecuteMsg::BuyWithLockup`].
+pub fn execute_set_lockup_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<LockupConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<LockupConfig, ContractError> {
+            if config.bonus_percent.is_zero() {
+                return Err(ContractError::InvalidLockupBonusPercent {});
+            }
+            Ok(LockupConfig {
+                min_duration_seconds: config.min_duration_seconds,
+                bonus_percent: config.bonus_percent,
+                remaining_bonus_budget: config.remaining_bonus_budget,
+            })
+        })
+        .transpose()?;
+    LOCKUP_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_lockup_config"))
+}
+
+/// Buys with `info.funds` like [`execute_buy`], but instead of minting
+/// to the caller, mints the curve-quoted amount plus a
+/// [`LockupConfig::bonus_percent`] bonus to the contract itself and
+/// records a [`Lockup`] the buyer can [`execute_claim_lockup`] once
+/// `duration_seconds` has passed. Uses [`process_buy`] directly, the
+/// same lower-level function [`buy_impl`] calls internally, since
+/// staking/referrer/ibc_forward don't apply to a buy that isn't minting
+/// to the buyer yet.
+pub fn execute_buy_with_lockup(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    duration_seconds: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let mut config = LOCKUP_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::LockupNotConfigured {})?;
+    if duration_seconds < config.min_duration_seconds {
+        return Err(ContractError::LockupDurationTooShort {
+            requested: duration_seconds,
+            minimum: config.min_duration_seconds,
+        });
+    }
+
+    if !matches!(RESERVE_MODE.load(deps.storage)?, ReserveTokenBacking::Native) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let reserve = RESERVE.load(deps.storage)?;
+    let payment = cw_utils::must_pay(&info, &reserve.denom)?;
+
+    let (minted, fee, treasury_payout, diversification_swap, _referral_payout, hatch_events) =
+        process_buy(deps.storage, env.block.height, payment, &info.sender, None, false)?;
+
+    let bonus = minted * config.bonus_percent;
+    if bonus > config.remaining_bonus_budget {
+        return Err(ContractError::LockupBudgetExhausted {});
+    }
+    config.remaining_bonus_budget = config.remaining_bonus_budget.checked_sub(bonus)?;
+    LOCKUP_CONFIG.save(deps.storage, &Some(config))?;
+
+    let locked_amount = minted.checked_add(bonus)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let mint_msg = mint_supply_msg(
+        deps.storage,
+        &supply_token,
+        locked_amount,
+        env.contract.address.to_string(),
+    )?;
+
+    let lockup_id = LOCKUP_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    let unlocks_at = env.block.time.plus_seconds(duration_seconds);
+    LOCKUPS.save(
+        deps.storage,
+        lockup_id,
+        &Lockup { owner: info.sender.clone(), amount: locked_amount, unlocks_at },
+    )?;
+    LOCKUP_COUNT.save(deps.storage, &(lockup_id + 1))?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "buy_with_lockup")
+        .add_attribute("buyer", info.sender.clone())
+        .add_attribute("lockup_id", lockup_id.to_string())
+        .add_attribute("minted", minted)
+        .add_attribute("bonus", bonus)
+        .add_attribute("unlocks_at", unlocks_at.to_string())
+        .add_message(mint_msg)
+        .add_events(hatch_events);
+
+    if let Some((treasury, amount)) = treasury_payout {
+        let payout_msg = reserve_payout_msg(deps.storage, &reserve, treasury.to_string(), amount)?;
+        response = response.add_message(payout_msg);
+    }
+    if let Some((router, target_denom, amount)) = diversification_swap {
+        let balance_before =
+            deps.querier.query_balance(&env.contract.address, target_denom.clone())?.amount;
+        PENDING_DIVERSIFICATION_SWAP.save(
+            deps.storage,
+            &PendingDiversificationSwap { denom: target_denom.clone(), balance_before },
+        )?;
+        let swap_msg = dex_swap_msg(deps.storage, &reserve, &router, amount, target_denom)?;
+        response = response.add_submessage(cosmwasm_std::SubMsg::reply_on_success(
+            swap_msg,
+            DIVERSIFICATION_SWAP_REPLY_ID,
+        ));
+    }
+
+    let (trade_evt, oracle_msgs) = trade_event(
+        deps.storage,
+        env.block.height,
+        env.block.time,
+        "buy",
+        &info.sender,
+        payment,
+        minted,
+        fee,
+    )?;
+    Ok(response.add_event(trade_evt).add_submessages(oracle_msgs))
+}
+
+/// Sends `lockup_id`'s full locked amount to its owner. Only callable
+/// once [`Lockup::unlocks_at`] has passed, and only by the lock's
+/// owner.
+pub fn execute_claim_lockup(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lockup_id: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let lockup = LOCKUPS
+        .may_load(deps.storage, lockup_id)?
+        .ok_or(ContractError::LockupNotFound { lockup_id })?;
+    if lockup.owner != info.sender {
+        return Err(ContractError::NotLockupOwner { lockup_id });
+    }
+    if env.block.time < lockup.unlocks_at {
+        return Err(ContractError::LockupNotYetUnlocked {
+            lockup_id,
+            unlocks_at: lockup.unlocks_at,
+        });
+    }
+    LOCKUPS.remove(deps.storage, lockup_id);
+
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let payout_msg: CosmosMsg<TokenFactoryMsg> = match SUPPLY_MODE.load(deps.storage)? {
+        SupplyTokenBacking::TokenFactory => BankMsg::Send {
+            to_address: lockup.owner.to_string(),
+            amount: vec![cosmwasm_std::coin(lockup.amount.u128(), supply_token.denom)],
+        }
+        .into(),
+        SupplyTokenBacking::Cw20 { address } => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: lockup.owner.to_string(),
+                amount: lockup.amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_lockup")
+        .add_attribute("lockup_id", lockup_id.to_string())
+        .add_attribute("owner", lockup.owner)
+        .add_attribute("amount", lockup.amount)
+        .add_message(payout_msg))
+}
+
+pub fn execute_set_holder_discount_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<HolderDiscountConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let config = config
+        .map(|config| -> Result<HolderDiscountConfig, ContractError> {
+            for tier in &config.tiers {
+                if tier.exit_fee_discount.is_zero() || tier.exit_fee_discount > Decimal::one() {
+                    return Err(ContractError::InvalidHolderDiscount {});
+                }
+            }
+            let sorted = config
+                .tiers
+                .windows(2)
+                .all(|pair| pair[0].min_tenure_seconds < pair[1].min_tenure_seconds);
+            if !sorted {
+                return Err(ContractError::HolderDiscountTiersNotSorted {});
+            }
+            Ok(HolderDiscountConfig { tiers: config.tiers })
+        })
+        .transpose()?;
+    HOLDER_DISCOUNT_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().add_attribute("action", "set_holder_discount_config"))
+}
+
+/// Passes if `sender` is the owner (including via a configured
+/// [`PolytoneProxyConfig`]) or holds `role` via [`ROLES`]; otherwise
+/// [`ContractError::MissingRole`].
+fn assert_role_or_owner(
+    storage: &dyn cosmwasm_std::Storage,
+    role: Role,
+    sender: &cosmwasm_std::Addr,
+) -> Result<(), ContractError> {
+    if assert_owner_or_polytone_proxy(storage, sender).is_ok() {
+        return Ok(());
+    }
+    if ROLES.has(storage, (role.discriminant(), sender)) {
+        return Ok(());
+    }
+    Err(ContractError::MissingRole { role })
+}
+
+/// Owner-only: grants `role` to `address`, so operational duties like
+/// pausing trading, tuning fees, or managing the hatch allowlist can be
+/// delegated without handing out the owner's key. Idempotent.
+pub fn execute_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.save(deps.storage, (role.discriminant(), &address), &cosmwasm_std::Empty {})?;
+    Ok(Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", format!("{role:?}"))
+        .add_attribute("address", address))
+}
+
+/// Owner-only: the inverse of [`execute_grant_role`]. Idempotent.
+pub fn execute_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.remove(deps.storage, (role.discriminant(), &address));
+    Ok(Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", format!("{role:?}"))
+        .add_attribute("address", address))
+}
+
+/// Callable by [`Role::Pauser`] or the owner. See [`TRADING_PAUSED`] for
+/// exactly what pausing does and doesn't cover.
+pub fn execute_set_trading_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_role_or_owner(deps.storage, Role::Pauser, &info.sender)?;
+    let was_paused = TRADING_PAUSED.may_load(deps.storage)?.unwrap_or(false);
+    TRADING_PAUSED.save(deps.storage, &paused)?;
+    record_param_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "trading_paused",
+        Some(to_binary(&was_paused)?),
+        Some(to_binary(&paused)?),
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "set_trading_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+/// Callable by [`Role::FeeAdmin`] or the owner. Patches only the fee
+/// fields that are `Some`, leaving the rest of [`crate::state::PhaseConfig`]
+/// untouched.
+pub fn execute_update_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    hatch_entry_fee: Option<Decimal>,
+    open_entry_fee: Option<Decimal>,
+    open_exit_fee: Option<Decimal>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_role_or_owner(deps.storage, Role::FeeAdmin, &info.sender)?;
+    assert_timelock_not_required(deps.storage)?;
+    let sender = info.sender.clone();
+    apply_update_fees(deps, env, &sender, hatch_entry_fee, open_entry_fee, open_exit_fee)
+}
+
+/// The part of [`execute_update_fees`] shared with
+/// [`apply_timelocked_action`], which re-checks authorization itself and
+/// must skip [`assert_timelock_not_required`] since it's the timelock
+/// path. Enforces [`MAX_FEE_RATE`] and [`MIN_FEE_UPDATE_INTERVAL_SECONDS`]
+/// unconditionally, on both paths, so neither a compromised owner nor a
+/// compromised [`Role::FeeAdmin`] can push an extreme fee or ratchet fees
+/// up repeatedly within a single block of trading.
+fn apply_update_fees(
+    deps: DepsMut,
+    env: Env,
+    changed_by: &cosmwasm_std::Addr,
+    hatch_entry_fee: Option<Decimal>,
+    open_entry_fee: Option<Decimal>,
+    open_exit_fee: Option<Decimal>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_frozen(deps.storage)?;
+    for fee in [hatch_entry_fee, open_entry_fee, open_exit_fee].into_iter().flatten() {
+        if fee > max_fee_rate() {
+            return Err(ContractError::FeeExceedsMax { max: max_fee_rate() });
+        }
+    }
+    if let Some(last_update) = LAST_FEE_UPDATE.may_load(deps.storage)? {
+        let next_allowed = last_update.plus_seconds(MIN_FEE_UPDATE_INTERVAL_SECONDS);
+        if env.block.time < next_allowed {
+            return Err(ContractError::FeeUpdateTooSoon { next_allowed });
+        }
+    }
+
+    let old_phase_config = PHASE_CONFIG.load(deps.storage)?;
+    let mut phase_config = old_phase_config.clone();
+    if let Some(fee) = hatch_entry_fee {
+        phase_config.hatch.entry_fee = fee;
+    }
+    if let Some(fee) = open_entry_fee {
+        phase_config.open.entry_fee = fee;
+    }
+    if let Some(fee) = open_exit_fee {
+        phase_config.open.exit_fee = fee;
+    }
+    PHASE_CONFIG.save(deps.storage, &phase_config)?;
+    LAST_FEE_UPDATE.save(deps.storage, &env.block.time)?;
+    record_param_change(
+        deps.storage,
+        &env,
+        changed_by,
+        "fees",
+        Some(to_binary(&old_phase_config)?),
+        Some(to_binary(&phase_config)?),
+    )?;
+    Ok(Response::new().add_attribute("action", "update_fees"))
+}
+
+/// Callable by [`Role::AllowlistManager`] or the owner. Errors if
+/// [`crate::state::HatchConfig::allowlist`] isn't already `Some`.
+pub fn execute_update_hatch_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_role_or_owner(deps.storage, Role::AllowlistManager, &info.sender)?;
+    assert_not_frozen(deps.storage)?;
+    let mut phase_config = PHASE_CONFIG.load(deps.storage)?;
+    let mut allowlist =
+        phase_config.hatch.allowlist.take().ok_or(ContractError::AllowlistNotConfigured {})?;
+    for address in add {
+        let address = deps.api.addr_validate(&address)?;
+        if !allowlist.contains(&address) {
+            allowlist.push(address);
+        }
+    }
+    for address in remove {
+        let address = deps.api.addr_validate(&address)?;
+        allowlist.retain(|a| *a != address);
+    }
+    phase_config.hatch.allowlist = Some(allowlist);
+    PHASE_CONFIG.save(deps.storage, &phase_config)?;
+    Ok(Response::new().add_attribute("action", "update_hatch_allowlist"))
+}
+
+/// Passes if `sender` is the owner (including via a configured
+/// [`crate::state::PolytoneProxyConfig`]) or is the configured
+/// [`MaintenanceOperator`] with `permission` set in its bitmask;
+/// otherwise [`ContractError::NotMaintenanceOperator`].
+fn assert_operator_permission(
+    storage: &dyn cosmwasm_std::Storage,
+    sender: &cosmwasm_std::Addr,
+    permission: u8,
+) -> Result<(), ContractError> {
+    if assert_owner_or_polytone_proxy(storage, sender).is_ok() {
+        return Ok(());
+    }
+    let has_permission = MAINTENANCE_OPERATOR
+        .may_load(storage)?
+        .flatten()
+        .map(|op| op.operator == *sender && op.permissions & permission != 0)
+        .unwrap_or(false);
+    if has_permission {
+        return Ok(());
+    }
+    Err(ContractError::NotMaintenanceOperator {})
+}
+
+/// Owner-only. `None` revokes the maintenance operator entirely.
+pub fn execute_set_maintenance_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: Option<MaintenanceOperatorMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let old_operator = MAINTENANCE_OPERATOR.may_load(deps.storage)?.flatten();
+    let operator = operator
+        .map(|operator| -> Result<MaintenanceOperator, ContractError> {
+            Ok(MaintenanceOperator {
+                operator: deps.api.addr_validate(&operator.operator)?,
+                permissions: operator.permissions,
+            })
+        })
+        .transpose()?;
+    MAINTENANCE_OPERATOR.save(deps.storage, &operator)?;
+    record_param_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "maintenance_operator",
+        old_operator.map(|o| to_binary(&o)).transpose()?,
+        operator.map(|o| to_binary(&o)).transpose()?,
+    )?;
+    Ok(Response::new().add_attribute("action", "set_maintenance_operator"))
+}
+
+/// Errs if a [`TimelockConfig`] is set, meaning this action must be
+/// queued via [`ExecuteMsg::QueueTimelockedAction`] and run via
+/// [`ExecuteMsg::ExecuteTimelockedAction`] instead of issued directly.
+fn assert_timelock_not_required(storage: &dyn cosmwasm_std::Storage) -> Result<(), ContractError> {
+    if TIMELOCK_CONFIG.may_load(storage)?.flatten().is_some() {
+        return Err(ContractError::TimelockRequired {});
+    }
+    Ok(())
+}
+
+/// Owner-only. `None` disables the timelock and leaves
+/// [`ExecuteMsg::UpdateFees`], [`ExecuteMsg::UpdatePhaseConfig`], and
+/// closing the commons immediate again, same as before this feature
+/// existed.
+pub fn execute_set_timelock_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Option<TimelockConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let old_config = TIMELOCK_CONFIG.may_load(deps.storage)?.flatten();
+    let config = config
+        .map(|config| -> Result<TimelockConfig, ContractError> {
+            if config.delay_seconds == 0 {
+                return Err(ContractError::InvalidTimelockDelay {});
+            }
+            Ok(TimelockConfig { delay_seconds: config.delay_seconds })
+        })
+        .transpose()?;
+    TIMELOCK_CONFIG.save(deps.storage, &config)?;
+    record_param_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "timelock_config",
+        old_config.map(|c| to_binary(&c)).transpose()?,
+        config.map(|c| to_binary(&c)).transpose()?,
+    )?;
+    Ok(Response::new().add_attribute("action", "set_timelock_config"))
+}
+
+/// Queues `action` for execution once [`TimelockConfig::delay_seconds`]
+/// has elapsed, requiring the same authorization the action would need
+/// if issued directly. Errors if no timelock is configured.
+pub fn execute_queue_timelocked_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: TimelockedAction,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config = TIMELOCK_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::TimelockNotConfigured {})?;
+
+    match &action {
+        TimelockedAction::UpdateFees { .. } => {
+            assert_role_or_owner(deps.storage, Role::FeeAdmin, &info.sender)?
+        }
+        TimelockedAction::UpdatePhaseConfig { .. } => {
+            assert_role_or_owner(deps.storage, Role::CurveAdmin, &info.sender)?
+        }
+        TimelockedAction::Close => assert_owner_or_polytone_proxy(deps.storage, &info.sender)?,
+    }
+
+    let execute_after = env.block.time.plus_seconds(config.delay_seconds);
+    let id = TIMELOCK_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    PENDING_TIMELOCKS.save(
+        deps.storage,
+        id,
+        &PendingTimelock {
+            action,
+            queued_by: info.sender,
+            queued_at: env.block.time,
+            execute_after,
+        },
+    )?;
+    TIMELOCK_COUNT.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "queue_timelocked_action")
+        .add_attribute("id", id.to_string())
+        .add_attribute("execute_after", execute_after.seconds().to_string()))
+}
+
+/// Applies a [`PendingTimelock`]'s action by replaying the same execute
+/// it would have run directly, with `info.sender` set to whoever queued
+/// it -- so if their authorization has since lapsed (e.g. the owner
+/// changed, or a role was revoked), the action fails here rather than
+/// running anyway.
+fn apply_timelocked_action(
+    deps: DepsMut,
+    env: &Env,
+    queued_by: cosmwasm_std::Addr,
+    action: TimelockedAction,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let info = MessageInfo { sender: queued_by, funds: vec![] };
+    match action {
+        TimelockedAction::UpdateFees { hatch_entry_fee, open_entry_fee, open_exit_fee } => {
+            assert_role_or_owner(deps.storage, Role::FeeAdmin, &info.sender)?;
+            apply_update_fees(
+                deps,
+                env.clone(),
+                &info.sender,
+                hatch_entry_fee,
+                open_entry_fee,
+                open_exit_fee,
+            )
+        }
+        TimelockedAction::UpdatePhaseConfig { phase_config } => {
+            assert_role_or_owner(deps.storage, Role::CurveAdmin, &info.sender)?;
+            apply_update_phase_config(deps, env, &info.sender, phase_config)
+        }
+        TimelockedAction::Close => {
+            assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+            apply_update_phase(deps, env.clone(), info, Phase::Closed)
+        }
+    }
+}
+
+/// Runs a queued action once its delay has elapsed. Permissionless, like
+/// the other `Crank*` executes: the action was already authorized at
+/// queue time, and [`apply_timelocked_action`] re-checks it against
+/// whoever queued it.
+pub fn execute_run_timelocked_action(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let pending = PENDING_TIMELOCKS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::TimelockNotFound { id })?;
+    if env.block.time < pending.execute_after {
+        return Err(ContractError::TimelockNotReady { execute_after: pending.execute_after });
+    }
+    PENDING_TIMELOCKS.remove(deps.storage, id);
+    let response = apply_timelocked_action(deps, &env, pending.queued_by, pending.action)?;
+    Ok(response.add_attribute("timelock_id", id.to_string()))
+}
+
+/// Cancels a queued action before it executes -- the "cancel window" is
+/// the whole delay, from the moment it's queued until
+/// [`execute_run_timelocked_action`] actually runs it. Requires the same
+/// authorization the action itself would.
+pub fn execute_cancel_timelocked_action(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let pending = PENDING_TIMELOCKS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::TimelockNotFound { id })?;
+    match &pending.action {
+        TimelockedAction::UpdateFees { .. } => {
+            assert_role_or_owner(deps.storage, Role::FeeAdmin, &info.sender)?
+        }
+        TimelockedAction::UpdatePhaseConfig { .. } => {
+            assert_role_or_owner(deps.storage, Role::CurveAdmin, &info.sender)?
+        }
+        TimelockedAction::Close => assert_owner_or_polytone_proxy(deps.storage, &info.sender)?,
+    }
+    PENDING_TIMELOCKS.remove(deps.storage, id);
+    Ok(Response::new()
+        .add_attribute("action", "cancel_timelocked_action")
+        .add_attribute("id", id.to_string()))
+}
+
+/// Owner-only. Sets or clears the veto address checked by
+/// [`execute_veto_timelocked_action`].
+pub fn execute_set_veto_address(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Option<VetoConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let old_config = VETO_CONFIG.may_load(deps.storage)?.flatten();
+    let config = config
+        .map(|c| -> Result<_, ContractError> {
+            Ok(VetoConfig {
+                veto: deps.api.addr_validate(&c.veto)?,
+                window_seconds: c.window_seconds,
+            })
+        })
+        .transpose()?;
+    VETO_CONFIG.save(deps.storage, &config)?;
+    record_param_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "veto_config",
+        old_config.map(|c| to_binary(&c)).transpose()?,
+        config.map(|c| to_binary(&c)).transpose()?,
+    )?;
+    Ok(Response::new().add_attribute("action", "set_veto_address"))
+}
+
+/// Errors unless `sender` is the owner or the configured veto address.
+fn assert_veto(
+    storage: &dyn cosmwasm_std::Storage,
+    sender: &cosmwasm_std::Addr,
+) -> Result<(), ContractError> {
+    if assert_owner_or_polytone_proxy(storage, sender).is_ok() {
+        return Ok(());
+    }
+    let is_veto = VETO_CONFIG
+        .may_load(storage)?
+        .flatten()
+        .map(|c| c.veto == *sender)
+        .unwrap_or(false);
+    if is_veto {
+        return Ok(());
+    }
+    Err(ContractError::NotVetoAddress {})
+}
+
+/// Cancels a pending [`TimelockedAction::Close`] within the configured
+/// veto window, recording `reason` on-chain. This fork's phases only
+/// ever move forward and `Closed` is terminal, so a queued re-open has
+/// no equivalent here to veto -- `Close` is the only queued phase
+/// transition that exists.
+pub fn execute_veto_timelocked_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    reason: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_veto(deps.storage, &info.sender)?;
+    let config = VETO_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::VetoNotConfigured {})?;
+    let pending = PENDING_TIMELOCKS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::TimelockNotFound { id })?;
+    if !matches!(pending.action, TimelockedAction::Close) {
+        return Err(ContractError::VetoNotApplicable { id });
+    }
+    if env.block.time > pending.queued_at.plus_seconds(config.window_seconds) {
+        return Err(ContractError::VetoWindowExpired { id });
+    }
+    PENDING_TIMELOCKS.remove(deps.storage, id);
+    Ok(Response::new()
+        .add_attribute("action", "veto_timelocked_action")
+        .add_attribute("id", id.to_string())
+        .add_attribute("reason", reason))
+}
+
+/// Owner-only. Sets or disables the token-weighted emergency closure
+/// vote. `quorum_ratio` must be in `(0, 1]`, `window_seconds` nonzero.
+pub fn execute_set_emergency_close_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Option<EmergencyCloseConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let old_config = EMERGENCY_CLOSE_CONFIG.may_load(deps.storage)?.flatten();
+    let config = config
+        .map(|c| {
+            if c.quorum_ratio.is_zero() || c.quorum_ratio > Decimal::one() || c.window_seconds == 0
+            {
+                return Err(ContractError::InvalidEmergencyCloseConfig {});
+            }
+            Ok(EmergencyCloseConfig {
+                quorum_ratio: c.quorum_ratio,
+                window_seconds: c.window_seconds,
+            })
+        })
+        .transpose()?;
+    EMERGENCY_CLOSE_CONFIG.save(deps.storage, &config)?;
+    record_param_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "emergency_close_config",
+        old_config.map(|c| to_binary(&c)).transpose()?,
+        config.map(|c| to_binary(&c)).transpose()?,
+    )?;
+    Ok(Response::new().add_attribute("action", "set_emergency_close_config"))
+}
+
+/// Deposits the attached supply tokens as a signal for emergency
+/// closure, when the supply token is token-factory-backed. See
+/// [`ReceiveMsg::SignalEmergencyClose`] for the cw20 equivalent; both
+/// funnel into [`apply_signal_emergency_close`].
+pub fn execute_signal_emergency_close(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if !matches!(SUPPLY_MODE.load(deps.storage)?, SupplyTokenBacking::TokenFactory) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let amount = cw_utils::must_pay(&info, &supply_token.denom)?;
+    let signaler = info.sender;
+    apply_signal_emergency_close(deps, env, signaler, amount)
+}
+
+/// The part of [`execute_signal_emergency_close`] shared with the
+/// [`ReceiveMsg::SignalEmergencyClose`] cw20 path, once each has
+/// checked authorization and pulled `amount` from its own transport. If
+/// a round's `window_seconds` elapses without reaching quorum, the next
+/// signal starts a fresh round rather than counting toward the stale
+/// one. Reaching quorum transitions to [`Phase::Closed`] in the same
+/// call, with `env.contract.address` recorded as the transition's
+/// trigger since no single signaler tips it over on their own.
+fn apply_signal_emergency_close(
+    mut deps: DepsMut,
+    env: Env,
+    signaler: cosmwasm_std::Addr,
+    amount: Uint128,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let config = EMERGENCY_CLOSE_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::EmergencyCloseNotConfigured {})?;
+    if matches!(PHASE.load(deps.storage)?, Phase::Closed) {
+        return Err(ContractError::AlreadyClosed {});
+    }
+
+    let mut round = EMERGENCY_CLOSE_ROUND.may_load(deps.storage)?.unwrap_or_default();
+    let window_start = EMERGENCY_CLOSE_WINDOW_START.may_load(deps.storage)?;
+    let expired = window_start
+        .map(|start| env.block.time > start.plus_seconds(config.window_seconds))
+        .unwrap_or(false);
+    if expired {
+        round += 1;
+        EMERGENCY_CLOSE_ROUND.save(deps.storage, &round)?;
+        EMERGENCY_CLOSE_TOTAL.save(deps.storage, &Uint128::zero())?;
+        EMERGENCY_CLOSE_WINDOW_START.save(deps.storage, &env.block.time)?;
+    } else if window_start.is_none() {
+        EMERGENCY_CLOSE_WINDOW_START.save(deps.storage, &env.block.time)?;
+    }
+
+    EMERGENCY_CLOSE_SIGNALS.update(deps.storage, (round, &signaler), |existing| {
+        existing.unwrap_or_default().checked_add(amount)
+    })?;
+    let total =
+        EMERGENCY_CLOSE_TOTAL.may_load(deps.storage)?.unwrap_or_default().checked_add(amount)?;
+    EMERGENCY_CLOSE_TOTAL.save(deps.storage, &total)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "signal_emergency_close")
+        .add_attribute("signaler", signaler.clone())
+        .add_attribute("round", round.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("total_signaled", total);
+
+    let supply = CURVE_STATE.load(deps.storage)?.supply;
+    if !supply.is_zero() && Decimal::from_ratio(total, supply) >= config.quorum_ratio {
+        let close_info = MessageInfo { sender: env.contract.address.clone(), funds: vec![] };
+        let close_response = apply_update_phase(deps.branch(), env, close_info, Phase::Closed)?;
+        response = response
+            .add_attribute("quorum_reached", "true")
+            .add_attributes(close_response.attributes)
+            .add_submessages(close_response.messages)
+            .add_events(close_response.events);
+    }
+    Ok(response)
+}
+
+/// Reclaims a signaler's deposit for `round`, whether or not it's still
+/// the current round. Signaling never locks funds past the depositor's
+/// own choice, even if quorum is never reached.
+pub fn execute_withdraw_emergency_close_signal(
+    deps: DepsMut,
+    info: MessageInfo,
+    round: u64,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    let amount = EMERGENCY_CLOSE_SIGNALS
+        .may_load(deps.storage, (round, &info.sender))?
+        .ok_or(ContractError::NoEmergencyCloseSignal { signaler: info.sender.clone(), round })?;
+    EMERGENCY_CLOSE_SIGNALS.remove(deps.storage, (round, &info.sender));
+    if round == EMERGENCY_CLOSE_ROUND.may_load(deps.storage)?.unwrap_or_default() {
+        let total = EMERGENCY_CLOSE_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+        EMERGENCY_CLOSE_TOTAL.save(deps.storage, &total.saturating_sub(amount))?;
+    }
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    let payout_msg: CosmosMsg<TokenFactoryMsg> = match SUPPLY_MODE.load(deps.storage)? {
+        SupplyTokenBacking::TokenFactory => BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![cosmwasm_std::coin(amount.u128(), supply_token.denom)],
+        }
+        .into(),
+        SupplyTokenBacking::Cw20 { address } => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_emergency_close_signal")
+        .add_attribute("signaler", info.sender.clone())
+        .add_attribute("round", round.to_string())
+        .add_attribute("amount", amount)
+        .add_message(payout_msg))
+}
+
+/// Owner-only. Sets or disables the guardian set behind
+/// [`execute_propose_recovery`]/[`execute_run_recovery`]. `threshold`
+/// must be between 1 and `guardians.len()`.
+pub fn execute_set_recovery_guardians(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Option<RecoveryConfigMsg>,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+    let old_config = RECOVERY_CONFIG.may_load(deps.storage)?.flatten();
+    let config = config
+        .map(|c| -> Result<_, ContractError> {
+            let guardians = c
+                .guardians
+                .iter()
+                .map(|g| deps.api.addr_validate(g))
+                .collect::<StdResult<Vec<_>>>()?;
+            if guardians.is_empty()
+                || c.threshold == 0
+                || c.threshold as usize > guardians.len()
+                || c.delay_seconds == 0
+            {
+                return Err(ContractError::InvalidRecoveryConfig {});
+            }
+            Ok(RecoveryConfig {
+                guardians,
+                threshold: c.threshold,
+                delay_seconds: c.delay_seconds,
+            })
+        })
+        .transpose()?;
+    RECOVERY_CONFIG.save(deps.storage, &config)?;
+    record_param_change(
+        deps.storage,
+        &env,
+        &info.sender,
+        "recovery_config",
+        old_config.map(|c| to_binary(&c)).transpose()?,
+        config.map(|c| to_binary(&c)).transpose()?,
+    )?;
+    Ok(Response::new().add_attribute("action", "set_recovery_guardians"))
+}
+
+/// Errors unless a recovery guardian set is configured and `sender` is
+/// a member of it, otherwise returns the loaded config for reuse.
+fn assert_recovery_guardian(
+    storage: &dyn cosmwasm_std::Storage,
+    sender: &cosmwasm_std::Addr,
+) -> Result<RecoveryConfig, ContractError> {
+    let config = RECOVERY_CONFIG
+        .may_load(storage)?
+        .flatten()
+        .ok_or(ContractError::RecoveryNotConfigured {})?;
+    if !config.guardians.contains(sender) {
+        return Err(ContractError::NotRecoveryGuardian {});
+    }
+    Ok(config)
+}
+
+/// Guardian-only. Starts a pending recovery for `new_owner`, counting
+/// the caller's own approval, and setting `execute_after` from
+/// [`RecoveryConfig::delay_seconds`] measured from now -- the same
+/// "delay starts at creation" shape as [`PendingTimelock`]. If a
+/// proposal for the same `new_owner` is already pending, this instead
+/// just adds the caller's approval, the same as
+/// [`execute_approve_recovery`]; a proposal for a different `new_owner`
+/// must be cancelled first.
+pub fn execute_propose_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_frozen(deps.storage)?;
+    let config = assert_recovery_guardian(deps.storage, &info.sender)?;
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+
+    if let Some(existing) = PENDING_RECOVERY.may_load(deps.storage)?.flatten() {
+        if existing.new_owner != new_owner {
+            return Err(ContractError::RecoveryProposalConflict {
+                existing: existing.new_owner,
+                new_owner,
+            });
+        }
+        return execute_approve_recovery(deps, info);
+    }
+
+    let execute_after = env.block.time.plus_seconds(config.delay_seconds);
+    PENDING_RECOVERY.save(
+        deps.storage,
+        &Some(RecoveryProposal {
+            new_owner: new_owner.clone(),
+            approvals: vec![info.sender.clone()],
+            execute_after,
+        }),
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "propose_recovery")
+        .add_attribute("proposer", info.sender)
+        .add_attribute("new_owner", new_owner)
+        .add_attribute("execute_after", execute_after.seconds().to_string()))
+}
+
+/// Guardian-only. Adds the caller's approval to the pending recovery
+/// proposal.
+pub fn execute_approve_recovery(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_frozen(deps.storage)?;
+    assert_recovery_guardian(deps.storage, &info.sender)?;
+    let mut proposal = PENDING_RECOVERY
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::NoPendingRecovery {})?;
+    if proposal.approvals.contains(&info.sender) {
+        return Err(ContractError::RecoveryAlreadyApproved { guardian: info.sender });
+    }
+    proposal.approvals.push(info.sender.clone());
+    let approvals = proposal.approvals.len();
+    PENDING_RECOVERY.save(deps.storage, &Some(proposal))?;
+    Ok(Response::new()
+        .add_attribute("action", "approve_recovery")
+        .add_attribute("guardian", info.sender)
+        .add_attribute("approvals", approvals.to_string()))
+}
+
+/// Owner- or guardian-callable. Discards the pending recovery proposal,
+/// if any.
+pub fn execute_cancel_recovery(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    if assert_owner_or_polytone_proxy(deps.storage, &info.sender).is_err() {
+        assert_recovery_guardian(deps.storage, &info.sender)?;
+    }
+    if PENDING_RECOVERY.may_load(deps.storage)?.flatten().is_none() {
+        return Err(ContractError::NoPendingRecovery {});
+    }
+    PENDING_RECOVERY.save(deps.storage, &None)?;
+    Ok(Response::new().add_attribute("action", "cancel_recovery"))
+}
+
+/// Permissionless, like the other `Crank*` executes. Once the pending
+/// proposal has [`RecoveryConfig::threshold`] approvals and its
+/// `execute_after` has passed, forces the `cw_ownable` owner to
+/// `new_owner` via [`cw_ownable::initialize_owner`] -- the same call
+/// `instantiate` uses, reused here to bypass the old owner's own
+/// signature entirely, since requiring it would defeat the point of a
+/// recovery path for a lost or compromised key.
+pub fn execute_run_recovery(
+    deps: DepsMut,
+    env: Env,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_not_frozen(deps.storage)?;
+    let config = RECOVERY_CONFIG
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::RecoveryNotConfigured {})?;
+    let proposal = PENDING_RECOVERY
+        .may_load(deps.storage)?
+        .flatten()
+        .ok_or(ContractError::NoPendingRecovery {})?;
+    let approvals = proposal.approvals.len() as u32;
+    if approvals < config.threshold {
+        return Err(ContractError::RecoveryThresholdNotMet {
+            approvals,
+            threshold: config.threshold,
+        });
+    }
+    if env.block.time < proposal.execute_after {
+        return Err(ContractError::RecoveryNotReady { execute_after: proposal.execute_after });
+    }
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(proposal.new_owner.as_str()))?;
+    PENDING_RECOVERY.save(deps.storage, &None)?;
+    Ok(Response::new()
+        .add_attribute("action", "execute_recovery")
+        .add_attribute("new_owner", proposal.new_owner))
+}
+
+/// Handles messages the token factory module delivers to this contract
+/// when it is registered as a denom's before-send hook.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    match msg {
+        SudoMsg::BlockBeforeSend { amount, .. } => {
+            let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+            if amount.denom == supply_token.denom && matches!(PHASE.load(deps.storage)?, Phase::Hatch)
+            {
+                return Err(ContractError::TransfersLocked {});
+            }
+            Ok(Response::new().add_attribute("action", "block_before_send"))
+        }
+        SudoMsg::IBCLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        }) => Ok(Response::new()
+            .add_attribute("action", "ibc_lifecycle_complete")
+            .add_attribute("channel", channel)
+            .add_attribute("sequence", sequence.to_string())
+            .add_attribute("success", success.to_string())),
+        SudoMsg::IBCLifecycleComplete(IbcLifecycleComplete::IbcTimeout { channel, sequence }) => {
+            Ok(Response::new()
+                .add_attribute("action", "ibc_lifecycle_complete")
+                .add_attribute("channel", channel)
+                .add_attribute("sequence", sequence.to_string())
+                .add_attribute("success", false.to_string()))
+        }
+        #[cfg(any(test, feature = "test-utils"))]
+        SudoMsg::ForcePhase { new_phase } => {
+            PHASE.save(deps.storage, &new_phase)?;
+            Ok(Response::new()
+                .add_attribute("action", "force_phase")
+                .add_attribute("new_phase", format!("{new_phase:?}")))
+        }
+        #[cfg(any(test, feature = "test-utils"))]
+        SudoMsg::SetCurveState { supply, reserve } => {
+            let mut curve_state = CURVE_STATE.load(deps.storage)?;
+            curve_state.supply = supply;
+            curve_state.reserve = reserve;
+            CURVE_STATE.save(deps.storage, &curve_state)?;
+            Ok(Response::new()
+                .add_attribute("action", "set_curve_state")
+                .add_attribute("supply", supply)
+                .add_attribute("reserve", reserve))
+        }
+    }
+}
+
+/// Guards every execute that mutates curve or fee configuration.
+pub fn assert_not_frozen(storage: &dyn cosmwasm_std::Storage) -> Result<(), ContractError> {
+    if FROZEN.load(storage)? {
+        return Err(ContractError::Frozen {});
+    }
+    Ok(())
+}
+
+/// Guards every path that mints or burns supply against the curve. See
+/// [`TRADING_PAUSED`] for exactly which entry points this covers.
+fn assert_trading_not_paused(storage: &dyn cosmwasm_std::Storage) -> Result<(), ContractError> {
+    if TRADING_PAUSED.load(storage)? {
+        return Err(ContractError::TradingPaused {});
+    }
+    Ok(())
+}
+
+/// Like [`assert_owner`], but also accepts calls from the Polytone proxy
+/// set via [`ExecuteMsg::SetPolytoneProxyConfig`], if any, so a remote
+/// owner DAO can administer this contract cross-chain through its proxy.
+/// Ownership transfer/renunciation itself doesn't go through this: it
+/// still requires being the literal cw-ownable owner, since it's handled
+/// by cw-ownable's own `update_ownership` directly rather than by any of
+/// the functions this guards.
+pub fn assert_owner_or_polytone_proxy(
+    storage: &dyn cosmwasm_std::Storage,
+    sender: &cosmwasm_std::Addr,
+) -> Result<(), ContractError> {
+    if let Some(config) = POLYTONE_PROXY_CONFIG.may_load(storage)?.flatten() {
+        if config.proxy == *sender {
+            return Ok(());
+        }
+    }
+    Ok(assert_owner(storage, sender)?)
+}
+
+pub fn execute_sweep_unrelated_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    recipient: String,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    assert_owner_or_polytone_proxy(deps.storage, &info.sender)?;
+
+    let reserve = RESERVE.load(deps.storage)?;
+    let supply_token = SUPPLY_DENOM.load(deps.storage)?;
+    if denom == reserve.denom || denom == supply_token.denom {
+        return Err(ContractError::SweepForbidden {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let balance = deps.querier.query_balance(&env.contract.address, denom.clone())?;
+    if balance.amount.is_zero() {
+        return Err(ContractError::NothingToSweep { denom });
+    }
+
+    let send = BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: vec![balance.clone()],
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "sweep_unrelated_funds")
+        .add_attribute("denom", denom)
+        .add_attribute("amount", balance.amount)
+        .add_attribute("recipient", recipient)
+        .add_message(send))
+}
+
+/// Returns whether `sender` is either the current owner or the DAO this
+/// commons belongs to. A handful of admin actions accept either.
+pub fn is_owner_or_dao(deps: Deps, api: &dyn Api, sender: &cosmwasm_std::Addr) -> StdResult<bool> {
+    let _ = api;
+    let dao = DAO.load(deps.storage)?;
+    Ok(sender == dao || is_owner(deps.storage, sender).unwrap_or(false))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::CurveInfo {} => to_binary(&query_curve_info(deps)?),
+        QueryMsg::PhaseConfig {} => to_binary(&query_phase_config(deps)?),
+        QueryMsg::Ownership {} => to_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::Dao {} => to_binary(&DAO.load(deps.storage)?),
+        QueryMsg::DenomAdmin {} => to_binary(&DENOM_ADMIN.load(deps.storage)?),
+        QueryMsg::SecondaryReserveOracle { denom } => to_binary(
+            &SECONDARY_RESERVES
+                .may_load(deps.storage, &denom)?
+                .map(|r| r.oracle),
+        ),
+        QueryMsg::ReserveDenomTrace {} => to_binary(&RESERVE_IBC_TRACE.load(deps.storage)?),
+        QueryMsg::AuxMinterAllowance { minter } => {
+            let minter = deps.api.addr_validate(&minter)?;
+            to_binary(
+                &AUX_MINTER_ALLOWANCES
+                    .may_load(deps.storage, &minter)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::SupplyAtHeight { height } => {
+            let height = height.unwrap_or(env.block.height);
+            to_binary(
+                &SUPPLY_SNAPSHOT
+                    .may_load_at_height(deps.storage, height)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::ReserveAtHeight { height } => {
+            let height = height.unwrap_or(env.block.height);
+            to_binary(
+                &RESERVE_SNAPSHOT
+                    .may_load_at_height(deps.storage, height)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::Hooks {} => to_binary(&ABC_HOOKS.query_hooks(deps)?),
+        QueryMsg::CloseProposalModule {} => {
+            to_binary(&CLOSE_PROPOSAL_MODULE.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::ProposalSellLimit {} => {
+            let active_proposals = ACTIVE_PROPOSALS
+                .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+                .collect::<StdResult<_>>()?;
+            to_binary(&ProposalSellLimitResponse {
+                limit: PROPOSAL_SELL_LIMIT.may_load(deps.storage)?.flatten(),
+                active_proposals,
+            })
+        }
+        QueryMsg::TreasuryConfig {} => to_binary(&TREASURY_CONFIG.may_load(deps.storage)?.flatten()),
+        QueryMsg::StakingConfig {} => to_binary(&STAKING_CONFIG.may_load(deps.storage)?.flatten()),
+        QueryMsg::LegacyCurve {} => to_binary(&LEGACY_CURVE.may_load(deps.storage)?),
+        QueryMsg::PhaseTransitions {} => {
+            let transitions = PHASE_TRANSITIONS
+                .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+                .map(|item| Ok(item?.1))
+                .collect::<StdResult<Vec<PhaseTransition>>>()?;
+            to_binary(&transitions)
+        }
+        QueryMsg::Twap { window } => to_binary(&query_twap(deps, env, window)?),
+        QueryMsg::PriceOracleConfig {} => {
+            to_binary(&PRICE_ORACLE_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::Volume { window } => to_binary(&query_volume(deps, env, window)?),
+        QueryMsg::HistoricalCurveInfo { start_after, limit } => to_binary(&paginate_map_values(
+            deps,
+            &CURVE_CHECKPOINTS,
+            start_after,
+            limit,
+            cosmwasm_std::Order::Ascending,
+        )?),
+        QueryMsg::ListTrades { start_after, limit } => to_binary(&paginate_map_values(
+            deps,
+            &TRADES,
+            start_after,
+            limit,
+            cosmwasm_std::Order::Ascending,
+        )?),
+        QueryMsg::Candles { from, to } => to_binary(&query_candles(deps, from, to)?),
+        QueryMsg::AccountStats { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&ACCOUNT_STATS.may_load(deps.storage, &address)?.unwrap_or_default())
+        }
+        QueryMsg::ReserveStakingConfig {} => {
+            to_binary(&RESERVE_STAKING_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::ReserveStakingState {} => {
+            to_binary(&RESERVE_STAKING_STATE.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::ReserveVaultConfig {} => {
+            to_binary(&RESERVE_VAULT_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::ReserveVaultDeposited {} => {
+            to_binary(&RESERVE_VAULT_DEPOSITED.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::HarvestedTotal {} => {
+            to_binary(&HARVESTED_TOTAL.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::DiversificationConfig {} => {
+            to_binary(&DIVERSIFICATION_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::PendingDiversification {} => {
+            to_binary(&PENDING_DIVERSIFICATION.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::DiversifiedBalance {} => {
+            to_binary(&DIVERSIFIED_BALANCE.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::LiquiditySeedConfig {} => {
+            to_binary(&LIQUIDITY_SEED_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::ReserveHealthThreshold {} => {
+            to_binary(&RESERVE_HEALTH_THRESHOLD.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::ReserveHealthFactor {} => {
+            let curve_state = CURVE_STATE.load(deps.storage)?;
+            let effective_reserve = effective_reserve_value(deps, &env, curve_state.reserve)?;
+            to_binary(&reserve_health_factor(deps.storage, effective_reserve)?)
+        }
+        QueryMsg::ReserveExchangeRateSource {} => to_binary(
+            &RESERVE_EXCHANGE_RATE_CONFIG.may_load(deps.storage)?.flatten(),
+        ),
+        QueryMsg::EffectiveReserveValue {} => {
+            let curve_state = CURVE_STATE.load(deps.storage)?;
+            to_binary(&effective_reserve_value(deps, &env, curve_state.reserve)?)
+        }
+        QueryMsg::BackstopConfig {} => {
+            to_binary(&BACKSTOP_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::BackstopBalance {} => {
+            to_binary(&BACKSTOP_BALANCE.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::ReferralConfig {} => {
+            to_binary(&REFERRAL_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::ReferralEarned { referrer } => {
+            let referrer = deps.api.addr_validate(&referrer)?;
+            to_binary(&REFERRAL_EARNED.may_load(deps.storage, &referrer)?.unwrap_or_default())
+        }
+        QueryMsg::IdempotencyKeyStatus { key } => {
+            let status = IDEMPOTENCY_KEYS.may_load(deps.storage, &key)?.filter(|record| {
+                env.block.time < record.recorded_at.plus_seconds(IDEMPOTENCY_KEY_TTL_SECONDS)
+            });
+            to_binary(&status)
+        }
+        QueryMsg::PolytoneProxyConfig {} => {
+            to_binary(&POLYTONE_PROXY_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::MirrorChannels {} => {
+            let channels: Vec<String> = MIRROR_CHANNELS
+                .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+                .collect::<StdResult<_>>()?;
+            to_binary(&channels)
+        }
+        QueryMsg::ZapConfig {} => to_binary(&ZAP_CONFIG.may_load(deps.storage)?.flatten()),
+        QueryMsg::OperatorDeposit { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            to_binary(&OPERATOR_DEPOSITS.may_load(deps.storage, &owner)?.unwrap_or_default())
+        }
+        QueryMsg::OperatorGrant { owner, operator } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let operator = deps.api.addr_validate(&operator)?;
+            to_binary(&OPERATOR_GRANTS.may_load(deps.storage, (&owner, &operator))?)
+        }
+        QueryMsg::SimulateBuy { net_payment } => {
+            let curve_state = CURVE_STATE.load(deps.storage)?;
+            let curve = load_curve(deps.storage, curve_state.decimals)?;
+            let quote =
+                quote_buy(curve.as_ref(), curve_state.supply, curve_state.reserve, net_payment)
+                    .map_err(StdError::from)?;
+            to_binary(&quote)
+        }
+        QueryMsg::SimulateSell { amount } => {
+            let curve_state = CURVE_STATE.load(deps.storage)?;
+            let curve = load_curve(deps.storage, curve_state.decimals)?;
+            let quote = quote_sell(curve.as_ref(), curve_state.supply, curve_state.reserve, amount)
+                .map_err(StdError::from)?;
+            to_binary(&quote)
+        }
+        QueryMsg::RecurringPurchase { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            to_binary(&RECURRING_ORDERS.may_load(deps.storage, &owner)?)
+        }
+        QueryMsg::LimitOrder { order_id } => {
+            to_binary(&LIMIT_ORDERS.may_load(deps.storage, order_id)?)
+        }
+        QueryMsg::ListLimitOrders { start_after, limit } => to_binary(&paginate_map_values(
+            deps,
+            &LIMIT_ORDERS,
+            start_after,
+            limit,
+            cosmwasm_std::Order::Ascending,
+        )?),
+        QueryMsg::BatchAuctionState {} => {
+            to_binary(&BATCH_AUCTION_STATE.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::BatchContribution { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&BATCH_CONTRIBUTIONS.may_load(deps.storage, &address)?)
+        }
+        QueryMsg::StreamingBuy { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            to_binary(&STREAMING_BUYS.may_load(deps.storage, &owner)?)
+        }
+        QueryMsg::BuyPool { pool_id } => to_binary(&BUY_POOLS.may_load(deps.storage, pool_id)?),
+        QueryMsg::BuyPoolContribution { pool_id, address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&BUY_POOL_CONTRIBUTIONS.may_load(deps.storage, (pool_id, &address))?)
+        }
+        QueryMsg::BlockTrade { trade_id } => {
+            to_binary(&BLOCK_TRADES.may_load(deps.storage, trade_id)?)
+        }
+        QueryMsg::LockupConfig {} => to_binary(&LOCKUP_CONFIG.may_load(deps.storage)?.flatten()),
+        QueryMsg::Lockup { lockup_id } => to_binary(&LOCKUPS.may_load(deps.storage, lockup_id)?),
+        QueryMsg::LockupsByOwner { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let lockups: Vec<Lockup> = LOCKUPS
+                .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?
+                .into_iter()
+                .filter(|(_, lockup)| lockup.owner == owner)
+                .map(|(_, lockup)| lockup)
+                .collect();
+            to_binary(&lockups)
+        }
+        QueryMsg::HolderDiscountConfig {} => {
+            to_binary(&HOLDER_DISCOUNT_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::EffectiveExitFee { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let phase = PHASE.load(deps.storage)?;
+            let exit_fee = match phase {
+                Phase::Hatch | Phase::Closed => Decimal::zero(),
+                Phase::Open => PHASE_CONFIG.load(deps.storage)?.open.exit_fee,
+            };
+            let effective =
+                holder_discounted_exit_fee(deps.storage, &address, env.block.time, exit_fee)?;
+            to_binary(&effective)
+        }
+        QueryMsg::RoleMembers { role } => {
+            let members: Vec<cosmwasm_std::Addr> = ROLES
+                .prefix(role.discriminant())
+                .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+                .collect::<StdResult<_>>()?;
+            to_binary(&members)
+        }
+        QueryMsg::HasRole { role, address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let has_role = is_owner(deps.storage, &address)?
+                || ROLES.has(deps.storage, (role.discriminant(), &address));
+            to_binary(&has_role)
+        }
+        QueryMsg::TradingPaused {} => to_binary(&TRADING_PAUSED.load(deps.storage)?),
+        QueryMsg::TimelockConfig {} => {
+            to_binary(&TIMELOCK_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::PendingTimelock { id } => {
+            to_binary(&PENDING_TIMELOCKS.may_load(deps.storage, id)?)
+        }
+        QueryMsg::ListPendingTimelocks { start_after, limit } => to_binary(&paginate_map_values(
+            deps,
+            &PENDING_TIMELOCKS,
+            start_after,
+            limit,
+            cosmwasm_std::Order::Ascending,
+        )?),
+        QueryMsg::MaintenanceOperator {} => {
+            to_binary(&MAINTENANCE_OPERATOR.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::VetoConfig {} => to_binary(&VETO_CONFIG.may_load(deps.storage)?.flatten()),
+        QueryMsg::EmergencyCloseConfig {} => {
+            to_binary(&EMERGENCY_CLOSE_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::EmergencyCloseStatus {} => to_binary(&EmergencyCloseStatusResponse {
+            round: EMERGENCY_CLOSE_ROUND.may_load(deps.storage)?.unwrap_or_default(),
+            total_signaled: EMERGENCY_CLOSE_TOTAL.may_load(deps.storage)?.unwrap_or_default(),
+            window_start: EMERGENCY_CLOSE_WINDOW_START.may_load(deps.storage)?,
+        }),
+        QueryMsg::EmergencyCloseSignal { round, address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(
+                &EMERGENCY_CLOSE_SIGNALS
+                    .may_load(deps.storage, (round, &address))?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::RecoveryConfig {} => {
+            to_binary(&RECOVERY_CONFIG.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::PendingRecovery {} => {
+            to_binary(&PENDING_RECOVERY.may_load(deps.storage)?.flatten())
+        }
+        QueryMsg::History { start_after, limit } => to_binary(&paginate_map_values(
+            deps,
+            &PARAM_CHANGES,
+            start_after,
+            limit,
+            cosmwasm_std::Order::Ascending,
+        )?),
+    }
+}
+
+/// The average spot price over the last `window` blocks, derived from
+/// [`PRICE_ACCUMULATOR`]/[`CUMULATIVE_PRICE_SNAPSHOT`] rather than the
+/// current spot price, so a single large trade can't move the reported
+/// price on its own. Falls back to the last known spot price if `window`
+/// reaches back to the contract's instantiation height or earlier.
+pub fn query_twap(deps: Deps, env: Env, window: u64) -> StdResult<Decimal> {
+    let accumulator = PRICE_ACCUMULATOR.load(deps.storage)?;
+    // Extrapolate the running total forward to the current height, in
+    // case no trade has happened since `last_update_height`.
+    let elapsed_since_update = env.block.height.saturating_sub(accumulator.last_update_height);
+    let cumulative_now = accumulator.cumulative_price
+        + accumulator.last_spot_price * Decimal::from_ratio(elapsed_since_update, 1u128);
+
+    let then_height = env.block.height.saturating_sub(window);
+    let cumulative_then =
+        match CUMULATIVE_PRICE_SNAPSHOT.may_load_at_height(deps.storage, then_height)? {
+            Some(cumulative) => cumulative,
+            None => return Ok(accumulator.last_spot_price),
+        };
+
+    let actual_window = env.block.height.saturating_sub(then_height);
+    if actual_window == 0 {
+        return Ok(accumulator.last_spot_price);
+    }
+    Ok((cumulative_now - cumulative_then) / Decimal::from_ratio(actual_window, 1u128))
+}
+
+/// Sums [`VOLUME_BY_DAY`] over the last `window` epoch days, including
+/// today's (necessarily partial) bucket, for a rough dashboard-friendly
+/// activity figure without an external indexer.
+pub fn query_volume(deps: Deps, env: Env, window: u64) -> StdResult<VolumeResponse> {
+    let today = env.block.time.seconds() / 86400;
+    let first_day = today.saturating_sub(window.saturating_sub(1));
+
+    let mut buy_volume = Uint128::zero();
+    let mut sell_volume = Uint128::zero();
+    for day in first_day..=today {
+        if let Some(bucket) = VOLUME_BY_DAY.may_load(deps.storage, day)? {
+            buy_volume += bucket.buy_volume;
+            sell_volume += bucket.sell_volume;
+        }
+    }
+    Ok(VolumeResponse { buy_volume, sell_volume })
+}
+
+/// The daily candles recorded in [`CANDLES_BY_DAY`] for epoch days
+/// `from..=to`, skipping days with no trades.
+pub fn query_candles(deps: Deps, from: u64, to: u64) -> StdResult<Vec<CandleResponse>> {
+    let mut candles = vec![];
+    for day in from..=to {
+        if let Some(candle) = CANDLES_BY_DAY.may_load(deps.storage, day)? {
+            candles.push(CandleResponse { day, candle });
+        }
+    }
+    Ok(candles)
+}
+
+pub fn query_curve_info(deps: Deps) -> StdResult<CurveInfoResponse> {
+    let curve_state = CURVE_STATE.load(deps.storage)?;
+    let curve = load_curve(deps.storage, curve_state.decimals)?;
+    let spot_price = curve.spot_price(curve_state.supply);
+
+    let reserve_denom = RESERVE.load(deps.storage)?.denom;
+    let supply_denom = SUPPLY_DENOM.load(deps.storage)?.denom;
+    let treasury_config = TREASURY_CONFIG.may_load(deps.storage)?.flatten();
+
+    Ok(CurveInfoResponse::new(
+        curve_state,
+        spot_price,
+        reserve_denom,
+        supply_denom,
+        treasury_config,
+    ))
+}
+
+pub fn query_phase_config(deps: Deps) -> StdResult<PhaseConfigResponse> {
+    Ok(PhaseConfigResponse {
+        phase: PHASE.load(deps.storage)?,
+        phase_config: PHASE_CONFIG.load(deps.storage)?,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(
+    deps: DepsMut,
+    _env: Env,
+    _msg: MigrateMsg,
+) -> Result<Response<TokenFactoryMsg>, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
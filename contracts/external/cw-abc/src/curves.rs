@@ -0,0 +1,5 @@
+//! Bonding curve math used to price the supply token against the reserve
+//! token. Extracted into the standalone [`cw_curves`] package so it can
+//! be reused (and unit tested) outside of this contract; re-exported here
+//! so existing `crate::curves::*` call sites keep working unchanged.
+pub use cw_curves::*;
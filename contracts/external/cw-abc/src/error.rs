@@ -0,0 +1,439 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+use crate::state::{Phase, Role};
+
+#[cfg_attr(any(test, feature = "test-utils"), derive(PartialEq))]
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Ownable(#[from] cw_ownable::OwnershipError),
+
+    #[error(transparent)]
+    PaymentError(#[from] cw_utils::PaymentError),
+
+    #[error(transparent)]
+    HookError(#[from] cw_hooks::HookError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("This operation is not allowed during the {current:?} phase")]
+    InvalidPhase { current: Phase },
+
+    #[error("Contribution of {amount} is outside of the allowed limits")]
+    ContributionLimit { amount: cosmwasm_std::Uint128 },
+
+    #[error("The hatch phase raise target has already been met")]
+    HatchRaiseMet {},
+
+    #[error("Cannot sweep the reserve or supply denom")]
+    SweepForbidden {},
+
+    #[error("The contract has been frozen and its configuration can no longer change")]
+    Frozen {},
+
+    #[error("Invalid denom metadata: {reason}")]
+    InvalidDenomMetadata { reason: String },
+
+    #[error("Phases only move forward: cannot go from {current:?} to {requested:?}")]
+    InvalidPhaseTransition { current: Phase, requested: Phase },
+
+    #[error("Transfers of the supply denom are locked during the hatch phase")]
+    TransfersLocked {},
+
+    #[error("Compliance clawback has been permanently disabled")]
+    ClawbackDisabled {},
+
+    #[error("Sender is not the compliance clawback role")]
+    NotClawbackRole {},
+
+    #[error("Got a submessage reply with unknown id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Error instantiating the cw20 supply token")]
+    Cw20InstantiateError {},
+
+    #[error("No funds of denom {denom} held by the contract")]
+    NothingToSweep { denom: String },
+
+    #[error("{denom} is not the reserve denom or a configured secondary reserve")]
+    UnsupportedReserveDenom { denom: String },
+
+    #[error("Requested amount of {amount} exceeds the remaining aux minter allowance of {allowance}")]
+    InsufficientAuxMinterAllowance {
+        amount: cosmwasm_std::Uint128,
+        allowance: cosmwasm_std::Uint128,
+    },
+
+    #[error("Sell amount of {amount} exceeds the {limit} limit while a proposal is open on the close proposal module")]
+    SellRestrictedDuringProposal {
+        amount: cosmwasm_std::Uint128,
+        limit: cosmwasm_std::Uint128,
+    },
+
+    #[error("Treasury split percent must be greater than zero and no more than one")]
+    InvalidTreasuryPercent {},
+
+    #[error("Auto-staking a buy requires a staking contract to be set via SetStakingConfig")]
+    StakingContractNotConfigured {},
+
+    #[error("Requested spend of {amount} exceeds the {funding} available in the funding pool")]
+    InsufficientFundingPool {
+        amount: cosmwasm_std::Uint128,
+        funding: cosmwasm_std::Uint128,
+    },
+
+    #[error("SpendFundingPool only supports bank and wasm messages")]
+    UnsupportedFundingPoolMessage {},
+
+    #[error("No legacy curve was imported, or its claimable supply has been exhausted")]
+    InsufficientLegacySupply {},
+
+    #[error("Reserve staking requires SetReserveStakingConfig to be set first")]
+    ReserveStakingNotConfigured {},
+
+    #[error("Reserve staking is only supported when the reserve is the chain's staking-bonded native denom")]
+    ReserveNotStakingDenom {},
+
+    #[error("Requested amount of {amount} exceeds the liquid reserve balance of {liquid}")]
+    InsufficientLiquidReserve {
+        amount: cosmwasm_std::Uint128,
+        liquid: cosmwasm_std::Uint128,
+    },
+
+    #[error("Reserve vault deposits require SetReserveVaultConfig to be set first")]
+    ReserveVaultNotConfigured {},
+
+    #[error("Reserve vault max deployable percent must be greater than zero and no more than one")]
+    InvalidReserveVaultPercent {},
+
+    #[error("Requested deposit of {amount} would exceed the vault cap of {max} of the reserve")]
+    ReserveVaultCapExceeded {
+        amount: cosmwasm_std::Uint128,
+        max: cosmwasm_std::Uint128,
+    },
+
+    #[error("Diversification percent must be greater than zero and no more than one")]
+    InvalidDiversificationPercent {},
+
+    #[error("Liquidity seeding requires both the supply and reserve tokens to be native, so both can be attached to one message")]
+    LiquiditySeedRequiresNativeTokens {},
+
+    #[error("Requested seed reserve amount of {amount} exceeds the {reserve} held in the curve")]
+    InsufficientReserveForSeed {
+        amount: cosmwasm_std::Uint128,
+        reserve: cosmwasm_std::Uint128,
+    },
+
+    #[error("SpendReserve requires SetReserveHealthThreshold to be set first")]
+    ReserveHealthThresholdNotConfigured {},
+
+    #[error("Spending {amount} would leave the reserve at a health factor of {ratio}, below the configured threshold of {threshold}")]
+    ReserveHealthFactorTooLow {
+        amount: cosmwasm_std::Uint128,
+        ratio: cosmwasm_std::Decimal,
+        threshold: cosmwasm_std::Decimal,
+    },
+
+    #[error("Reserve denom migration is only supported when the reserve is currently native")]
+    ReserveMigrationRequiresNativeReserve {},
+
+    #[error("Reserve denom migration requires depositing exactly {expected} of the new denom, got {received}")]
+    IncorrectReserveMigrationDeposit {
+        expected: cosmwasm_std::Uint128,
+        received: cosmwasm_std::Uint128,
+    },
+
+    #[error("Backstop percent must be greater than zero and no more than one")]
+    InvalidBackstopPercent {},
+
+    #[error("The reserve is not under-collateralized: its health factor is {ratio}, and a top-up is only allowed during a shortfall")]
+    NoReserveShortfall { ratio: cosmwasm_std::Decimal },
+
+    #[error("Requested backstop top-up of {amount} exceeds the {balance} held in the backstop pool")]
+    InsufficientBackstopBalance {
+        amount: cosmwasm_std::Uint128,
+        balance: cosmwasm_std::Uint128,
+    },
+
+    #[error("Referral percent must be greater than zero and no more than one")]
+    InvalidReferralPercent {},
+
+    #[error("ibc_forward requires the supply token to be a token-factory denom, not a cw20")]
+    IbcForwardRequiresNativeSupply {},
+
+    #[error("ibc_forward cannot be combined with staking the same buy's minted tokens")]
+    IbcForwardConflictsWithStake {},
+
+    #[error("malformed ibc-hooks memo: {reason}")]
+    MalformedIbcMemo { reason: String },
+
+    #[error("invalid EVM sender address {address}: expected a 0x-prefixed 40 hex-character address")]
+    InvalidEvmAddress { address: String },
+
+    #[error("ZapBuy requires SetZapConfig to be set first")]
+    ZapNotConfigured {},
+
+    #[error("Zap-in swap returned {received} of the reserve, below the requested minimum of {min}")]
+    ZapSlippage {
+        received: cosmwasm_std::Uint128,
+        min: cosmwasm_std::Uint128,
+    },
+
+    #[error("Pre-depositing for operators requires the {denom} denom to be native, not cw20")]
+    OperatorDepositRequiresNative { denom: String },
+
+    #[error("{denom} is not the reserve or supply denom")]
+    NotReserveOrSupplyDenom { denom: String },
+
+    #[error("Requested withdrawal of {amount} exceeds the deposited balance of {balance}")]
+    InsufficientOperatorDeposit {
+        amount: cosmwasm_std::Uint128,
+        balance: cosmwasm_std::Uint128,
+    },
+
+    #[error("Sender is not an operator granted by {owner}")]
+    NotGrantedOperator { owner: String },
+
+    #[error("This operator grant expired at {expired_at}")]
+    OperatorGrantExpired { expired_at: cosmwasm_std::Timestamp },
+
+    #[error("Requested amount of {amount} exceeds the operator's remaining limit of {limit}")]
+    OperatorLimitExceeded {
+        amount: cosmwasm_std::Uint128,
+        limit: cosmwasm_std::Uint128,
+    },
+
+    #[error("initial_curve_state cannot be combined with import_legacy_curve")]
+    ConflictingCurveImport {},
+
+    #[error("initial_curve_state is only supported when the reserve is native, so its initial_reserve can be attached to Instantiate")]
+    InitialCurveStateRequiresNativeReserve {},
+
+    #[error("initial_curve_state.initial_reserve of {initial_reserve} does not match curve.reserve(initial_supply) of {expected}")]
+    InitialCurveStateMismatch {
+        initial_reserve: cosmwasm_std::Uint128,
+        expected: cosmwasm_std::Uint128,
+    },
+
+    #[error("initial_curve_state requires depositing exactly {expected} of the reserve denom, got {received}")]
+    IncorrectInitialReserveDeposit {
+        expected: cosmwasm_std::Uint128,
+        received: cosmwasm_std::Uint128,
+    },
+
+    #[error("allocations of {allocated} would bring the curve's supply to {new_supply}, requiring a reserve of {required}, but only {available} is seeded")]
+    AllocationExceedsCurveReserve {
+        allocated: cosmwasm_std::Uint128,
+        new_supply: cosmwasm_std::Uint128,
+        required: cosmwasm_std::Uint128,
+        available: cosmwasm_std::Uint128,
+    },
+
+    #[error("Invalid supply token subdenom: {reason}")]
+    InvalidSubdenom { reason: String },
+
+    // Phases here move only by an explicit owner `UpdatePhase` call, not a
+    // scheduled start/end time, so these two are reserved for a future
+    // timed-hatch feature and unreachable today; see `HatchRaiseMet` above
+    // for the same situation.
+    #[error("The hatch phase has not started")]
+    HatchNotStarted {},
+
+    #[error("The hatch phase has ended")]
+    HatchEnded {},
+
+    #[error("{address} is not on the hatch phase allowlist")]
+    NotAllowlisted { address: String },
+
+    #[error("This commons is closed and no longer accepts buys or sells")]
+    CommonsClosed {},
+
+    #[error("This buy would exceed the hatch phase's raise cap; only {remaining} of reserve remains before it")]
+    HatchCapExceeded { remaining: cosmwasm_std::Uint128 },
+
+    #[error("Buy attached a permit but no permit authorizer key is configured")]
+    PermitAuthorizerNotConfigured {},
+
+    #[error("Permit expired at {expires_at}")]
+    PermitExpired { expires_at: cosmwasm_std::Timestamp },
+
+    #[error("Buy of {requested} exceeds the permit's max_amount of {max_amount}")]
+    PermitAmountExceeded {
+        requested: cosmwasm_std::Uint128,
+        max_amount: cosmwasm_std::Uint128,
+    },
+
+    #[error("Permit signature does not match the configured authorizer key")]
+    InvalidPermitSignature {},
+
+    #[error("Sender has no recurring purchase set up")]
+    NoRecurringPurchase {},
+
+    #[error("Requested withdrawal of {amount} exceeds the recurring purchase's deposit of {deposited}")]
+    InsufficientRecurringDeposit {
+        amount: cosmwasm_std::Uint128,
+        deposited: cosmwasm_std::Uint128,
+    },
+
+    #[error("No limit order with id {order_id}")]
+    LimitOrderNotFound { order_id: u64 },
+
+    #[error("Sender is not the owner of limit order {order_id}")]
+    NotLimitOrderOwner { order_id: u64 },
+
+    #[error("SettleBatchAuction cannot run until the hatch phase has ended")]
+    BatchAuctionStillOpen {},
+
+    #[error("Sender already has a streaming buy in progress")]
+    StreamingBuyAlreadyActive {},
+
+    #[error("Sender has no streaming buy set up")]
+    NoStreamingBuy {},
+
+    #[error("BuyAndDistribute requires at least one recipient")]
+    EmptyRecipientList {},
+
+    #[error("BuyAndDistribute recipient weights must sum to more than zero")]
+    ZeroTotalWeight {},
+
+    #[error("No buy pool with id {pool_id}")]
+    BuyPoolNotFound { pool_id: u64 },
+
+    #[error("Buy pool {pool_id} has already been settled")]
+    BuyPoolAlreadySettled { pool_id: u64 },
+
+    #[error("Buy pool {pool_id} has not yet reached its target of {target}; only {total_pooled} pooled so far")]
+    BuyPoolTargetNotMet {
+        pool_id: u64,
+        target: cosmwasm_std::Uint128,
+        total_pooled: cosmwasm_std::Uint128,
+    },
+
+    #[error("Sender has no contribution to buy pool {pool_id}")]
+    NoBuyPoolContribution { pool_id: u64 },
+
+    #[error("No block trade with id {trade_id}")]
+    BlockTradeNotFound { trade_id: u64 },
+
+    #[error("Sender is not the owner of block trade {trade_id}")]
+    NotBlockTradeOwner { trade_id: u64 },
+
+    #[error("Block trades are only supported during the Open phase")]
+    BlockTradesRequireOpenPhase {},
+
+    #[error("Lockup bonus percent must be greater than zero")]
+    InvalidLockupBonusPercent {},
+
+    #[error("BuyWithLockup requires SetLockupConfig to be set first")]
+    LockupNotConfigured {},
+
+    #[error("Lockup duration of {requested} is below the configured minimum of {minimum}")]
+    LockupDurationTooShort { requested: u64, minimum: u64 },
+
+    #[error("Lockup bonus budget is exhausted")]
+    LockupBudgetExhausted {},
+
+    #[error("No lockup with id {lockup_id}")]
+    LockupNotFound { lockup_id: u64 },
+
+    #[error("Sender is not the owner of lockup {lockup_id}")]
+    NotLockupOwner { lockup_id: u64 },
+
+    #[error("Lockup {lockup_id} unlocks at {unlocks_at} and cannot be claimed yet")]
+    LockupNotYetUnlocked { lockup_id: u64, unlocks_at: cosmwasm_std::Timestamp },
+
+    #[error("Holder discount tiers must have strictly increasing min_tenure_seconds")]
+    HolderDiscountTiersNotSorted {},
+
+    #[error("Holder discount exit_fee_discount must be greater than zero and no more than one")]
+    InvalidHolderDiscount {},
+
+    #[error("Sender does not hold the {role:?} role or ownership")]
+    MissingRole { role: Role },
+
+    #[error("Hatch allowlist is not configured; use UpdatePhaseConfig to enable it first")]
+    AllowlistNotConfigured {},
+
+    #[error("Trading is currently paused")]
+    TradingPaused {},
+
+    #[error("No timelock is configured; use SetTimelockConfig to set delay_seconds first")]
+    TimelockNotConfigured {},
+
+    #[error("A timelock is configured; use QueueTimelockedAction/ExecuteTimelockedAction instead")]
+    TimelockRequired {},
+
+    #[error("Timelock delay_seconds must be greater than zero")]
+    InvalidTimelockDelay {},
+
+    #[error("No pending timelock with id {id}")]
+    TimelockNotFound { id: u64 },
+
+    #[error("This timelock cannot be executed until {execute_after}")]
+    TimelockNotReady { execute_after: cosmwasm_std::Timestamp },
+
+    #[error("Sender is neither the owner nor a maintenance operator with the required permission")]
+    NotMaintenanceOperator {},
+
+    #[error("Sender is neither the owner nor the configured veto address")]
+    NotVetoAddress {},
+
+    #[error("No veto address is configured; use SetVetoAddress to set one first")]
+    VetoNotConfigured {},
+
+    #[error("Veto only applies to a pending Close timelock, not id {id}")]
+    VetoNotApplicable { id: u64 },
+
+    #[error("The veto window for timelock {id} has expired")]
+    VetoWindowExpired { id: u64 },
+
+    #[error("Fee exceeds the protocol-level maximum of {max}")]
+    FeeExceedsMax { max: cosmwasm_std::Decimal },
+
+    #[error("Fees were already updated too recently; next update allowed at {next_allowed}")]
+    FeeUpdateTooSoon { next_allowed: cosmwasm_std::Timestamp },
+
+    #[error("quorum_ratio must be in (0, 1] and window_seconds must be greater than zero")]
+    InvalidEmergencyCloseConfig {},
+
+    #[error("No emergency-close vote is configured; use SetEmergencyCloseConfig to set one first")]
+    EmergencyCloseNotConfigured {},
+
+    #[error("The commons is already Closed")]
+    AlreadyClosed {},
+
+    #[error("No emergency-close signal from {signaler} in round {round}")]
+    NoEmergencyCloseSignal { signaler: cosmwasm_std::Addr, round: u64 },
+
+    #[error("guardians must be non-empty and threshold must be between 1 and guardians.len()")]
+    InvalidRecoveryConfig {},
+
+    #[error("No recovery guardians are configured; use SetRecoveryGuardians to set them first")]
+    RecoveryNotConfigured {},
+
+    #[error("Sender is not a configured recovery guardian")]
+    NotRecoveryGuardian {},
+
+    #[error("No pending recovery proposal")]
+    NoPendingRecovery {},
+
+    #[error("A recovery for {existing} is pending; cancel it before proposing {new_owner}")]
+    RecoveryProposalConflict {
+        existing: cosmwasm_std::Addr,
+        new_owner: cosmwasm_std::Addr,
+    },
+
+    #[error("{guardian} has already approved this recovery proposal")]
+    RecoveryAlreadyApproved { guardian: cosmwasm_std::Addr },
+
+    #[error("Recovery has {approvals} of {threshold} required approvals")]
+    RecoveryThresholdNotMet { approvals: u32, threshold: u32 },
+
+    #[error("This recovery cannot execute until {execute_after}")]
+    RecoveryNotReady { execute_after: cosmwasm_std::Timestamp },
+}
@@ -0,0 +1,298 @@
+//! Test fixtures for downstream contracts embedding `cw-abc`, so they
+//! don't have to hand-roll [`InstantiateMsg`] boilerplate or a
+//! [`crate::testing::mock_app`] wiring of their own to exercise it.
+//! Gated behind the `test-utils` feature, same as [`crate::testing`],
+//! which this module builds on.
+//!
+//! Scoped to this contract's most common configuration — a native
+//! reserve and a token-factory supply — since that's what a downstream
+//! integrator embedding `cw-abc` almost always uses; a cw20 reserve or
+//! supply still needs to be wired up by hand via [`InstantiateMsg`]
+//! directly.
+use cosmwasm_std::{coin, Addr, BankMsg, CosmosMsg, Decimal, Uint128};
+use cw_multi_test::{AppResponse, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
+
+use crate::contract::{execute, instantiate, query};
+use crate::curves::CurveType;
+use crate::msg::{
+    CurveInfoResponse, ExecuteMsg, InstantiateMsg, PhaseConfigResponse, QueryMsg,
+    ReserveTokenMode, SupplyTokenMode,
+};
+use crate::state::{ClosedConfig, HatchConfig, MinMax, OpenConfig, Phase, PhaseConfig};
+use crate::testing::{mock_app, TokenFactoryApp};
+use crate::ContractError;
+
+/// A permissive hatch/open/closed configuration with no allowlist and
+/// generous limits, good enough for a test that isn't specifically
+/// exercising phase-gating edge cases.
+pub fn default_phase_config() -> PhaseConfig {
+    PhaseConfig {
+        hatch: HatchConfig {
+            contribution_limits: MinMax { min: Uint128::zero(), max: Uint128::MAX },
+            initial_raise: MinMax { min: Uint128::zero(), max: Uint128::MAX },
+            entry_fee: Decimal::percent(5),
+            allowlist: None,
+            batch_auction: false,
+        },
+        open: OpenConfig { entry_fee: Decimal::percent(2), exit_fee: Decimal::percent(2) },
+        closed: ClosedConfig {},
+    }
+}
+
+/// Builds an [`InstantiateMsg`] for a native-reserve, token-factory-supply
+/// commons, defaulting every field a test doesn't care about so call
+/// sites only spell out what they're actually varying.
+pub struct InstantiateMsgBuilder {
+    owner: Option<String>,
+    dao: Option<String>,
+    subdenom: String,
+    supply_decimals: u8,
+    reserve_denom: String,
+    reserve_decimals: u8,
+    curve_type: CurveType,
+    phase_config: PhaseConfig,
+}
+
+impl Default for InstantiateMsgBuilder {
+    fn default() -> Self {
+        InstantiateMsgBuilder {
+            owner: Some(OWNER.to_string()),
+            dao: None,
+            subdenom: "abc".to_string(),
+            supply_decimals: 6,
+            reserve_denom: RESERVE_DENOM.to_string(),
+            reserve_decimals: 6,
+            curve_type: CurveType::Linear { slope: Decimal::percent(1), scale: 6 },
+            phase_config: default_phase_config(),
+        }
+    }
+}
+
+impl InstantiateMsgBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    pub fn dao(mut self, dao: impl Into<String>) -> Self {
+        self.dao = Some(dao.into());
+        self
+    }
+
+    pub fn subdenom(mut self, subdenom: impl Into<String>) -> Self {
+        self.subdenom = subdenom.into();
+        self
+    }
+
+    pub fn reserve_denom(mut self, denom: impl Into<String>) -> Self {
+        self.reserve_denom = denom.into();
+        self
+    }
+
+    pub fn decimals(mut self, supply_decimals: u8, reserve_decimals: u8) -> Self {
+        self.supply_decimals = supply_decimals;
+        self.reserve_decimals = reserve_decimals;
+        self
+    }
+
+    pub fn curve_type(mut self, curve_type: CurveType) -> Self {
+        self.curve_type = curve_type;
+        self
+    }
+
+    pub fn phase_config(mut self, phase_config: PhaseConfig) -> Self {
+        self.phase_config = phase_config;
+        self
+    }
+
+    pub fn build(self) -> InstantiateMsg {
+        InstantiateMsg {
+            owner: self.owner,
+            dao: self.dao,
+            supply_token_mode: SupplyTokenMode::TokenFactory { subdenom: self.subdenom },
+            supply_decimals: self.supply_decimals,
+            reserve_token_mode: ReserveTokenMode::Native { denom: self.reserve_denom },
+            reserve_decimals: self.reserve_decimals,
+            curve_type: self.curve_type,
+            phase_config: self.phase_config,
+            import_legacy_curve: None,
+            initial_curve_state: None,
+            allocations: vec![],
+            denom_metadata: None,
+        }
+    }
+}
+
+/// A default owner address, used by [`InstantiateMsgBuilder::default`]
+/// and [`TestCommons::instantiate`].
+pub const OWNER: &str = "owner";
+/// A default native reserve denom, used by
+/// [`InstantiateMsgBuilder::default`].
+pub const RESERVE_DENOM: &str = "ureserve";
+
+fn abc_contract() -> Box<dyn Contract<crate::bindings::TokenFactoryMsg>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// A freshly instantiated commons on a [`TokenFactoryApp`], with the
+/// buy/sell/phase/query boilerplate a test would otherwise repeat wrapped
+/// up into a handful of methods.
+pub struct TestCommons {
+    pub app: TokenFactoryApp,
+    pub addr: Addr,
+    pub reserve_denom: String,
+    pub supply_denom: String,
+}
+
+impl TestCommons {
+    /// Instantiates `msg` (built via [`InstantiateMsgBuilder`], typically)
+    /// on a fresh [`mock_app`], with `msg.owner` as the instantiator.
+    pub fn instantiate(msg: InstantiateMsg) -> Self {
+        let reserve_denom = match &msg.reserve_token_mode {
+            ReserveTokenMode::Native { denom } => denom.clone(),
+            ReserveTokenMode::Cw20 { .. } => {
+                panic!("TestCommons only supports a native reserve; wire up a cw20 reserve by hand")
+            }
+        };
+        let owner = msg.owner.clone().unwrap_or_else(|| OWNER.to_string());
+
+        let mut app = mock_app();
+        let code_id = app.store_code(abc_contract());
+        let addr = app
+            .instantiate_contract(code_id, Addr::unchecked(owner), &msg, &[], "abc", None)
+            .unwrap();
+
+        let supply_denom = app
+            .wrap()
+            .query_wasm_smart::<CurveInfoResponse>(&addr, &QueryMsg::CurveInfo {})
+            .unwrap()
+            .supply_denom;
+
+        TestCommons { app, addr, reserve_denom, supply_denom }
+    }
+
+    /// Mints `amount` of the reserve denom to `to`, so it can be spent on
+    /// a buy.
+    pub fn mint_reserve(&mut self, to: &str, amount: u128) {
+        self.app
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: to.to_string(),
+                amount: vec![coin(amount, &self.reserve_denom)],
+            }))
+            .unwrap();
+    }
+
+    pub fn buy(&mut self, buyer: &str, payment: u128) -> anyhow::Result<AppResponse> {
+        let buy = ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        };
+        self.app.execute_contract(
+            Addr::unchecked(buyer),
+            self.addr.clone(),
+            &buy,
+            &[coin(payment, &self.reserve_denom)],
+        )
+    }
+
+    pub fn sell(&mut self, seller: &str, amount: u128) -> anyhow::Result<AppResponse> {
+        let supply_denom = self.supply_denom.clone();
+        self.app.execute_contract(
+            Addr::unchecked(seller),
+            self.addr.clone(),
+            &ExecuteMsg::Sell {},
+            &[coin(amount, supply_denom)],
+        )
+    }
+
+    pub fn update_phase(&mut self, owner: &str, new_phase: Phase) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(owner),
+            self.addr.clone(),
+            &ExecuteMsg::UpdatePhase { new_phase },
+            &[],
+        )
+    }
+
+    pub fn curve_info(&self) -> CurveInfoResponse {
+        self.app.wrap().query_wasm_smart(&self.addr, &QueryMsg::CurveInfo {}).unwrap()
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.app
+            .wrap()
+            .query_wasm_smart::<PhaseConfigResponse>(&self.addr, &QueryMsg::PhaseConfig {})
+            .unwrap()
+            .phase
+    }
+
+    pub fn balance(&self, addr: &str, denom: &str) -> Uint128 {
+        self.app.wrap().query_balance(addr, denom).unwrap().amount
+    }
+
+    /// Replays a JSON transcript of [`ReplayOp`]s against `self` and
+    /// returns the curve's final state, so a reported mainnet incident's
+    /// exact operation sequence can be pasted into a test and its outcome
+    /// reproduced locally. Operations that error (an incident transcript
+    /// often includes the attempts that failed, not just the ones that
+    /// landed) are ignored rather than panicking the replay.
+    pub fn replay(&mut self, transcript_json: &str) -> CurveInfoResponse {
+        let ops: Vec<ReplayOp> = cosmwasm_std::from_json(transcript_json).unwrap();
+        for op in ops {
+            match op.kind {
+                ReplayOpKind::Buy => {
+                    let _ = self.buy(&op.sender, op.amount.u128());
+                }
+                ReplayOpKind::Sell => {
+                    let _ = self.sell(&op.sender, op.amount.u128());
+                }
+                ReplayOpKind::Donate => {
+                    self.mint_reserve(&op.sender, op.amount.u128());
+                    let send = CosmosMsg::Bank(BankMsg::Send {
+                        to_address: self.addr.to_string(),
+                        amount: vec![coin(op.amount.u128(), &self.reserve_denom)],
+                    });
+                    let _ = self.app.execute(Addr::unchecked(&op.sender), send);
+                }
+            }
+        }
+        self.curve_info()
+    }
+}
+
+/// One step in a JSON transcript consumed by [`TestCommons::replay`], e.g.
+/// `{"sender": "hatcher1", "kind": "buy", "amount": "1000000"}`.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ReplayOp {
+    pub sender: String,
+    pub kind: ReplayOpKind,
+    pub amount: Uint128,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayOpKind {
+    Buy,
+    Sell,
+    /// Sends `amount` of the reserve denom straight to the contract, the
+    /// way a plain bank transfer would. There's no `ExecuteMsg::Donate`
+    /// on this contract, so this is the closest a transcript gets to
+    /// "someone sent reserve tokens to the curve without buying".
+    Donate,
+}
+
+/// Asserts that `res` failed with exactly `expected`, the way `tests.rs`
+/// already does inline for `full_lifecycle`, but without repeating the
+/// `unwrap_err().downcast().unwrap()` dance at every call site.
+pub fn assert_contract_error(res: anyhow::Result<AppResponse>, expected: ContractError) {
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, expected);
+}
@@ -0,0 +1,199 @@
+//! A storage-operation counting harness standing in for real wasm gas
+//! metering: `cw-multi-test` runs contract code as native Rust calls, not
+//! compiled wasm, so there's no gas meter to read there, and this
+//! workspace has no dependency on a real one (`osmosis-test-tube`) and
+//! can't add one without network access to fetch it.
+//!
+//! Calls [`crate::contract::instantiate`]/[`crate::contract::execute`]
+//! directly against a hand-built [`DepsMut`], bypassing multi-test's App
+//! and bank keeper entirely: `Buy`/`Sell` only ever read a payment from
+//! `info.funds`, never from real bank state, so this needs no bank
+//! module to exercise the hot paths.
+//!
+//! `MAX_OPS` below is a coarse guess, not a baseline pinned against a
+//! real `cargo test` run (this sandbox can't run one). A maintainer
+//! running this suite for the first time in a real environment should
+//! tighten it to the actual observed counts plus a small margin.
+use std::cell::Cell;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{coin, Decimal, DepsMut, Order, QuerierWrapper, Record, Storage, Uint128};
+
+use crate::contract::{execute, instantiate};
+use crate::curves::CurveType;
+use crate::msg::{ExecuteMsg, InstantiateMsg, ReserveTokenMode, SupplyTokenMode};
+use crate::state::{ClosedConfig, HatchConfig, MinMax, OpenConfig, Phase, PhaseConfig};
+
+const OWNER: &str = "owner";
+const RESERVE_DENOM: &str = "ureserve";
+/// A coarse ceiling on storage ops per `Buy`/`Sell`, generous enough not
+/// to trip on ordinary hook/fee/referral bookkeeping but tight enough to
+/// catch a hot path that starts scanning something it shouldn't.
+const MAX_OPS: u64 = 30;
+
+/// Wraps a [`MockStorage`], counting every `get`/`set`/`remove` call as a
+/// deterministic, environment-independent proxy for gas.
+#[derive(Default)]
+struct CountingStorage {
+    inner: MockStorage,
+    gets: Cell<u64>,
+    sets: Cell<u64>,
+    removes: Cell<u64>,
+}
+
+impl CountingStorage {
+    fn ops(&self) -> u64 {
+        self.gets.get() + self.sets.get() + self.removes.get()
+    }
+
+    fn reset(&self) {
+        self.gets.set(0);
+        self.sets.set(0);
+        self.removes.set(0);
+    }
+}
+
+impl Storage for CountingStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.gets.set(self.gets.get() + 1);
+        self.inner.get(key)
+    }
+
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        self.inner.range(start, end, order)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.sets.set(self.sets.get() + 1);
+        self.inner.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.removes.set(self.removes.get() + 1);
+        self.inner.remove(key);
+    }
+}
+
+fn permissive_phase_config() -> PhaseConfig {
+    PhaseConfig {
+        hatch: HatchConfig {
+            contribution_limits: MinMax { min: Uint128::zero(), max: Uint128::MAX },
+            initial_raise: MinMax { min: Uint128::zero(), max: Uint128::MAX },
+            entry_fee: Decimal::percent(5),
+            allowlist: None,
+            batch_auction: false,
+        },
+        open: OpenConfig { entry_fee: Decimal::percent(2), exit_fee: Decimal::percent(2) },
+        closed: ClosedConfig {},
+    }
+}
+
+fn instantiate_commons(storage: &mut CountingStorage, curve_type: CurveType) {
+    let api = MockApi::default();
+    let querier = MockQuerier::default();
+    let deps = DepsMut { storage, api: &api, querier: QuerierWrapper::new(&querier) };
+    let msg = InstantiateMsg {
+        owner: Some(OWNER.to_string()),
+        dao: None,
+        supply_token_mode: SupplyTokenMode::TokenFactory { subdenom: "abc".to_string() },
+        supply_decimals: 6,
+        reserve_token_mode: ReserveTokenMode::Native { denom: RESERVE_DENOM.to_string() },
+        reserve_decimals: 6,
+        curve_type,
+        phase_config: permissive_phase_config(),
+        import_legacy_curve: None,
+        initial_curve_state: None,
+        allocations: vec![],
+        denom_metadata: None,
+    };
+    instantiate(deps, mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+}
+
+fn buy(storage: &mut CountingStorage, buyer: &str, payment: u128) {
+    let api = MockApi::default();
+    let querier = MockQuerier::default();
+    let deps = DepsMut { storage, api: &api, querier: QuerierWrapper::new(&querier) };
+    let info = mock_info(buyer, &[coin(payment, RESERVE_DENOM)]);
+    let buy = ExecuteMsg::Buy {
+        stake: None,
+        referrer: None,
+        ibc_forward: None,
+        idempotency_key: None,
+        permit: None,
+    };
+    execute(deps, mock_env(), info, buy).unwrap();
+}
+
+fn sell(storage: &mut CountingStorage, seller: &str, supply_denom: &str, amount: u128) {
+    let api = MockApi::default();
+    let querier = MockQuerier::default();
+    let deps = DepsMut { storage, api: &api, querier: QuerierWrapper::new(&querier) };
+    let info = mock_info(seller, &[coin(amount, supply_denom)]);
+    execute(deps, mock_env(), info, ExecuteMsg::Sell {}).unwrap();
+}
+
+fn open_phase(storage: &mut CountingStorage) {
+    let api = MockApi::default();
+    let querier = MockQuerier::default();
+    let deps = DepsMut { storage, api: &api, querier: QuerierWrapper::new(&querier) };
+    let info = mock_info(OWNER, &[]);
+    execute(deps, mock_env(), info, ExecuteMsg::UpdatePhase { new_phase: Phase::Open }).unwrap();
+}
+
+#[test]
+fn buy_sell_storage_ops_stay_bounded_across_curves_and_state_sizes() {
+    let curve_types = [
+        CurveType::Constant { value: Decimal::percent(150), scale: 6 },
+        CurveType::Linear { slope: Decimal::percent(1), scale: 6 },
+        CurveType::SquareRoot { slope: Decimal::percent(300), scale: 6 },
+    ];
+    let supply_denom = format!("factory/{}/abc", mock_env().contract.address);
+
+    for curve_type in curve_types {
+        let mut storage = CountingStorage::default();
+        instantiate_commons(&mut storage, curve_type);
+
+        storage.reset();
+        buy(&mut storage, "hatcher1", 1_000_000);
+        let hatch_buy_ops = storage.ops();
+        assert!(hatch_buy_ops <= MAX_OPS, "hatch-phase buy took {hatch_buy_ops} storage ops");
+
+        // A buyer's first buy also records `FIRST_ACQUIRED`, so the
+        // baseline to compare against is a repeat buy by the same buyer.
+        storage.reset();
+        buy(&mut storage, "hatcher1", 1);
+        let repeat_buy_ops = storage.ops();
+
+        // Grow state (many more hatchers) and confirm a buy's op count
+        // doesn't scale with it -- a hot path becoming O(n) in the
+        // number of past hatchers is exactly the kind of regression this
+        // harness exists to catch.
+        for i in 0..50 {
+            buy(&mut storage, &format!("hatcher-{i}"), 1);
+        }
+        storage.reset();
+        buy(&mut storage, "hatcher1", 1);
+        let hatch_buy_ops_after_growth = storage.ops();
+        assert_eq!(
+            hatch_buy_ops_after_growth, repeat_buy_ops,
+            "buy's storage-op count grew with the number of past hatchers"
+        );
+
+        open_phase(&mut storage);
+
+        storage.reset();
+        buy(&mut storage, "hatcher1", 1_000_000);
+        let open_buy_ops = storage.ops();
+        assert!(open_buy_ops <= MAX_OPS, "open-phase buy took {open_buy_ops} storage ops");
+
+        storage.reset();
+        sell(&mut storage, "hatcher1", &supply_denom, 1);
+        let sell_ops = storage.ops();
+        assert!(sell_ops <= MAX_OPS, "sell took {sell_ops} storage ops");
+    }
+}
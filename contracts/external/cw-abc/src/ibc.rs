@@ -0,0 +1,450 @@
+//! Resolves the IBC denom trace for an `ibc/<hash>` reserve denom via a
+//! Stargate query to the transfer module's `DenomTrace` RPC, unwraps
+//! packet-forward-middleware-wrapped ibc-hooks memos for the buy path
+//! (see [`unwrap_pfm_buy_memo`]), and runs the state-sync channel a
+//! "mirror" contract on another chain subscribes to for a read-only
+//! replica of this commons' curve state (the `ibc_channel_*`/
+//! `ibc_packet_*` entry points below, and [`state_sync_packet_msgs`],
+//! called from `contract::trade_event` after every trade).
+//!
+//! Pulling in `cosmos-sdk-proto` for two fields, or `serde_json` for one
+//! nested-object unwrap, felt like overkill next to what's already a
+//! `cosmwasm-std`-only dependency graph, so both are hand-decoded here
+//! instead. If this grows further, switch to real proto/JSON dependencies.
+
+use std::borrow::Cow;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    entry_point, to_binary, Decimal, Deps, DepsMut, Env, Ibc3ChannelOpenResponse,
+    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcMsg,
+    IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
+    IbcTimeout, QueryRequest, StdError, StdResult, SubMsg, Uint128,
+};
+
+use crate::bindings::TokenFactoryMsg;
+use crate::error::ContractError;
+use crate::msg::StateSyncPacket;
+use crate::state::{DenomTrace, Phase, MIRROR_CHANNELS};
+
+const DENOM_TRACE_QUERY_PATH: &str = "/ibc.applications.transfer.v1.Query/DenomTrace";
+
+/// Extracts the hash from an `ibc/<hash>` denom, erroring if it isn't one.
+pub fn ibc_hash(denom: &str) -> StdResult<&str> {
+    denom
+        .strip_prefix("ibc/")
+        .filter(|hash| !hash.is_empty())
+        .ok_or_else(|| StdError::generic_err(format!("{denom} is not an ibc/<hash> denom")))
+}
+
+/// Queries the transfer module for the denom trace behind `hash`.
+pub fn query_denom_trace(deps: Deps, hash: &str) -> StdResult<DenomTrace> {
+    let request = QueryRequest::Stargate {
+        path: DENOM_TRACE_QUERY_PATH.to_string(),
+        data: encode_denom_trace_request(hash).into(),
+    };
+    let raw: cosmwasm_std::Binary = deps.querier.query(&request)?;
+    decode_denom_trace_response(raw.as_slice())
+}
+
+fn encode_varint_len(len: usize, buf: &mut Vec<u8>) {
+    let mut len = len as u64;
+    loop {
+        let mut byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len != 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+fn encode_denom_trace_request(hash: &str) -> Vec<u8> {
+    // QueryDenomTraceRequest { hash: string = 1 }
+    let mut buf = vec![0x0a];
+    encode_varint_len(hash.len(), &mut buf);
+    buf.extend_from_slice(hash.as_bytes());
+    buf
+}
+
+fn decode_denom_trace_response(data: &[u8]) -> StdResult<DenomTrace> {
+    // QueryDenomTraceResponse { denom_trace: DenomTrace = 1 }
+    let inner = read_length_delimited_field(data, 0x0a)
+        .ok_or_else(|| StdError::generic_err("missing denom_trace in DenomTrace response"))?;
+
+    // DenomTrace { path: string = 1, base_denom: string = 2 }
+    let path = read_length_delimited_field(inner, 0x0a)
+        .ok_or_else(|| StdError::generic_err("missing path in denom trace"))?;
+    let base_denom = read_length_delimited_field(inner, 0x12)
+        .ok_or_else(|| StdError::generic_err("missing base_denom in denom trace"))?;
+
+    Ok(DenomTrace {
+        path: String::from_utf8(path.to_vec())
+            .map_err(|_| StdError::generic_err("denom trace path is not valid utf8"))?,
+        base_denom: String::from_utf8(base_denom.to_vec())
+            .map_err(|_| StdError::generic_err("denom trace base_denom is not valid utf8"))?,
+    })
+}
+
+/// Finds the first occurrence of `tag` in `data` and returns the bytes of
+/// its length-delimited value. Only handles the flat, single-occurrence
+/// messages this module deals with.
+fn read_length_delimited_field(data: &[u8], tag: u8) -> Option<&[u8]> {
+    let mut i = 0;
+    while i < data.len() {
+        let field_tag = data[i];
+        i += 1;
+        if field_tag & 0x07 != 2 {
+            return None;
+        }
+        let mut len = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = *data.get(i)?;
+            i += 1;
+            len |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        let value = data.get(i..i + len)?;
+        if field_tag == tag {
+            return Some(value);
+        }
+        i += len;
+    }
+    None
+}
+
+/// A packet-forward-middleware memo nests its final hop's payload behind
+/// this many `"forward"` hops at most before we give up: real PFM routes
+/// stay far below this, so hitting it means the memo is malformed (or
+/// pathologically recursive) rather than a legitimate deep route.
+const MAX_PFM_HOPS: u8 = 8;
+
+/// Walks a (possibly packet-forward-middleware-wrapped) ibc-hooks `wasm`
+/// memo down to its innermost `wasm.msg` payload and returns that
+/// payload's raw JSON text, ready to decode as an
+/// [`crate::msg::ExecuteMsg`].
+///
+/// A PFM-wrapped memo looks like `{"forward":{...,"next":<memo>}}`,
+/// where `next` may be a nested object or a JSON string containing the
+/// next hop's encoded memo, repeated hop by hop until the actual `wasm`
+/// hook. This unwraps either shape and surfaces a
+/// [`ContractError::MalformedIbcMemo`] for anything that doesn't parse,
+/// rather than panicking or silently misrouting the buy.
+pub fn unwrap_pfm_buy_memo(memo: &str) -> Result<String, ContractError> {
+    let mut current = Cow::Borrowed(memo);
+    for _ in 0..MAX_PFM_HOPS {
+        let object = current.trim();
+        let forward = extract_json_field(object, "forward")
+            .map_err(|reason| ContractError::MalformedIbcMemo { reason })?;
+        let Some(forward) = forward else {
+            let wasm = extract_json_field(object, "wasm")
+                .map_err(|reason| ContractError::MalformedIbcMemo { reason })?
+                .ok_or_else(|| ContractError::MalformedIbcMemo {
+                    reason: "memo has neither a \"forward\" nor a \"wasm\" field".to_string(),
+                })?;
+            let msg = extract_json_field(wasm.trim(), "msg")
+                .map_err(|reason| ContractError::MalformedIbcMemo { reason })?
+                .ok_or_else(|| ContractError::MalformedIbcMemo {
+                    reason: "memo's \"wasm\" field has no \"msg\"".to_string(),
+                })?;
+            return Ok(msg.into_owned());
+        };
+        let next = extract_json_field(forward.trim(), "next")
+            .map_err(|reason| ContractError::MalformedIbcMemo { reason })?
+            .ok_or_else(|| ContractError::MalformedIbcMemo {
+                reason: "\"forward\" hop has no \"next\" field".to_string(),
+            })?;
+        current = Cow::Owned(next.into_owned());
+    }
+    Err(ContractError::MalformedIbcMemo {
+        reason: format!("memo nests more than {MAX_PFM_HOPS} \"forward\" hops"),
+    })
+}
+
+/// Scans `json` for a top-level `"key": <value>` and returns that value:
+/// a JSON string's unescaped-enough contents, or the raw `{...}`/`[...]`
+/// slice (delimiters included) for an object or array. `Ok(None)` means
+/// `key` isn't present; `Err` carries a human-readable reason for
+/// anything that looks like broken JSON along the way.
+fn extract_json_field<'a>(json: &'a str, key: &str) -> Result<Option<Cow<'a, str>>, String> {
+    let bytes = json.as_bytes();
+    let needle = format!("\"{key}\"");
+    let mut i = 0;
+    while let Some(offset) = json.get(i..).and_then(|rest| rest.find(&needle)) {
+        let mut j = skip_ws(bytes, i + offset + needle.len());
+        if bytes.get(j) != Some(&b':') {
+            i += offset + needle.len();
+            continue;
+        }
+        j = skip_ws(bytes, j + 1);
+        return match bytes.get(j) {
+            Some(b'"') => read_json_string(json, j)
+                .map(|(value, _)| Some(value))
+                .ok_or_else(|| format!("unterminated string value for \"{key}\"")),
+            Some(b'{') | Some(b'[') => matching_delimiter(bytes, j)
+                .map(|end| Some(Cow::Borrowed(&json[j..=end])))
+                .ok_or_else(|| format!("unbalanced braces in value for \"{key}\"")),
+            Some(_) => Err(format!("\"{key}\" value is not a string or object/array")),
+            None => Err(format!("truncated JSON after \"{key}\"")),
+        };
+    }
+    Ok(None)
+}
+
+fn skip_ws(bytes: &[u8], mut i: usize) -> usize {
+    while matches!(bytes.get(i), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+        i += 1;
+    }
+    i
+}
+
+/// Reads a JSON string starting at `bytes[start] == '"'`. Only `\"` and
+/// `\\` are unescaped; any other escape sequence is left as-is, which is
+/// fine for the memo fields this parses (none are expected to carry
+/// anything fancier). Returns the string's contents and the index of its
+/// closing quote.
+fn read_json_string(json: &str, start: usize) -> Option<(Cow<'_, str>, usize)> {
+    let bytes = json.as_bytes();
+    let content_start = start + 1;
+    let mut owned: Option<String> = None;
+    let mut i = content_start;
+    while let Some(&b) = bytes.get(i) {
+        match b {
+            b'"' => {
+                let value = match owned {
+                    Some(s) => Cow::Owned(s),
+                    None => Cow::Borrowed(&json[content_start..i]),
+                };
+                return Some((value, i));
+            }
+            b'\\' => {
+                let next = *bytes.get(i + 1)?;
+                let owned_str = owned.get_or_insert_with(|| json[content_start..i].to_string());
+                match next {
+                    b'"' => owned_str.push('"'),
+                    b'\\' => owned_str.push('\\'),
+                    other => {
+                        owned_str.push('\\');
+                        owned_str.push(other as char);
+                    }
+                }
+                i += 2;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns the index of the delimiter matching `bytes[start]` (a `{` or
+/// `[`), skipping over the contents of any quoted strings along the way.
+fn matching_delimiter(bytes: &[u8], start: usize) -> Option<usize> {
+    let (open, close) = match bytes[start] {
+        b'{' => (b'{', b'}'),
+        b'[' => (b'[', b']'),
+        _ => return None,
+    };
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut i = start;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if in_string {
+            if b == b'\\' {
+                i += 2;
+                continue;
+            }
+            if b == b'"' {
+                in_string = false;
+            }
+        } else if b == b'"' {
+            in_string = true;
+        } else if b == open {
+            depth += 1;
+        } else if b == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Channel version negotiated for state-sync channels: a mirror contract
+/// proposes it on `ibc_channel_open`, and this contract requires an
+/// exact match on both sides (no downgrade negotiation, since there's
+/// only ever been one version of [`StateSyncPacket`]).
+pub const STATE_SYNC_CHANNEL_VERSION: &str = "cw-abc-state-sync-v1";
+
+/// How long a pushed [`StateSyncPacket`] stays valid for relaying before
+/// timing out. State sync is self-healing (the next trade pushes fresh
+/// state regardless), so this just bounds how long a stale packet can
+/// sit in the relayer's queue before being dropped.
+const STATE_SYNC_PACKET_TIMEOUT_SECONDS: u64 = 3600;
+
+/// Only mirror contracts *subscribe*; they never publish anything back
+/// over this channel, so any inbound packet is unexpected.
+#[cw_serde]
+enum Ack {
+    Error(String),
+}
+
+/// Validates a state-sync channel proposal: unordered, and, if the
+/// counterparty already named a version, that it matches
+/// [`STATE_SYNC_CHANNEL_VERSION`] exactly.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> StdResult<Option<Ibc3ChannelOpenResponse>> {
+    let channel = msg.channel();
+    if channel.order != IbcOrder::Unordered {
+        return Err(StdError::generic_err(
+            "state-sync channels must be unordered: only the latest curve state matters",
+        ));
+    }
+    if channel.version != STATE_SYNC_CHANNEL_VERSION {
+        return Err(StdError::generic_err(format!(
+            "state-sync channel version must be {STATE_SYNC_CHANNEL_VERSION}, got {}",
+            channel.version
+        )));
+    }
+    if let Some(counterparty_version) = msg.counterparty_version() {
+        if counterparty_version != STATE_SYNC_CHANNEL_VERSION {
+            return Err(StdError::generic_err(format!(
+                "state-sync channel version must be {STATE_SYNC_CHANNEL_VERSION}, got {}",
+                counterparty_version
+            )));
+        }
+    }
+    Ok(Some(Ibc3ChannelOpenResponse {
+        version: STATE_SYNC_CHANNEL_VERSION.to_string(),
+    }))
+}
+
+/// Registers the newly connected channel in [`MIRROR_CHANNELS`], so it
+/// starts receiving [`StateSyncPacket`] pushes on the next trade.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse<TokenFactoryMsg>, ContractError> {
+    let channel_id = &msg.channel().endpoint.channel_id;
+    MIRROR_CHANNELS.save(deps.storage, channel_id, &cosmwasm_std::Empty {})?;
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_connect")
+        .add_attribute("channel_id", channel_id))
+}
+
+/// Unregisters a closed channel from [`MIRROR_CHANNELS`], so it stops
+/// receiving pushes.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse<TokenFactoryMsg>, ContractError> {
+    let channel_id = &msg.channel().endpoint.channel_id;
+    MIRROR_CHANNELS.remove(deps.storage, channel_id);
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+/// State-sync channels are push-only from this contract to the mirror,
+/// so this contract never expects to receive a packet on one. Replies
+/// with an error acknowledgement rather than failing the underlying
+/// relay tx, per the usual "never let a packet ack itself fail" ibc
+/// convention.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketReceiveMsg,
+) -> StdResult<IbcReceiveResponse<TokenFactoryMsg>> {
+    let ack = to_binary(&Ack::Error(
+        "cw-abc state-sync channels are push-only; this contract doesn't accept packets"
+            .to_string(),
+    ))?;
+    Ok(IbcReceiveResponse::new(ack).add_attribute("action", "ibc_packet_receive"))
+}
+
+/// Acknowledges a delivered [`StateSyncPacket`]. Nothing to do beyond
+/// logging: state sync is fire-and-forget, and the next trade pushes
+/// fresh state regardless of whether this one was acked or not.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse<TokenFactoryMsg>, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_packet_ack")
+        .add_attribute("channel_id", msg.original_packet.src.channel_id))
+}
+
+/// A timed-out [`StateSyncPacket`] just means a mirror missed one
+/// update; the next trade pushes fresh state regardless, so there's
+/// nothing to retry here beyond logging.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse<TokenFactoryMsg>, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_packet_timeout")
+        .add_attribute("channel_id", msg.packet.src.channel_id))
+}
+
+/// Builds a [`StateSyncPacket`] from the given curve state and returns
+/// one `IbcMsg::SendPacket` submessage per channel in [`MIRROR_CHANNELS`].
+/// Called from `contract::trade_event` after every trade, so every
+/// subscribed mirror sees the fresh state on the same block as the
+/// trade that produced it.
+pub fn state_sync_packet_msgs(
+    storage: &dyn cosmwasm_std::Storage,
+    time: cosmwasm_std::Timestamp,
+    phase: Phase,
+    supply: Uint128,
+    reserve: Uint128,
+    spot_price: Decimal,
+) -> StdResult<Vec<SubMsg>> {
+    let channels: Vec<String> = MIRROR_CHANNELS
+        .keys(storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    if channels.is_empty() {
+        return Ok(vec![]);
+    }
+    let packet = to_binary(&StateSyncPacket {
+        supply,
+        reserve,
+        spot_price,
+        phase,
+    })?;
+    let valid_until = time.plus_seconds(STATE_SYNC_PACKET_TIMEOUT_SECONDS);
+    let timeout = IbcTimeout::with_timestamp(valid_until);
+    Ok(channels
+        .into_iter()
+        .map(|channel_id| {
+            SubMsg::new(IbcMsg::SendPacket {
+                channel_id,
+                data: packet.clone(),
+                timeout: timeout.clone(),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,18 @@
+pub mod bindings;
+pub mod boot;
+pub mod contract;
+pub mod curves;
+pub mod error;
+#[cfg(feature = "test-utils")]
+pub mod fixtures;
+#[cfg(test)]
+mod gas_bench;
+pub mod ibc;
+pub mod msg;
+pub mod state;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+#[cfg(test)]
+mod tests;
+
+pub use crate::error::ContractError;
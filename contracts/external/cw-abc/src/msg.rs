@@ -0,0 +1,1550 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Binary, CosmosMsg, Decimal, Empty, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_ownable::cw_ownable_execute;
+
+// so that consumers don't need a cw_ownable dependency to consume this
+// contract's queries.
+pub use cw_ownable::Ownership;
+
+use crate::bindings::Metadata;
+use crate::curves::CurveType;
+use crate::state::{CurveState, LimitOrderSide, Phase, PhaseConfig, Role, TimelockedAction};
+
+/// How the reserve token accepted for buys is represented on-chain.
+#[cw_serde]
+pub enum ReserveTokenMode {
+    /// A native bank denom, e.g. `uusd` or an `ibc/...` denom.
+    Native { denom: String },
+    /// An existing cw20 contract. Buys arrive via [`ExecuteMsg::Receive`]
+    /// carrying a [`ReceiveMsg::Buy`] payload instead of attached funds.
+    Cw20 { address: String },
+}
+
+/// How the supply token minted against the curve is represented on-chain.
+/// Token factory isn't available on every chain, so [`SupplyTokenMode::Cw20`]
+/// lets the contract instantiate and mint/burn an ordinary cw20 instead,
+/// reusing the same curve and phase logic either way.
+#[cw_serde]
+pub enum SupplyTokenMode {
+    /// Mint a token-factory denom `factory/<this contract>/<subdenom>`.
+    TokenFactory { subdenom: String },
+    /// Instantiate a cw20-base token, with this contract as its minter.
+    Cw20 { code_id: u64, label: String },
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The address that may reconfigure the curve. Defaults to the
+    /// instantiator.
+    pub owner: Option<String>,
+    /// The DAO this commons is funding. Defaults to the instantiator, so
+    /// that dao-core can create this contract as a module via
+    /// `ModuleInstantiateInfo` without passing its own address through.
+    pub dao: Option<String>,
+    pub supply_token_mode: SupplyTokenMode,
+    pub supply_decimals: u8,
+    pub reserve_token_mode: ReserveTokenMode,
+    pub reserve_decimals: u8,
+    pub curve_type: CurveType,
+    pub phase_config: PhaseConfig,
+    /// Seeds this commons's curve state from a cw20-bonding deployment
+    /// being retired in favor of this contract, and opens a claim window
+    /// for that deployment's cw20 holders. Leave unset for a commons
+    /// starting fresh. Mutually exclusive with `initial_curve_state`.
+    pub import_legacy_curve: Option<ImportLegacyCurveMsg>,
+    /// Seeds this commons's curve state directly from a pre-existing
+    /// supply/reserve pair, for a community migrating an existing token
+    /// that isn't a cw20-bonding deployment (see `import_legacy_curve`
+    /// for that case). No supply is minted; `initial_supply` is assumed
+    /// to already exist elsewhere and be reconciled by the caller.
+    /// Mutually exclusive with `import_legacy_curve`.
+    pub initial_curve_state: Option<InitialCurveStateMsg>,
+    /// Mints `amount` of the supply token to `address` for each entry, at
+    /// instantiation (a team allocation, an airdrop escrow, ...), folded
+    /// into `CurveState::supply` so `CurveInfoResponse` reports the true
+    /// total supply. Since minting supply without reserve to back it
+    /// would break the curve invariant that the reserve can always repay
+    /// the outstanding supply, the reserve seeded by
+    /// `import_legacy_curve`/`initial_curve_state` (zero, for a fresh
+    /// commons) must already be enough to cover the allocated supply on
+    /// top of whatever supply that reserve already backs.
+    pub allocations: Vec<AllocationMsg>,
+    /// Bank metadata for the supply denom, only used in
+    /// [`SupplyTokenMode::TokenFactory`] mode. Left unset, metadata is
+    /// auto-generated from `subdenom`/`supply_decimals` (a base unit at
+    /// exponent 0 and a display unit at `supply_decimals`, both named
+    /// after the subdenom); most instantiators otherwise leave
+    /// `denom_units` empty, producing a denom wallets can't display
+    /// sensibly. If set, it's validated the same way
+    /// [`ExecuteMsg::UpdateDenomMetadata`] validates a later update.
+    pub denom_metadata: Option<Metadata>,
+}
+
+/// See [`InstantiateMsg::allocations`].
+#[cw_serde]
+pub struct AllocationMsg {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+/// See [`InstantiateMsg::initial_curve_state`].
+#[cw_serde]
+pub struct InitialCurveStateMsg {
+    pub initial_supply: Uint128,
+    /// Must equal `curve_type.to_curve_fn()(decimals).reserve(initial_supply)`
+    /// and be attached to the `Instantiate` message as the reserve denom
+    /// (so this only supports a native reserve, not a cw20 one, which has
+    /// no way to attach funds to `Instantiate`).
+    pub initial_reserve: Uint128,
+}
+
+/// See [`InstantiateMsg::import_legacy_curve`].
+#[cw_serde]
+pub struct ImportLegacyCurveMsg {
+    /// The cw20-bonding token being retired. Its holders swap into this
+    /// contract's supply token 1:1 via [`ReceiveMsg::ClaimLegacyBalance`].
+    pub legacy_cw20: String,
+    /// [`CurveState::reserve`] to seed this contract with, read from the
+    /// legacy contract at whatever snapshot height the caller migrated
+    /// at (this contract has no way to read another chain module's
+    /// historical state itself).
+    pub reserve_snapshot: Uint128,
+    /// [`CurveState::supply`] to seed this contract with, and the total
+    /// amount claimable via `ClaimLegacyBalance` before it's exhausted.
+    pub supply_snapshot: Uint128,
+}
+
+#[cw_ownable_execute]
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Buy supply tokens with the reserve denom attached as funds. Only
+    /// valid when the reserve is a native denom; in
+    /// [`ReserveTokenMode::Cw20`] mode, buy via `Receive` instead, sending
+    /// the reserve cw20 with a [`ReceiveMsg::Buy`] payload.
+    ///
+    /// If `stake` (or, when unset, [`StakingConfig::default_stake`]) is
+    /// true, the minted supply is staked on the buyer's behalf with
+    /// [`StakingConfig::staking_contract`] instead of being sent to the
+    /// buyer.
+    ///
+    /// If `referrer` is set and [`ExecuteMsg::SetReferralConfig`] is
+    /// configured, a cut of the payment is credited to it; see
+    /// [`QueryMsg::ReferralEarned`].
+    ///
+    /// If `ibc_forward` is set, the minted supply is sent on over IBC to
+    /// the named remote address instead of being credited to the buyer;
+    /// only valid when the supply token is a token-factory denom, and
+    /// mutually exclusive with `stake`.
+    ///
+    /// If `idempotency_key` is set, this buy's result is recorded under
+    /// that key; a later `Buy` reusing the same key within
+    /// [`crate::state::IDEMPOTENCY_KEY_TTL_SECONDS`] replays the
+    /// original result (refunding whatever was attached) instead of
+    /// buying again. Meant for interchain account controllers that may
+    /// retry a buy after seeing a timeout without knowing if it landed;
+    /// see [`QueryMsg::IdempotencyKeyStatus`].
+    ///
+    /// If `permit` is set, its signature is checked against
+    /// [`ExecuteMsg::SetPermitAuthorizerConfig`]'s key and, if valid,
+    /// lets this buy satisfy the hatch phase's
+    /// [`crate::state::HatchConfig::allowlist`] without the sender's
+    /// address having been stored on-chain ahead of time -- see
+    /// [`BuyPermit`]. Ignored outside the hatch phase.
+    Buy {
+        stake: Option<bool>,
+        referrer: Option<String>,
+        ibc_forward: Option<IbcForwardMsg>,
+        idempotency_key: Option<String>,
+        permit: Option<BuyPermit>,
+    },
+    /// Like [`ExecuteMsg::Buy`], but for buys initiated by an ICS-20
+    /// transfer carrying an ibc-hooks "wasm" memo: the receiving chain's
+    /// ibc-hooks module parses the memo and calls this variant directly
+    /// with the transferred coin attached, so `info.sender` is the
+    /// module's derived intermediary address rather than the source-chain
+    /// buyer. `receiver` names that buyer explicitly (as the memo must,
+    /// since the intermediary address isn't spendable by anyone) and is
+    /// who the minted supply (and any auto-stake) goes to. See
+    /// [`SudoMsg::IBCLifecycleComplete`] for the callback the memo's
+    /// `ibc_callback` can request once this settles.
+    IbcHooksBuy {
+        receiver: String,
+        stake: Option<bool>,
+        referrer: Option<String>,
+    },
+    /// Like [`ExecuteMsg::IbcHooksBuy`], but for transfers routed through
+    /// packet-forward-middleware, where the final `wasm` hook can be left
+    /// nested inside one or more PFM `forward` hops instead of being
+    /// unwrapped before dispatch. `memo` is the raw memo text exactly as
+    /// received; the contract unwraps any `forward` hops itself (see
+    /// `unwrap_pfm_buy_memo` in `src/ibc.rs`) down to the innermost
+    /// `wasm.msg`, which must decode as an [`ExecuteMsg::IbcHooksBuy`].
+    /// Malformed or unrecognized memos fail with
+    /// [`crate::error::ContractError::MalformedIbcMemo`] rather than
+    /// being silently ignored.
+    IbcHooksBuyRaw { memo: String },
+    /// Like [`ExecuteMsg::IbcHooksBuy`], but for buys originated by a
+    /// general-message-passing call from an EVM chain (Axelar/Wormhole),
+    /// where the bridged reserve is credited to this contract ahead of
+    /// the call rather than attached to it as an ICS-20 transfer.
+    /// `evm_sender` is the originating EVM address, recorded for the
+    /// `evm_sender` attribute only (the gateway that invokes this
+    /// contract is trusted to have already verified it; this contract
+    /// has no way to check an EVM signature itself) — the actual
+    /// destination is `receiver`, a Cosmos address the EVM caller names
+    /// explicitly, since there's no general mapping from an EVM address
+    /// to a Cosmos one.
+    GmpBuy {
+        evm_sender: String,
+        receiver: String,
+        stake: Option<bool>,
+        referrer: Option<String>,
+    },
+    /// Sell `amount` of the supply denom, attached as funds, back into
+    /// the reserve. Only valid when the supply token is a token-factory
+    /// denom; in [`SupplyTokenMode::Cw20`] mode, sell via `Receive`
+    /// instead, sending the cw20 with a [`ReceiveMsg::Sell`] payload.
+    Sell {},
+    /// Handles a cw20 `Send`, dispatching on the embedded [`ReceiveMsg`].
+    /// The sending cw20 contract must match the reserve (for `Buy`) or
+    /// supply (for `Sell`) token's configured address.
+    Receive(Cw20ReceiveMsg),
+    /// Callable by [`Role::CurveAdmin`] or the owner. Reconfigure the
+    /// hatch/open/closed phase parameters, replacing the whole
+    /// [`PhaseConfig`] at once -- including its fee fields, which are
+    /// subject to the same `max_fee_rate` cap and
+    /// `MIN_FEE_UPDATE_INTERVAL_SECONDS` cooldown as
+    /// [`ExecuteMsg::UpdateFees`] whenever they actually change. Blocked
+    /// once [`ExecuteMsg::Freeze`] has been called.
+    UpdatePhaseConfig { phase_config: PhaseConfig },
+    /// Irreversibly renounce the owner and disable every
+    /// parameter-changing execute, so the curve and fee schedule become
+    /// permanently fixed.
+    Freeze {},
+    /// Transfer token-factory admin rights over the supply denom to a new
+    /// address, e.g. the DAO, when closing the commons or migrating.
+    UpdateDenomAdmin { new_admin: String },
+    /// Correct the supply denom's bank metadata (name, symbol, display,
+    /// and denom units), which cannot be changed once set at
+    /// instantiation without this.
+    UpdateDenomMetadata { metadata: Metadata },
+    /// Register (or clear, with `None`) the token-factory before-send
+    /// hook for the supply denom. While set and the phase is
+    /// [`Phase::Hatch`], transfers of the supply denom are blocked in
+    /// [`crate::contract::sudo`].
+    SetBeforeSendHook { contract_addr: Option<String> },
+    /// Advance the commons to the next phase. Phases only move forward:
+    /// Hatch -> Open -> Closed.
+    UpdatePhase { new_phase: Phase },
+    /// Grant (or revoke, with `None`) the compliance clawback role,
+    /// which may issue [`ExecuteMsg::ForceTransfer`]. Disabled by
+    /// default.
+    SetClawbackRole { address: Option<String> },
+    /// Permanently disable compliance clawback. There is no way to
+    /// re-enable it once called.
+    DisableClawbackPermanently {},
+    /// Claw tokens of the supply denom back from a compromised or
+    /// sanctioned account. Only callable by the address set via
+    /// [`ExecuteMsg::SetClawbackRole`].
+    ForceTransfer {
+        from: String,
+        to: String,
+        amount: Uint128,
+    },
+    /// Recover a denom mistakenly sent to the contract. May not be used
+    /// on the reserve or supply denom, which are accounted for
+    /// separately.
+    SweepUnrelatedFunds { denom: String, recipient: String },
+    /// Configure (or, with `oracle: None`, remove) a secondary reserve
+    /// denom accepted for buys alongside the canonical reserve. `oracle`
+    /// must answer [`OracleQueryMsg::Price`] with the conversion rate
+    /// from `denom` into the canonical reserve unit.
+    UpdateSecondaryReserve {
+        denom: String,
+        oracle: Option<String>,
+    },
+    /// Set (or, with `allowance: None`, revoke) `minter`'s remaining
+    /// allowance to mint the supply token outside the curve via
+    /// [`ExecuteMsg::AuxMint`].
+    SetAuxMinterAllowance {
+        minter: String,
+        allowance: Option<Uint128>,
+    },
+    /// Mint `amount` of the supply token to `recipient` against the
+    /// sender's allowance set via [`ExecuteMsg::SetAuxMinterAllowance`].
+    /// Increases [`crate::state::CurveState::supply`] like a curve buy
+    /// would, so the curve's spot price stays consistent.
+    AuxMint { recipient: String, amount: Uint128 },
+    /// Register `address` to receive fire-and-forget
+    /// [`abc_hooks::AbcHookMsg`] callbacks on every buy, sell, and phase
+    /// change.
+    AddHook { address: String },
+    /// Deregister a hook added via [`ExecuteMsg::AddHook`].
+    RemoveHook { address: String },
+    /// Sets (or, with `module: None`, clears) the DAO proposal module
+    /// trusted to report proposal lifecycle events via
+    /// [`ExecuteMsg::ProposalHook`]. The module must separately be
+    /// configured (e.g. via its own `AddProposalHook`) to actually send
+    /// them here.
+    SetCloseProposalModule { module: Option<String> },
+    /// Sets (or, with `limit: None`, clears) the sell amount above which
+    /// sells are rejected while a proposal from
+    /// [`ExecuteMsg::SetCloseProposalModule`] is open for voting, to
+    /// guard against front-running a close-the-commons proposal.
+    SetProposalSellLimit { limit: Option<Uint128> },
+    /// Delivered by the configured close-proposal module on every new
+    /// proposal and status change. Only accepted from that module.
+    ProposalHook(dao_proposal_hooks::ProposalHookMsg),
+    /// Sets (or, with `config: None`, clears) the DAO treasury address and
+    /// the percentage of every buy's net payment routed to it directly via
+    /// [`BankMsg`](cosmwasm_std::BankMsg) or a cw20 transfer, beyond the
+    /// [`PhaseConfig`] entry fee that flows into the funding pool.
+    SetTreasuryConfig { config: Option<TreasuryConfigMsg> },
+    /// Sets (or, with `config: None`, clears) the staking contract that
+    /// [`ExecuteMsg::Buy`] and [`ReceiveMsg::Buy`] can auto-stake minted
+    /// supply into.
+    SetStakingConfig { config: Option<StakingConfigMsg> },
+    /// Executes `msgs` (e.g. bank sends or wasm executes for a grant) and
+    /// debits `amount` from [`crate::state::CurveState::funding`], so a
+    /// DAO owner can spend the funding pool from proposal outcomes
+    /// without under- or over-drawing it. Fails if `amount` exceeds the
+    /// funding pool.
+    SpendFundingPool {
+        amount: Uint128,
+        msgs: Vec<CosmosMsg<Empty>>,
+    },
+    /// Sets (or, with `config: None`, clears) an external price oracle
+    /// pushed the curve's spot price after a trade, so this contract's
+    /// supply token can be listed as collateral elsewhere without that
+    /// oracle having to poll [`QueryMsg::CurveInfo`] itself.
+    SetPriceOracle {
+        config: Option<PriceOracleConfigMsg>,
+    },
+    /// Sets (or, with `config: None`, clears) the validator and liquidity
+    /// floor for staking idle native reserve. Only valid while the
+    /// reserve is the chain's staking-bonded native denom.
+    SetReserveStakingConfig {
+        config: Option<ReserveStakingConfigMsg>,
+    },
+    /// Delegates `amount` of the currently liquid reserve to
+    /// [`crate::state::ReserveStakingConfig::validator`]. Callable by
+    /// anyone; only ever moves reserve already sitting idle in the
+    /// contract's own balance.
+    StakeReserve { amount: Uint128 },
+    /// Begins unbonding `amount` of reserve previously staked via
+    /// [`ExecuteMsg::StakeReserve`]. Runs automatically from
+    /// [`ExecuteMsg::Sell`]/[`ReceiveMsg::Sell`] when a payout would
+    /// otherwise take the liquid reserve below
+    /// [`crate::state::ReserveStakingConfig::min_liquid_reserve`], but may
+    /// also be called directly.
+    UndelegateReserve { amount: Uint128 },
+    /// Withdraws any pending staking rewards from
+    /// [`crate::state::ReserveStakingConfig::validator`] into
+    /// [`crate::state::CurveState::funding`].
+    WithdrawReserveRewards {},
+    /// Sets (or, with `config: None`, clears) the whitelisted yield vault
+    /// and the percentage of [`crate::state::CurveState::reserve`] that
+    /// may be deployed into it at once.
+    SetReserveVaultConfig {
+        config: Option<ReserveVaultConfigMsg>,
+    },
+    /// Deposits `amount` of the reserve into
+    /// [`crate::state::ReserveVaultConfig::vault`], capped by
+    /// [`crate::state::ReserveVaultConfig::max_percent`] of
+    /// [`crate::state::CurveState::reserve`]. The deposited amount stays
+    /// counted in `CurveState::reserve`, since it still backs the curve;
+    /// only [`crate::state::RESERVE_VAULT_DEPOSITED`] tracks where it is.
+    DepositReserveToVault { amount: Uint128 },
+    /// Withdraws `amount` previously deposited via
+    /// [`ExecuteMsg::DepositReserveToVault`] back from the vault.
+    WithdrawReserveFromVault { amount: Uint128 },
+    /// Pulls whatever accrued yield is currently available from every
+    /// configured reserve-yield strategy (staking rewards, vault yield)
+    /// and credits it to [`crate::state::CurveState::funding`], never the
+    /// curve reserve. A no-op for any strategy that isn't configured or
+    /// has nothing accrued. Callable by anyone.
+    Harvest {},
+    /// Sets (or, with `config: None`, clears) the policy for periodically
+    /// swapping a capped slice of incoming reserve into a second asset
+    /// via a DEX router, diversifying the commons's holdings beyond the
+    /// reserve token.
+    SetDiversificationConfig {
+        config: Option<DiversificationConfigMsg>,
+    },
+    /// Sets (or, with `config: None`, clears) the policy for seeding a DEX
+    /// pool at the Hatch->Open transition, pairing freshly minted supply
+    /// with a slice of the reserve so secondary-market liquidity exists
+    /// from day one.
+    SetLiquiditySeedConfig {
+        config: Option<LiquiditySeedConfigMsg>,
+    },
+    /// Sets (or, with `threshold: None`, clears) the minimum ratio of
+    /// `CurveState::reserve` to the curve's own required reserve for the
+    /// current supply that [`ExecuteMsg::SpendReserve`] must leave behind.
+    SetReserveHealthThreshold { threshold: Option<Decimal> },
+    /// Pays `amount` of the reserve directly to `recipient`, bypassing the
+    /// curve, as long as [`ExecuteMsg::SetReserveHealthThreshold`]'s ratio
+    /// holds afterward. Errors if no threshold is configured, so this
+    /// can't be used until the DAO has explicitly opted into the risk.
+    SpendReserve { recipient: String, amount: Uint128 },
+    /// Atomically swaps the reserve to a new native denom, e.g. moving from
+    /// a bridged stablecoin to its native counterpart: the sender must
+    /// attach exactly `CurveState::reserve` of `new_denom`, which is
+    /// credited as the reserve, while the same amount of the old reserve
+    /// denom is paid back to the sender in the same message. Only
+    /// supported when the reserve is currently native, since a single
+    /// message can only bank-attach a native deposit.
+    MigrateReserveDenom { new_denom: String, new_decimals: u8 },
+    /// Sets (or, with `config: None`, clears) an exchange-rate source for
+    /// a reserve that's a yield-bearing derivative (e.g. stATOM) rather
+    /// than its plain underlying asset, so reserve-backed calculations
+    /// like [`ExecuteMsg::SpendReserve`]'s health-factor check operate on
+    /// underlying value instead of the face amount of derivative tokens
+    /// held.
+    SetReserveExchangeRateSource {
+        config: Option<ReserveExchangeRateSourceMsg>,
+    },
+    /// Sets (or, with `config: None`, clears) the share of entry/exit fees
+    /// diverted into the backstop pool instead of `CurveState::funding`.
+    SetBackstopConfig { config: Option<BackstopConfigMsg> },
+    /// Tops up `CurveState::reserve` from the backstop pool by `amount`,
+    /// bypassing governance entirely, as long as the reserve is currently
+    /// under-collateralized (its [`QueryMsg::ReserveHealthFactor`] is
+    /// below one). Callable by anyone, since it can only ever improve
+    /// solvency.
+    TopUpReserveFromBackstop { amount: Uint128 },
+    /// Sets (or, with `config: None`, clears) the cut of buy payments
+    /// credited to the `referrer` named in [`ExecuteMsg::Buy`], if any.
+    SetReferralConfig { config: Option<ReferralConfigMsg> },
+    /// Sets (or, with `config: None`, clears) the Polytone proxy allowed
+    /// to act as owner, for a remote (owner) DAO administering this
+    /// contract cross-chain. Once set, calls from `config.proxy` pass
+    /// every owner check in this contract the same way calls from the
+    /// literal cw-ownable owner do; ownership transfer/renunciation
+    /// itself (`UpdateOwnership`, [`ExecuteMsg::Freeze`]) still requires
+    /// being the literal owner, since those go through cw-ownable's own
+    /// mechanism directly.
+    SetPolytoneProxyConfig {
+        config: Option<PolytoneProxyConfigMsg>,
+    },
+    /// Sets (or, with `config: None`, clears) the DEX router
+    /// [`ExecuteMsg::ZapBuy`] swaps into. `None` (the default) means
+    /// zap-in buys aren't accepted.
+    SetZapConfig { config: Option<ZapConfigMsg> },
+    /// Sets (or, with `config: None`, clears) the key trusted to sign
+    /// [`ExecuteMsg::Buy`]'s `permit` vouchers. `None` (the default)
+    /// means no permit can ever be accepted, regardless of what a `Buy`
+    /// attaches.
+    SetPermitAuthorizerConfig {
+        config: Option<PermitAuthorizerConfigMsg>,
+    },
+    /// Swaps whatever denom is attached into the reserve denom via
+    /// [`ExecuteMsg::SetZapConfig`]'s router, then runs an ordinary buy
+    /// with the proceeds, in one transaction. `min_reserve_out` is the
+    /// least the swap must return or the whole zap fails with
+    /// [`crate::error::ContractError::ZapSlippage`], since the buy itself
+    /// runs from a submessage reply and so can't be previewed by the
+    /// caller beforehand. Always buys for `info.sender` with no auto-stake,
+    /// referrer, IBC forwarding, or idempotency key; use [`ExecuteMsg::Buy`]
+    /// directly (after a separate swap) if any of those are needed.
+    ZapBuy { min_reserve_out: Uint128 },
+    /// Mirror of [`ExecuteMsg::ZapBuy`] for selling: burns `amount` of the
+    /// supply denom (attached as funds, same as [`ExecuteMsg::Sell`]) and
+    /// routes the released reserve through [`ExecuteMsg::SetZapConfig`]'s
+    /// router for `output_denom` instead of paying the reserve out
+    /// directly. `min_output` guards against router slippage the same
+    /// way `ZapBuy`'s `min_reserve_out` does. If the swap submessage
+    /// itself fails (no route, no liquidity for `output_denom`), the
+    /// released reserve is refunded to the seller directly rather than
+    /// the whole sell failing.
+    ZapSell {
+        output_denom: String,
+        min_output: Uint128,
+    },
+    /// Pre-deposits `info.funds` (in the reserve or supply denom) into the
+    /// sender's own [`crate::state::OPERATOR_DEPOSITS`] balance, so an
+    /// address granted via [`ExecuteMsg::Grant`] can later buy or sell on
+    /// the sender's behalf via [`ExecuteMsg::BuyFor`]/[`ExecuteMsg::SellFor`]
+    /// without holding the tokens directly — factory denoms have no
+    /// cw20-style allowance a third party could otherwise be authorized
+    /// against. Only supported for a native reserve/supply denom; a cw20
+    /// balance must be sold/bought directly by its holder.
+    DepositForOperator {},
+    /// Withdraws `amount` of `denom` (the reserve or supply denom)
+    /// previously deposited via [`ExecuteMsg::DepositForOperator`] back to
+    /// the sender.
+    WithdrawOperatorDeposit { denom: String, amount: Uint128 },
+    /// Authorizes `operator` to spend the sender's
+    /// [`crate::state::OPERATOR_DEPOSITS`] balance via
+    /// [`ExecuteMsg::SellFor`]/[`ExecuteMsg::BuyFor`], capped by
+    /// `sell_limit`/`buy_limit` (each `None` for unlimited) and, if set,
+    /// no longer usable after `expires_at`. Replaces any existing grant to
+    /// the same operator.
+    Grant {
+        operator: String,
+        sell_limit: Option<Uint128>,
+        buy_limit: Option<Uint128>,
+        expires_at: Option<Timestamp>,
+    },
+    /// Revokes a grant previously issued to `operator` via
+    /// [`ExecuteMsg::Grant`].
+    Revoke { operator: String },
+    /// Sells `amount` of `owner`'s pre-deposited supply balance into the
+    /// reserve on their behalf, with the same fee accounting as
+    /// [`ExecuteMsg::Sell`], paying the proceeds out directly to `owner`.
+    /// Only callable by an address `owner` has granted via
+    /// [`ExecuteMsg::Grant`] with enough remaining `sell_limit`.
+    SellFor { owner: String, amount: Uint128 },
+    /// Buys with `amount` of `owner`'s pre-deposited reserve balance on
+    /// their behalf, with the same accounting as [`ExecuteMsg::Buy`] (no
+    /// staking, referrer, or IBC forwarding), minting directly to `owner`.
+    /// Only callable by an address `owner` has granted via
+    /// [`ExecuteMsg::Grant`] with enough remaining `buy_limit`.
+    BuyFor { owner: String, amount: Uint128 },
+    /// Sets up (or, if the sender already has one, reconfigures) a
+    /// recurring purchase: `amount_per_interval` of the reserve, bought
+    /// every `interval_seconds`, until the deposit funding it runs out.
+    /// `info.funds` (if any, in the reserve denom) top up the order's
+    /// deposit; reconfiguring an existing order keeps its current
+    /// deposit and `next_due` time. See [`ExecuteMsg::CrankRecurringPurchases`].
+    SetRecurringPurchase {
+        amount_per_interval: Uint128,
+        interval_seconds: u64,
+    },
+    /// Tops up the sender's existing recurring purchase deposit by
+    /// `info.funds` (in the reserve denom), without changing its
+    /// schedule.
+    DepositRecurringPurchase {},
+    /// Withdraws `amount` of the sender's deposited, not yet spent
+    /// recurring-purchase balance, without cancelling the order itself.
+    WithdrawRecurringPurchase { amount: Uint128 },
+    /// Cancels the sender's recurring purchase and refunds its remaining
+    /// deposit.
+    CancelRecurringPurchase {},
+    /// Permissionless: buys `amount_per_interval` for every recurring
+    /// purchase (up to `limit`, in ascending owner-address order) whose `next_due`
+    /// has passed and whose deposit can still cover it, minting to each
+    /// owner the same way [`ExecuteMsg::BuyFor`] does. A due order with
+    /// too little deposit left is skipped rather than erroring, so one
+    /// underfunded order can't block the rest of the crank.
+    CrankRecurringPurchases { limit: Option<u32> },
+    /// Places a resting order: `side: Buy` escrows `info.funds` in the
+    /// reserve denom and buys it all once the spot price drops to or
+    /// below `threshold_price`; `side: Sell` escrows `info.funds` in the
+    /// supply denom and sells it all once the price rises to or above
+    /// `threshold_price`. Expires at `expires_at`, after which
+    /// [`ExecuteMsg::CrankLimitOrders`] drops it and refunds the escrow
+    /// instead of matching it. Only supported for a native reserve and
+    /// token-factory supply, since matching replays [`ExecuteMsg::Buy`]/
+    /// [`ExecuteMsg::Sell`], which require the same.
+    PlaceLimitOrder {
+        side: LimitOrderSide,
+        threshold_price: Decimal,
+        expires_at: Timestamp,
+    },
+    /// Cancels `order_id`, refunding its escrow to the owner. Only
+    /// callable by the order's owner.
+    CancelLimitOrder { order_id: u64 },
+    /// Permissionless: matches every resting order (up to `limit`,
+    /// lowest id first) whose side condition the current spot price now
+    /// satisfies, replaying [`ExecuteMsg::Buy`]/[`ExecuteMsg::Sell`] with
+    /// the escrowed funds and crediting the result to the order's owner
+    /// the same way [`ExecuteMsg::BuyFor`]/[`ExecuteMsg::SellFor`] do. An
+    /// expired order is dropped and refunded instead of matched,
+    /// whether or not its price condition is met.
+    CrankLimitOrders { limit: Option<u32> },
+    /// Permissionless: once the phase has moved past `Hatch`, clears
+    /// [`crate::state::BATCH_AUCTION_STATE`]'s pooled hatch-phase
+    /// contributions at a single clearing price (computed the first
+    /// time this is called, by quoting the curve once for the whole
+    /// pool) and mints each contributor's pro-rata share, up to `limit`
+    /// contributors per call so a large hatcher count can be settled
+    /// over several transactions. A no-op if
+    /// [`crate::state::HatchConfig::batch_auction`] was never set or
+    /// everyone's already been paid out.
+    SettleBatchAuction { limit: Option<u32> },
+    /// Starts a streaming buy: `info.funds` (a single coin in the
+    /// reserve denom) vest into supply tokens linearly over
+    /// `duration_seconds`, cranked in slices by
+    /// [`ExecuteMsg::CrankStreamingBuys`] instead of moving the curve
+    /// all at once. Only one streaming buy per address at a time; fails
+    /// if the sender already has one still converting. Only supported
+    /// for a native reserve, like [`ExecuteMsg::Buy`].
+    SetStreamingBuy { duration_seconds: u64 },
+    /// Cancels the sender's streaming buy and refunds whatever of its
+    /// deposit hasn't vested yet.
+    CancelStreamingBuy {},
+    /// Permissionless: for every streaming buy (up to `limit`, in
+    /// ascending owner-address order) that hasn't fully vested, buys the
+    /// portion of `total_amount` that has vested since it was last
+    /// cranked, pro-rated by elapsed time against `duration_seconds`,
+    /// minting to each owner the same way [`ExecuteMsg::BuyFor`] does. A
+    /// stream that's fully vested is removed instead of left in storage.
+    CrankStreamingBuys { limit: Option<u32> },
+    /// Buys once against the curve with `info.funds` and splits the
+    /// minted supply across `recipients` (address, weight) pairs
+    /// pro-rata by weight, instead of minting it all to the caller --
+    /// useful for a grant program or payroll paid in the commons token.
+    /// Doesn't support staking, a referrer, or IBC forwarding; use
+    /// [`ExecuteMsg::Buy`] for that.
+    BuyAndDistribute { recipients: Vec<(String, Uint128)> },
+    /// Opens a crowdfunded buy pool with a `target` amount of the native
+    /// reserve; contributors join via [`ExecuteMsg::JoinBuyPool`] until
+    /// the target is met, then anyone can call
+    /// [`ExecuteMsg::SettleBuyPool`] to run one curve buy for the whole
+    /// pool and mint each contributor's pro-rata share. Returns the new
+    /// pool's id as a `pool_id` attribute.
+    OpenBuyPool { target: Uint128 },
+    /// Contributes `info.funds` (a single coin in the reserve denom) to
+    /// `pool_id`.
+    JoinBuyPool { pool_id: u64 },
+    /// Withdraws the sender's contribution to `pool_id` and refunds it,
+    /// as long as the pool hasn't been settled yet.
+    CancelBuyPoolContribution { pool_id: u64 },
+    /// Permissionless: once `pool_id`'s `total_pooled` has reached its
+    /// `target`, quotes the curve once for the whole pool (fixing a
+    /// single clearing price for every contributor, computed the first
+    /// time this is called) and mints each contributor's pro-rata
+    /// share, up to `limit` contributors per call so a pool with more
+    /// contributors than fit in one transaction can be settled over
+    /// several. See [`ExecuteMsg::SettleBatchAuction`], which settles
+    /// the same way.
+    SettleBuyPool { pool_id: u64, limit: Option<u32> },
+    /// Commits an OTC block trade: `side: Buy` escrows `info.funds` in
+    /// the reserve denom, `side: Sell` escrows it in the supply denom.
+    /// Rather than trading at the curve's marginal price immediately,
+    /// [`ExecuteMsg::CrankBlockTrades`] executes it once `delay_blocks`
+    /// have passed, at the [`QueryMsg::Twap`] over the last `delay_blocks`
+    /// blocks up to the crank's height (the blocks right after the commit
+    /// only if it's cranked as soon as it comes due) --
+    /// limiting the price impact and front-running risk a single large
+    /// trade would otherwise have on itself. Only supported for a native
+    /// reserve and token-factory supply, like [`ExecuteMsg::PlaceLimitOrder`],
+    /// and only while the phase is `Open`.
+    CommitBlockTrade { side: LimitOrderSide, delay_blocks: u64 },
+    /// Cancels `trade_id`, refunding its escrow to the owner. Only
+    /// callable before it's cranked, and only by the trade's owner.
+    CancelBlockTrade { trade_id: u64 },
+    /// Permissionless: executes every committed block trade (up to
+    /// `limit`, lowest id first) whose `delay_blocks` have elapsed,
+    /// pricing it at the TWAP over the last `delay_blocks` blocks up to
+    /// the crank's height instead of the curve's marginal price and crediting the result to the trade's owner the
+    /// same way [`ExecuteMsg::CrankLimitOrders`] does.
+    CrankBlockTrades { limit: Option<u32> },
+    /// Sets (or, with `config: None`, clears) the bonus offered on
+    /// [`ExecuteMsg::BuyWithLockup`].
+    SetLockupConfig { config: Option<LockupConfigMsg> },
+    /// Buys with `info.funds` like [`ExecuteMsg::Buy`], but instead of
+    /// minting to the caller, locks the minted amount plus a
+    /// [`LockupConfigMsg::bonus_percent`] bonus (drawn from the
+    /// configured budget) in the contract for `duration_seconds`, which
+    /// must be at least [`LockupConfigMsg::min_duration_seconds`].
+    /// Returns the new lock's id as a `lockup_id` attribute; claim it
+    /// with [`ExecuteMsg::ClaimLockup`] once unlocked.
+    BuyWithLockup { duration_seconds: u64 },
+    /// Sends `lockup_id`'s full locked amount to its owner. Only
+    /// callable once `Lockup::unlocks_at` has passed, and only by the
+    /// lock's owner.
+    ClaimLockup { lockup_id: u64 },
+    /// Sets (or, with `config: None`, clears) the holder-tenure discount
+    /// schedule applied to the exit fee on [`ExecuteMsg::Sell`],
+    /// [`ExecuteMsg::ZapSell`], and the cw20 sell path. Tiers must have
+    /// strictly increasing `min_tenure_seconds`.
+    SetHolderDiscountConfig { config: Option<HolderDiscountConfigMsg> },
+    /// Owner-only: lets `address` act as `role` without holding
+    /// ownership itself. Idempotent.
+    GrantRole { role: Role, address: String },
+    /// Owner-only: the inverse of [`ExecuteMsg::GrantRole`]. Idempotent;
+    /// a no-op if `address` doesn't currently hold `role`.
+    RevokeRole { role: Role, address: String },
+    /// Callable by [`Role::Pauser`] or the owner. Halts every path that
+    /// mints or burns supply against the curve while `paused` is true --
+    /// see [`crate::state::TRADING_PAUSED`] for exactly which entry
+    /// points that covers.
+    SetTradingPaused { paused: bool },
+    /// Callable by [`Role::FeeAdmin`] or the owner. Patches only the fee
+    /// fields of [`crate::state::PhaseConfig`] that are `Some`, leaving
+    /// contribution limits, the allowlist, and the batch-auction toggle
+    /// untouched; use [`ExecuteMsg::UpdatePhaseConfig`] for those. Blocked
+    /// once [`ExecuteMsg::Freeze`] has been called.
+    UpdateFees {
+        hatch_entry_fee: Option<Decimal>,
+        open_entry_fee: Option<Decimal>,
+        open_exit_fee: Option<Decimal>,
+    },
+    /// Callable by [`Role::AllowlistManager`] or the owner. Adds `add`
+    /// and then removes `remove` from
+    /// [`crate::state::HatchConfig::allowlist`]. Errors if the allowlist
+    /// isn't already `Some`; use [`ExecuteMsg::UpdatePhaseConfig`] to
+    /// turn it on or off. Blocked once [`ExecuteMsg::Freeze`] has been
+    /// called.
+    UpdateHatchAllowlist { add: Vec<String>, remove: Vec<String> },
+    /// Owner-only. Sets (or, with `config: None`, clears) the delay that
+    /// gates [`TimelockedAction`]s. While set, [`ExecuteMsg::UpdateFees`],
+    /// [`ExecuteMsg::UpdatePhaseConfig`], and closing the commons via
+    /// [`ExecuteMsg::UpdatePhase`] can no longer be issued directly and
+    /// must go through [`ExecuteMsg::QueueTimelockedAction`] instead.
+    SetTimelockConfig { config: Option<TimelockConfigMsg> },
+    /// Queues `action` for execution once [`TimelockConfigMsg::delay_seconds`]
+    /// has elapsed, requiring the same authorization the action would
+    /// need if issued directly. Errors if no timelock is configured.
+    QueueTimelockedAction { action: TimelockedAction },
+    /// Runs a queued action once its delay has elapsed. Permissionless,
+    /// like the other `Crank*` executes: the action was already
+    /// authorized at queue time, and is re-checked against whoever
+    /// queued it in case their authorization has since lapsed.
+    ExecuteTimelockedAction { id: u64 },
+    /// Cancels a queued action before it executes. Requires the same
+    /// authorization the action itself would.
+    CancelTimelockedAction { id: u64 },
+    /// Owner-only. Sets (or, with `operator: None`, clears) a narrowly
+    /// scoped maintenance delegate: `operator` may then call
+    /// [`ExecuteMsg::UpdatePhase`] (for any transition except to
+    /// `Closed`, which always requires the owner) and/or
+    /// [`ExecuteMsg::UpdateDenomMetadata`], according to which
+    /// `crate::state::OPERATOR_PERM_*` bits are set in `permissions`,
+    /// without holding ownership itself.
+    SetMaintenanceOperator { operator: Option<MaintenanceOperatorMsg> },
+    /// Owner-only. Sets (or, with `config: None`, clears) a security
+    /// council address that can cancel a queued
+    /// [`crate::state::TimelockedAction::Close`] via
+    /// [`ExecuteMsg::VetoTimelockedAction`], within
+    /// `VetoConfigMsg::window_seconds` of it being queued. This fork has
+    /// no `Closed` -> `Open` re-open transition to veto; `Close` is the
+    /// only queued phase change that exists.
+    SetVetoAddress { config: Option<VetoConfigMsg> },
+    /// Callable by the configured veto address or the owner, within the
+    /// configured window. Cancels the pending
+    /// [`crate::state::TimelockedAction::Close`] timelock `id` and
+    /// records `reason` in the response attributes. Errors if `id`
+    /// isn't a pending `Close`, or if the window has elapsed -- past
+    /// that, only [`ExecuteMsg::CancelTimelockedAction`] still applies.
+    VetoTimelockedAction { id: u64, reason: String },
+    /// Owner-only. Sets (or, with `config: None`, disables) a
+    /// token-weighted emergency closure vote: any holder can then call
+    /// [`ExecuteMsg::SignalEmergencyClose`] to push the commons toward
+    /// [`crate::state::Phase::Closed`] without any owner or DAO action,
+    /// a backstop independent of governance.
+    SetEmergencyCloseConfig { config: Option<EmergencyCloseConfigMsg> },
+    /// Deposits the attached supply tokens as a signal for emergency
+    /// closure. If the running total for the current round reaches
+    /// `EmergencyCloseConfigMsg::quorum_ratio` of the outstanding supply
+    /// within `window_seconds` of the round's first signal, the commons
+    /// closes automatically as part of this call. Errors if no vote is
+    /// configured or the commons is already `Closed`. Only valid when
+    /// the supply token is a token-factory denom; in
+    /// [`SupplyTokenMode::Cw20`] mode, signal via `Receive` instead,
+    /// sending the cw20 with a [`ReceiveMsg::SignalEmergencyClose`]
+    /// payload.
+    SignalEmergencyClose {},
+    /// Reclaims a signaler's deposit from round `round`, whether or not
+    /// that round is still current. No-op restriction beyond having a
+    /// nonzero deposit to withdraw -- signaling never locks funds past
+    /// the depositor's own choice, even if quorum is never reached.
+    WithdrawEmergencyCloseSignal { round: u64 },
+    /// Owner-only. Sets (or, with `config: None`, disables) the guardian
+    /// set that can force an ownership rotation via
+    /// [`ExecuteMsg::ProposeRecovery`]/[`ExecuteMsg::ApproveRecovery`]/
+    /// [`ExecuteMsg::ExecuteRecovery`] without needing the current
+    /// owner's signature at all -- the recovery path for a lost or
+    /// compromised owner key. `threshold` must be between 1 and
+    /// `guardians.len()`.
+    SetRecoveryGuardians { config: Option<RecoveryConfigMsg> },
+    /// Guardian-only. Starts a pending recovery proposing `new_owner`,
+    /// counting the caller's own approval, or errors
+    /// [`crate::ContractError::RecoveryProposalConflict`] if a proposal
+    /// for a different `new_owner` is already pending.
+    ProposeRecovery { new_owner: String },
+    /// Guardian-only. Adds the caller's approval to the pending
+    /// recovery proposal. Errors if there is none, or if the caller
+    /// already approved it.
+    ApproveRecovery {},
+    /// Owner- or guardian-callable. Discards the pending recovery
+    /// proposal, if any -- the owner's way to reject a mistaken or
+    /// malicious recovery attempt during
+    /// `RecoveryConfigMsg::delay_seconds`, or a guardian's way to
+    /// retract one.
+    CancelRecovery {},
+    /// Permissionless, like the other `Crank*` executes. Once the
+    /// pending proposal has `RecoveryConfigMsg::threshold` approvals and
+    /// `delay_seconds` has elapsed since it was proposed, forces the
+    /// contract's `cw_ownable` owner to `new_owner` and clears the
+    /// proposal, bypassing the old owner's signature.
+    ExecuteRecovery {},
+}
+
+/// The payload for [`ExecuteMsg::SetTimelockConfig`]. `delay_seconds`
+/// must be greater than zero.
+#[cw_serde]
+pub struct TimelockConfigMsg {
+    pub delay_seconds: u64,
+}
+
+/// The payload for [`ExecuteMsg::SetVetoAddress`].
+#[cw_serde]
+pub struct VetoConfigMsg {
+    pub veto: String,
+    pub window_seconds: u64,
+}
+
+/// The payload for [`ExecuteMsg::SetEmergencyCloseConfig`].
+#[cw_serde]
+pub struct EmergencyCloseConfigMsg {
+    pub quorum_ratio: Decimal,
+    pub window_seconds: u64,
+}
+
+/// The payload for [`ExecuteMsg::SetRecoveryGuardians`].
+#[cw_serde]
+pub struct RecoveryConfigMsg {
+    pub guardians: Vec<String>,
+    pub threshold: u32,
+    pub delay_seconds: u64,
+}
+
+/// The payload for [`ExecuteMsg::SetMaintenanceOperator`]. `permissions`
+/// is a bitmask of the `crate::state::OPERATOR_PERM_*` flags.
+#[cw_serde]
+pub struct MaintenanceOperatorMsg {
+    pub operator: String,
+    pub permissions: u8,
+}
+
+/// The payload for [`ExecuteMsg::SetStakingConfig`].
+#[cw_serde]
+pub struct StakingConfigMsg {
+    pub staking_contract: String,
+    pub default_stake: bool,
+}
+
+/// Executed against the configured staking contract when auto-staking a
+/// buy: a bank-attached native coin of the supply denom for
+/// [`SupplyTokenMode::TokenFactory`], or a cw20 `Send` carrying this same
+/// payload for [`SupplyTokenMode::Cw20`]. The staking contract must stake
+/// the attached amount for `recipient`, not the caller.
+#[cw_serde]
+pub enum StakeExecuteMsg {
+    StakeFor { recipient: String },
+}
+
+/// The payload for [`ExecuteMsg::SetTreasuryConfig`]. `percent` must be
+/// greater than zero and no more than one.
+#[cw_serde]
+pub struct TreasuryConfigMsg {
+    pub treasury: String,
+    pub percent: Decimal,
+}
+
+/// The payload for [`ExecuteMsg::SetPriceOracle`]. `push_interval` is the
+/// minimum number of blocks between pushes, so a burst of trades in the
+/// same few blocks doesn't spam the oracle with a submessage each.
+#[cw_serde]
+pub struct PriceOracleConfigMsg {
+    pub oracle: String,
+    pub push_interval: u64,
+}
+
+/// Executed against [`ExecuteMsg::SetPriceOracle`]'s configured oracle
+/// after a trade crosses `push_interval` blocks since the last push.
+#[cw_serde]
+pub enum PriceOracleExecuteMsg {
+    UpdatePrice { price: Decimal },
+}
+
+/// The payload for [`ExecuteMsg::SetReserveStakingConfig`].
+#[cw_serde]
+pub struct ReserveStakingConfigMsg {
+    pub validator: String,
+    pub min_liquid_reserve: Uint128,
+}
+
+/// The payload for [`ExecuteMsg::SetReserveVaultConfig`]. `max_percent`
+/// must be greater than zero and no more than one.
+#[cw_serde]
+pub struct ReserveVaultConfigMsg {
+    pub vault: String,
+    pub max_percent: Decimal,
+}
+
+/// The adapter interface a vault contract accepted by
+/// [`ExecuteMsg::SetReserveVaultConfig`] must implement. `Deposit` is sent
+/// as a bank-attached execute (native reserve) or a cw20 `Send` (cw20
+/// reserve) carrying this payload; `Withdraw` is executed directly.
+#[cw_serde]
+pub enum VaultAdapterExecuteMsg {
+    Deposit {},
+    Withdraw { amount: Uint128 },
+}
+
+/// Queried against a configured vault to check this contract's deposited
+/// balance, e.g. for off-chain accounting reconciliation.
+#[cw_serde]
+pub enum VaultAdapterQueryMsg {
+    Balance { account: String },
+}
+
+#[cw_serde]
+pub struct VaultBalanceResponse {
+    pub balance: Uint128,
+}
+
+/// The payload for [`ExecuteMsg::SetDiversificationConfig`]. `percent`
+/// must be greater than zero and no more than one.
+#[cw_serde]
+pub struct DiversificationConfigMsg {
+    pub router: String,
+    pub target_denom: String,
+    pub percent: Decimal,
+    pub swap_interval: u64,
+    pub max_swap_amount: Uint128,
+}
+
+/// Executed against [`ExecuteMsg::SetDiversificationConfig`]'s configured
+/// router to swap accrued reserve into `target_denom`. Delivered as a
+/// bank-attached execute for native reserve, or a cw20 `Send` carrying
+/// this payload for cw20 reserve.
+#[cw_serde]
+pub enum DexRouterExecuteMsg {
+    Swap { target_denom: String },
+}
+
+/// The payload for [`ExecuteMsg::SetLiquiditySeedConfig`].
+#[cw_serde]
+pub struct LiquiditySeedConfigMsg {
+    pub pool_creator: String,
+    pub supply_amount: Uint128,
+    pub reserve_amount: Uint128,
+}
+
+/// Executed against [`ExecuteMsg::SetLiquiditySeedConfig`]'s configured
+/// `pool_creator` at the Hatch->Open transition, with the freshly minted
+/// supply and reserve slice attached as funds (e.g. an Osmosis gamm
+/// create-pool adapter).
+#[cw_serde]
+pub enum DexPoolExecuteMsg {
+    CreatePool {},
+}
+
+/// Queried against a secondary reserve's configured oracle contract.
+#[cw_serde]
+pub enum OracleQueryMsg {
+    /// Returns a [`PriceResponse`] with the rate to multiply an amount of
+    /// `denom` by to get its value in the canonical reserve unit.
+    Price { denom: String },
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    pub rate: Decimal,
+}
+
+/// The payload for [`ExecuteMsg::SetBackstopConfig`]. `percent` must be
+/// greater than zero and no more than one.
+#[cw_serde]
+pub struct BackstopConfigMsg {
+    pub percent: Decimal,
+}
+
+/// The payload for [`ExecuteMsg::SetReferralConfig`]. `percent` must be
+/// greater than zero and no more than one.
+#[cw_serde]
+pub struct ReferralConfigMsg {
+    pub percent: Decimal,
+}
+
+/// The payload for [`ExecuteMsg::SetLockupConfig`]. `bonus_percent` must
+/// be greater than zero, and `remaining_bonus_budget` funds the total
+/// bonuses `ExecuteMsg::BuyWithLockup` can ever mint under this config.
+#[cw_serde]
+pub struct LockupConfigMsg {
+    pub min_duration_seconds: u64,
+    pub bonus_percent: Decimal,
+    pub remaining_bonus_budget: Uint128,
+}
+
+/// The payload for [`ExecuteMsg::SetHolderDiscountConfig`]. `tiers` must
+/// be sorted by strictly increasing `min_tenure_seconds`, and every
+/// `exit_fee_discount` must be greater than zero and no more than one.
+#[cw_serde]
+pub struct HolderDiscountConfigMsg {
+    pub tiers: Vec<crate::state::HolderDiscountTier>,
+}
+
+/// The payload for [`ExecuteMsg::SetPolytoneProxyConfig`]. `proxy` is the
+/// address of the Polytone proxy contract instantiated on this chain for
+/// the remote owner DAO on `connection_id`.
+#[cw_serde]
+pub struct PolytoneProxyConfigMsg {
+    pub connection_id: String,
+    pub proxy: String,
+}
+
+/// The payload for [`ExecuteMsg::SetZapConfig`].
+#[cw_serde]
+pub struct ZapConfigMsg {
+    pub router: String,
+}
+
+/// The payload for [`ExecuteMsg::SetPermitAuthorizerConfig`].
+#[cw_serde]
+pub struct PermitAuthorizerConfigMsg {
+    /// A compressed secp256k1 public key (33 bytes).
+    pub pubkey: Binary,
+}
+
+/// The exact bytes signed to produce a [`BuyPermit::signature`]: the
+/// authorizer (or whatever off-chain tooling signs on its behalf) builds
+/// this struct, serializes it with [`cosmwasm_std::to_vec`], hashes it
+/// with SHA-256, and signs the hash.
+#[cw_serde]
+pub struct PermitPayload {
+    pub buyer: String,
+    pub max_amount: Uint128,
+    pub expires_at: Timestamp,
+}
+
+/// A voucher signed by [`ExecuteMsg::SetPermitAuthorizerConfig`]'s key,
+/// authorizing the buyer named in [`PermitPayload::buyer`] to buy up to
+/// `max_amount` of the reserve during the hatch phase without being on
+/// [`crate::state::HatchConfig::allowlist`], until `expires_at`.
+#[cw_serde]
+pub struct BuyPermit {
+    pub max_amount: Uint128,
+    pub expires_at: Timestamp,
+    pub signature: Binary,
+}
+
+/// The payload for [`ExecuteMsg::Buy`]'s `ibc_forward`. `timeout` is a
+/// number of seconds from the buy's block time after which the transfer
+/// can time out.
+#[cw_serde]
+pub struct IbcForwardMsg {
+    pub channel: String,
+    pub to_address: String,
+    pub timeout: u64,
+}
+
+/// The payload for [`ExecuteMsg::SetReserveExchangeRateSource`].
+#[cw_serde]
+pub struct ReserveExchangeRateSourceMsg {
+    pub oracle: String,
+    /// Maximum age, in seconds, of the oracle's reported rate before it's
+    /// considered too stale to use.
+    pub max_staleness: u64,
+}
+
+/// Queried against [`ExecuteMsg::SetReserveExchangeRateSource`]'s
+/// configured oracle for a yield-bearing reserve derivative.
+#[cw_serde]
+pub enum ExchangeRateQueryMsg {
+    /// Returns an [`ExchangeRateResponse`] with the current rate to
+    /// convert one reserve token into its underlying value.
+    ExchangeRate {},
+}
+
+#[cw_serde]
+pub struct ExchangeRateResponse {
+    pub rate: Decimal,
+    /// When `rate` was last updated, checked against the configured
+    /// `max_staleness`.
+    pub last_updated: Timestamp,
+}
+
+#[cw_serde]
+pub enum MigrateMsg {}
+
+/// The payload embedded in a cw20 `Send` to this contract.
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// Buy supply tokens, when the reserve is a cw20 (see
+    /// [`ReserveTokenMode::Cw20`]). See [`ExecuteMsg::Buy`] for `stake`,
+    /// `referrer`, and `ibc_forward`.
+    Buy {
+        stake: Option<bool>,
+        referrer: Option<String>,
+        ibc_forward: Option<IbcForwardMsg>,
+    },
+    /// Sell supply tokens back into the reserve, when the supply token is
+    /// a cw20 (see [`SupplyTokenMode::Cw20`]).
+    Sell {},
+    /// Deposit supply tokens as a signal for emergency closure, when the
+    /// supply token is a cw20 (see [`SupplyTokenMode::Cw20`]). See
+    /// [`ExecuteMsg::SignalEmergencyClose`] for the token-factory
+    /// equivalent.
+    SignalEmergencyClose {},
+    /// Swaps the sent amount of [`ImportLegacyCurveMsg::legacy_cw20`] for
+    /// the same amount of this contract's supply token, 1:1. The legacy
+    /// tokens are held by this contract (there's nowhere useful to send
+    /// them back to); only available if `import_legacy_curve` was set at
+    /// instantiation, and only up to its `supply_snapshot`.
+    ClaimLegacyBalance {},
+}
+
+/// Delivered by the token factory module to the registered before-send
+/// hook contract ahead of every transfer of a hooked denom. Returning an
+/// error from [`crate::contract::sudo`] blocks the transfer.
+///
+/// Also carries the ibc-hooks callback convention: when an
+/// [`ExecuteMsg::IbcHooksBuy`] memo names this contract in
+/// `ibc_callback`, the chain's ibc-hooks module calls
+/// [`SudoMsg::IBCLifecycleComplete`] once the transfer that carried it
+/// settles, so this contract's `minted` attribute reaches the ack (or
+/// timeout) delivered back to the source chain.
+#[cw_serde]
+pub enum SudoMsg {
+    BlockBeforeSend {
+        from: String,
+        to: String,
+        amount: cosmwasm_std::Coin,
+    },
+    IBCLifecycleComplete(IbcLifecycleComplete),
+    /// Jumps straight to `new_phase`, skipping [`ExecuteMsg::UpdatePhase`]'s
+    /// forward-only check, so a lifecycle scenario (a hatch that's meant to
+    /// have expired, say) doesn't need to actually cross the raise cap or
+    /// wait for an owner call to get there. Test-only: block time itself
+    /// doesn't need a sudo message, since `cw-multi-test`'s `App::update_block`
+    /// already advances it directly.
+    #[cfg(any(test, feature = "test-utils"))]
+    ForcePhase { new_phase: Phase },
+    /// Overwrites [`crate::state::CurveState`]'s `supply`/`reserve` directly,
+    /// so a test can start from an arbitrary point on the curve (e.g. right
+    /// before a vesting cliff or hatch-cap crossing) without replaying every
+    /// buy that would otherwise get it there. Test-only.
+    #[cfg(any(test, feature = "test-utils"))]
+    SetCurveState { supply: Uint128, reserve: Uint128 },
+}
+
+/// The two outcomes an ibc-hooks callback can report, mirroring the
+/// `x/ibc-hooks` convention used across the ecosystem.
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    /// The source chain received an acknowledgement for the packet at
+    /// `channel`/`sequence`; `success` reflects whether it was an error
+    /// ack, and `ack` carries the raw acknowledgement data.
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    /// The packet at `channel`/`sequence` timed out before being
+    /// relayed, so the transfer (and any [`ExecuteMsg::IbcHooksBuy`] it
+    /// carried) never happened.
+    IbcTimeout { channel: String, sequence: u64 },
+}
+
+/// The packet body sent to every channel in
+/// [`crate::state::MIRROR_CHANNELS`] after each trade, so a "mirror"
+/// contract on another chain can keep a read-only replica of this
+/// commons' curve state without polling. See the `ibc_channel_*`/
+/// `ibc_packet_*` entry points in `src/ibc.rs` for the handshake a
+/// counterparty contract subscribes with.
+#[cw_serde]
+pub struct StateSyncPacket {
+    pub supply: Uint128,
+    pub reserve: Uint128,
+    pub spot_price: Decimal,
+    pub phase: Phase,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(CurveInfoResponse)]
+    CurveInfo {},
+    #[returns(PhaseConfigResponse)]
+    PhaseConfig {},
+    #[returns(::cw_ownable::Ownership<::cosmwasm_std::Addr>)]
+    Ownership {},
+    #[returns(::cosmwasm_std::Addr)]
+    Dao {},
+    #[returns(::cosmwasm_std::Addr)]
+    DenomAdmin {},
+    /// The oracle configured for a secondary reserve `denom`, if any.
+    #[returns(Option<::cosmwasm_std::Addr>)]
+    SecondaryReserveOracle { denom: String },
+    /// The resolved IBC denom trace behind the reserve token, if it's an
+    /// `ibc/<hash>` denom.
+    #[returns(Option<crate::state::DenomTrace>)]
+    ReserveDenomTrace {},
+    /// `minter`'s remaining allowance to mint via
+    /// [`ExecuteMsg::AuxMint`].
+    #[returns(Uint128)]
+    AuxMinterAllowance { minter: String },
+    /// The outstanding supply at `height`, or now if `height` is `None`.
+    #[returns(Uint128)]
+    SupplyAtHeight { height: Option<u64> },
+    /// The reserve held at `height`, or now if `height` is `None`.
+    #[returns(Uint128)]
+    ReserveAtHeight { height: Option<u64> },
+    /// The contracts registered via [`ExecuteMsg::AddHook`].
+    #[returns(::cw_hooks::HooksResponse)]
+    Hooks {},
+    /// The DAO proposal module set via
+    /// [`ExecuteMsg::SetCloseProposalModule`], if any.
+    #[returns(Option<::cosmwasm_std::Addr>)]
+    CloseProposalModule {},
+    /// The sell limit set via [`ExecuteMsg::SetProposalSellLimit`], if
+    /// any, and the proposal IDs currently restricting sells to it.
+    #[returns(ProposalSellLimitResponse)]
+    ProposalSellLimit {},
+    /// The DAO treasury split set via [`ExecuteMsg::SetTreasuryConfig`],
+    /// if any.
+    #[returns(Option<crate::state::TreasuryConfig>)]
+    TreasuryConfig {},
+    /// The staking contract set via [`ExecuteMsg::SetStakingConfig`], if
+    /// any.
+    #[returns(Option<crate::state::StakingConfig>)]
+    StakingConfig {},
+    /// The legacy cw20-bonding curve imported via
+    /// [`InstantiateMsg::import_legacy_curve`], if any, and how much of
+    /// its `supply_snapshot` is still unclaimed.
+    #[returns(Option<crate::state::LegacyCurve>)]
+    LegacyCurve {},
+    /// The history of phase transitions recorded via
+    /// [`ExecuteMsg::UpdatePhase`], oldest first.
+    #[returns(Vec<crate::state::PhaseTransition>)]
+    PhaseTransitions {},
+    /// The time-weighted average spot price over the last `window` blocks,
+    /// derived from the on-chain cumulative price accumulator rather than
+    /// the current (spot-manipulable) `CurveInfo` price.
+    #[returns(Decimal)]
+    Twap { window: u64 },
+    /// The external price oracle set via [`ExecuteMsg::SetPriceOracle`],
+    /// if any.
+    #[returns(Option<crate::state::PriceOracleConfig>)]
+    PriceOracleConfig {},
+    /// Buy/sell volume summed over the last `window` daily buckets
+    /// (including today's, partial), so a dashboard can show 24h/7d
+    /// activity without running a full indexer.
+    #[returns(VolumeResponse)]
+    Volume { window: u64 },
+    /// Post-trade `(height, reserve, supply, spot_price)` checkpoints,
+    /// oldest first, for on-chain charting and retroactive analysis.
+    /// Paginated by height; `start_after` excludes the given height.
+    #[returns(Vec<crate::state::CurveCheckpoint>)]
+    HistoricalCurveInfo {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Individual buy/sell trades, oldest first, for small-chain explorers
+    /// without their own indexer. Paginated by an internal trade counter,
+    /// not height; `start_after` excludes the given counter value.
+    #[returns(Vec<crate::state::TradeRecord>)]
+    ListTrades {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Daily OHLC candles for epoch days `from..=to` (missing days, with no
+    /// trades, are omitted), so a lightweight front-end can render a price
+    /// chart straight from the contract.
+    #[returns(Vec<CandleResponse>)]
+    Candles { from: u64, to: u64 },
+    /// `address`'s lifetime reserve contributed, tokens bought, and tokens
+    /// sold, for contributor recognition or retroactive airdrops.
+    #[returns(crate::state::AccountStats)]
+    AccountStats { address: String },
+    /// The validator and liquidity floor set via
+    /// [`ExecuteMsg::SetReserveStakingConfig`], if any.
+    #[returns(Option<crate::state::ReserveStakingConfig>)]
+    ReserveStakingConfig {},
+    /// How much of the reserve is currently bonded versus unbonding.
+    #[returns(crate::state::ReserveStakingState)]
+    ReserveStakingState {},
+    /// The vault and deployable percentage set via
+    /// [`ExecuteMsg::SetReserveVaultConfig`], if any.
+    #[returns(Option<crate::state::ReserveVaultConfig>)]
+    ReserveVaultConfig {},
+    /// How much of the reserve is currently deposited in the configured
+    /// vault.
+    #[returns(Uint128)]
+    ReserveVaultDeposited {},
+    /// Lifetime reserve-yield credited to `CurveState::funding` via
+    /// [`ExecuteMsg::Harvest`], across every strategy.
+    #[returns(Uint128)]
+    HarvestedTotal {},
+    /// The router, target denom, and swap policy set via
+    /// [`ExecuteMsg::SetDiversificationConfig`], if any.
+    #[returns(Option<crate::state::DiversificationConfig>)]
+    DiversificationConfig {},
+    /// Reserve accrued for diversification but not yet swapped, since
+    /// `swap_interval` hasn't elapsed or the accrued amount is still
+    /// under a single swap's `max_swap_amount`.
+    #[returns(Uint128)]
+    PendingDiversification {},
+    /// Lifetime total of `target_denom` credited from diversification
+    /// swaps via [`ExecuteMsg::SetDiversificationConfig`].
+    #[returns(Uint128)]
+    DiversifiedBalance {},
+    /// The pool creator and seed amounts set via
+    /// [`ExecuteMsg::SetLiquiditySeedConfig`], if any.
+    #[returns(Option<crate::state::LiquiditySeedConfig>)]
+    LiquiditySeedConfig {},
+    /// The minimum post-spend ratio set via
+    /// [`ExecuteMsg::SetReserveHealthThreshold`], if any.
+    #[returns(Option<Decimal>)]
+    ReserveHealthThreshold {},
+    /// The current ratio of `CurveState::reserve` to the curve's own
+    /// required reserve for the current supply, i.e. what
+    /// [`ExecuteMsg::SpendReserve`] checks against
+    /// `ReserveHealthThreshold`. Reports [`Decimal::one`] while the curve
+    /// has no supply yet, since there's nothing to be under-collateralized
+    /// against.
+    #[returns(Decimal)]
+    ReserveHealthFactor {},
+    /// The oracle and staleness bound set via
+    /// [`ExecuteMsg::SetReserveExchangeRateSource`], if any.
+    #[returns(Option<crate::state::ReserveExchangeRateConfig>)]
+    ReserveExchangeRateSource {},
+    /// The underlying value of `CurveState::reserve`, per the configured
+    /// [`ExecuteMsg::SetReserveExchangeRateSource`]. Equal to
+    /// `CurveState::reserve` itself when no source is configured, i.e.
+    /// the reserve token already is the underlying asset.
+    #[returns(Uint128)]
+    EffectiveReserveValue {},
+    /// The fee share set via [`ExecuteMsg::SetBackstopConfig`], if any.
+    #[returns(Option<crate::state::BackstopConfig>)]
+    BackstopConfig {},
+    /// The reserve-denominated balance accumulated via
+    /// [`ExecuteMsg::SetBackstopConfig`], spendable only through
+    /// [`ExecuteMsg::TopUpReserveFromBackstop`].
+    #[returns(Uint128)]
+    BackstopBalance {},
+    /// The cut set via [`ExecuteMsg::SetReferralConfig`], if any.
+    #[returns(Option<crate::state::ReferralConfig>)]
+    ReferralConfig {},
+    /// Lifetime reserve-denominated amount credited to `referrer` via
+    /// [`ExecuteMsg::Buy`]'s `referrer` field.
+    #[returns(Uint128)]
+    ReferralEarned { referrer: String },
+    /// The recorded result of a past [`ExecuteMsg::Buy`] made with
+    /// `idempotency_key`, or `None` if that key hasn't been used (or its
+    /// record has expired and is eligible for reuse).
+    #[returns(Option<crate::state::IdempotencyRecord>)]
+    IdempotencyKeyStatus { key: String },
+    /// The Polytone proxy set via [`ExecuteMsg::SetPolytoneProxyConfig`],
+    /// if any, allowed to act as owner alongside the literal cw-ownable
+    /// owner.
+    #[returns(Option<crate::state::PolytoneProxyConfig>)]
+    PolytoneProxyConfig {},
+    /// IBC channel ids currently subscribed to [`StateSyncPacket`]
+    /// pushes, per [`crate::state::MIRROR_CHANNELS`].
+    #[returns(Vec<String>)]
+    MirrorChannels {},
+    /// The router set via [`ExecuteMsg::SetZapConfig`], if any.
+    #[returns(Option<crate::state::ZapConfig>)]
+    ZapConfig {},
+    /// `owner`'s pre-deposited reserve/supply balances from
+    /// [`ExecuteMsg::DepositForOperator`].
+    #[returns(crate::state::OperatorDeposit)]
+    OperatorDeposit { owner: String },
+    /// The grant `owner` issued to `operator` via [`ExecuteMsg::Grant`], if
+    /// any.
+    #[returns(Option<crate::state::OperatorGrant>)]
+    OperatorGrant { owner: String, operator: String },
+    /// Quotes how much supply `net_payment` reserve tokens would mint at
+    /// the current curve state. `net_payment` is the amount that would
+    /// actually reach the curve, i.e. after whatever entry fee, treasury
+    /// split, diversification, or referral cut a real
+    /// [`ExecuteMsg::Buy`] of the gross amount would deduct first; this
+    /// only runs the curve math itself, not that deduction logic. Does
+    /// not mutate state.
+    #[returns(crate::curves::Quote)]
+    SimulateBuy { net_payment: Uint128 },
+    /// Quotes how much reserve `amount` of the supply token would
+    /// release at the current curve state, before the exit fee a real
+    /// [`ExecuteMsg::Sell`] would then deduct. Does not mutate state.
+    #[returns(crate::curves::Quote)]
+    SimulateSell { amount: Uint128 },
+    /// `owner`'s recurring purchase set up via
+    /// [`ExecuteMsg::SetRecurringPurchase`], if any.
+    #[returns(Option<crate::state::RecurringOrder>)]
+    RecurringPurchase { owner: String },
+    /// A single resting order placed via [`ExecuteMsg::PlaceLimitOrder`],
+    /// if it still exists.
+    #[returns(Option<crate::state::LimitOrder>)]
+    LimitOrder { order_id: u64 },
+    /// Every resting order, oldest first. Paginated by order id;
+    /// `start_after` excludes the given id.
+    #[returns(Vec<crate::state::LimitOrder>)]
+    ListLimitOrders {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// The pooled hatch-phase contributions and clearing state for a
+    /// [`crate::state::HatchConfig::batch_auction`] hatch, if any have
+    /// ever been pooled.
+    #[returns(crate::state::BatchAuctionState)]
+    BatchAuctionState {},
+    /// `address`'s pooled, not-yet-settled contribution to a batch
+    /// auction, if any.
+    #[returns(Option<Uint128>)]
+    BatchContribution { address: String },
+    /// `owner`'s streaming buy set up via [`ExecuteMsg::SetStreamingBuy`],
+    /// if any.
+    #[returns(Option<crate::state::StreamingBuy>)]
+    StreamingBuy { owner: String },
+    /// A crowdfunded buy pool opened via [`ExecuteMsg::OpenBuyPool`], if
+    /// it still exists.
+    #[returns(Option<crate::state::BuyPool>)]
+    BuyPool { pool_id: u64 },
+    /// `address`'s not-yet-settled contribution to `pool_id`, if any.
+    #[returns(Option<Uint128>)]
+    BuyPoolContribution { pool_id: u64, address: String },
+    /// An OTC block trade committed via [`ExecuteMsg::CommitBlockTrade`],
+    /// if it still exists.
+    #[returns(Option<crate::state::BlockTrade>)]
+    BlockTrade { trade_id: u64 },
+    /// The bonus offered on [`ExecuteMsg::BuyWithLockup`], if configured.
+    #[returns(Option<crate::state::LockupConfig>)]
+    LockupConfig {},
+    /// A lock opened via [`ExecuteMsg::BuyWithLockup`], if it still
+    /// exists (it's removed once [`ExecuteMsg::ClaimLockup`] pays it
+    /// out).
+    #[returns(Option<crate::state::Lockup>)]
+    Lockup { lockup_id: u64 },
+    /// Every not-yet-claimed lock owned by `owner`, oldest first.
+    #[returns(Vec<crate::state::Lockup>)]
+    LockupsByOwner { owner: String },
+    /// The holder-tenure discount schedule set via
+    /// [`ExecuteMsg::SetHolderDiscountConfig`], if any.
+    #[returns(Option<crate::state::HolderDiscountConfig>)]
+    HolderDiscountConfig {},
+    /// The `Open` phase's exit fee `address` would actually pay right
+    /// now, after applying whatever tier of
+    /// [`QueryMsg::HolderDiscountConfig`] its
+    /// [`crate::state::FIRST_ACQUIRED`] tenure qualifies for. Equal to
+    /// the plain exit fee if no config is set or `address` has never
+    /// bought.
+    #[returns(Decimal)]
+    EffectiveExitFee { address: String },
+    /// Every address holding `role` via [`ExecuteMsg::GrantRole`]. Does
+    /// not include the owner, which implicitly holds every role.
+    #[returns(Vec<::cosmwasm_std::Addr>)]
+    RoleMembers { role: Role },
+    /// Whether `address` currently holds `role`, either via
+    /// [`ExecuteMsg::GrantRole`] or by being the owner.
+    #[returns(bool)]
+    HasRole { role: Role, address: String },
+    /// Whether trading is currently halted by
+    /// [`ExecuteMsg::SetTradingPaused`]. See
+    /// [`crate::state::TRADING_PAUSED`] for exactly what that covers.
+    #[returns(bool)]
+    TradingPaused {},
+    /// The delay set via [`ExecuteMsg::SetTimelockConfig`], if any.
+    #[returns(Option<crate::state::TimelockConfig>)]
+    TimelockConfig {},
+    /// A single queued action by id, if it hasn't been executed or
+    /// cancelled yet.
+    #[returns(Option<crate::state::PendingTimelock>)]
+    PendingTimelock { id: u64 },
+    /// Every not-yet-executed or cancelled queued action, oldest first.
+    /// Paginated by the same internal counter as the id; `start_after`
+    /// excludes the given id.
+    #[returns(Vec<crate::state::PendingTimelock>)]
+    ListPendingTimelocks {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// The address and permission bitmask set via
+    /// [`ExecuteMsg::SetMaintenanceOperator`], if any.
+    #[returns(Option<crate::state::MaintenanceOperator>)]
+    MaintenanceOperator {},
+    /// The address and window set via [`ExecuteMsg::SetVetoAddress`], if
+    /// any.
+    #[returns(Option<crate::state::VetoConfig>)]
+    VetoConfig {},
+    /// The quorum ratio and window set via
+    /// [`ExecuteMsg::SetEmergencyCloseConfig`], if any.
+    #[returns(Option<crate::state::EmergencyCloseConfig>)]
+    EmergencyCloseConfig {},
+    /// The current signaling round, its running total, and when it
+    /// started (`None` until the round's first signal).
+    #[returns(EmergencyCloseStatusResponse)]
+    EmergencyCloseStatus {},
+    /// `address`'s not-yet-withdrawn signal amount in round `round`.
+    #[returns(::cosmwasm_std::Uint128)]
+    EmergencyCloseSignal { round: u64, address: String },
+    /// The guardians, threshold, and delay set via
+    /// [`ExecuteMsg::SetRecoveryGuardians`], if any.
+    #[returns(Option<crate::state::RecoveryConfig>)]
+    RecoveryConfig {},
+    /// The current pending recovery proposal, if any.
+    #[returns(Option<crate::state::RecoveryProposal>)]
+    PendingRecovery {},
+    /// Every recorded admin parameter change, oldest first. Paginated by
+    /// the same internal counter as the id; `start_after` excludes the
+    /// given id. See [`crate::state::ParamChange`] for exactly which
+    /// config setters are covered.
+    #[returns(Vec<crate::state::ParamChange>)]
+    History {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+/// See [`QueryMsg::EmergencyCloseStatus`].
+#[cw_serde]
+pub struct EmergencyCloseStatusResponse {
+    pub round: u64,
+    pub total_signaled: Uint128,
+    pub window_start: Option<cosmwasm_std::Timestamp>,
+}
+
+#[cw_serde]
+pub struct ProposalSellLimitResponse {
+    pub limit: Option<Uint128>,
+    pub active_proposals: Vec<u64>,
+}
+
+#[cw_serde]
+pub struct CurveInfoResponse {
+    pub reserve: Uint128,
+    pub supply: Uint128,
+    pub funding: Uint128,
+    pub spot_price: Decimal,
+    pub reserve_denom: String,
+    pub supply_denom: String,
+    /// The DAO treasury split set via [`ExecuteMsg::SetTreasuryConfig`],
+    /// if any, applied to every buy's net payment on top of the entry fee.
+    pub treasury_config: Option<crate::state::TreasuryConfig>,
+}
+
+impl CurveInfoResponse {
+    pub fn new(
+        state: CurveState,
+        spot_price: Decimal,
+        reserve_denom: String,
+        supply_denom: String,
+        treasury_config: Option<crate::state::TreasuryConfig>,
+    ) -> Self {
+        CurveInfoResponse {
+            reserve: state.reserve,
+            supply: state.supply,
+            funding: state.funding,
+            spot_price,
+            reserve_denom,
+            supply_denom,
+            treasury_config,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PhaseConfigResponse {
+    pub phase: Phase,
+    pub phase_config: PhaseConfig,
+}
+
+#[cw_serde]
+pub struct VolumeResponse {
+    pub buy_volume: Uint128,
+    pub sell_volume: Uint128,
+}
+
+#[cw_serde]
+pub struct CandleResponse {
+    pub day: u64,
+    pub candle: crate::state::DailyCandle,
+}
@@ -0,0 +1,1152 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, Decimal, Empty, Timestamp, Uint128};
+use cw_hooks::Hooks;
+use cw_storage_plus::{Item, Map, SnapshotItem, Strategy};
+
+use crate::curves::{CurveType, DecimalPlaces};
+
+/// The lifecycle of an augmented bonding curve commons.
+#[cw_serde]
+pub enum Phase {
+    /// Contributions are limited by [`HatchConfig`] and tracked
+    /// per-address so early hatchers can't dominate the raise.
+    Hatch,
+    /// Unrestricted buying and selling.
+    Open,
+    /// Trading is permanently disabled.
+    Closed,
+}
+
+/// An inclusive `[min, max]` bound, used both for individual contribution
+/// limits and for the hatch phase's overall raise target.
+#[cw_serde]
+pub struct MinMax {
+    pub min: Uint128,
+    pub max: Uint128,
+}
+
+impl MinMax {
+    pub fn contains(&self, amount: Uint128) -> bool {
+        amount >= self.min && amount <= self.max
+    }
+}
+
+#[cw_serde]
+pub struct HatchConfig {
+    /// Bounds on how much a single address may contribute during the
+    /// hatch phase.
+    pub contribution_limits: MinMax,
+    /// Bounds on the total reserve raised during the hatch phase.
+    pub initial_raise: MinMax,
+    /// Fee taken (in reserve tokens) on every hatch-phase buy, sent to the
+    /// funding pool.
+    pub entry_fee: cosmwasm_std::Decimal,
+    /// If set, only these addresses may buy during the hatch phase.
+    /// Unrestricted once the phase moves past `Hatch`.
+    pub allowlist: Option<Vec<cosmwasm_std::Addr>>,
+    /// If true, hatch-phase buys pool their net contribution in
+    /// [`BATCH_AUCTION_STATE`]/[`BATCH_CONTRIBUTIONS`] instead of minting
+    /// against the curve immediately.
+    /// [`crate::msg::ExecuteMsg::SettleBatchAuction`] clears the whole
+    /// pool at a single clearing price once the phase moves past
+    /// `Hatch`, so every hatcher's mint depends only on their share of
+    /// the pool, not on when during the window they bought in.
+    pub batch_auction: bool,
+}
+
+#[cw_serde]
+pub struct OpenConfig {
+    pub entry_fee: cosmwasm_std::Decimal,
+    pub exit_fee: cosmwasm_std::Decimal,
+}
+
+#[cw_serde]
+pub struct ClosedConfig {}
+
+#[cw_serde]
+pub struct PhaseConfig {
+    pub hatch: HatchConfig,
+    pub open: OpenConfig,
+    pub closed: ClosedConfig,
+}
+
+/// The token-factory denom minted by this contract when reserve tokens
+/// are bought in.
+#[cw_serde]
+pub struct SupplyToken {
+    /// The full token-factory denom, e.g. `factory/<contract>/<subdenom>`.
+    pub denom: String,
+    pub subdenom: String,
+    pub decimals: u8,
+}
+
+/// The token accepted as payment for the supply token. `denom` holds either
+/// a bank denom or, in [`ReserveTokenBacking::Cw20`] mode, the cw20
+/// contract's address.
+#[cw_serde]
+pub struct ReserveToken {
+    pub denom: String,
+    pub decimals: u8,
+}
+
+/// How the reserve token is actually represented on-chain.
+/// [`ReserveTokenBacking::Cw20`] lets an existing cw20 asset (e.g. a
+/// bridged stablecoin) back the curve instead of a bank denom; buys then
+/// arrive via [`crate::msg::ExecuteMsg::Receive`] rather than attached
+/// funds.
+#[cw_serde]
+pub enum ReserveTokenBacking {
+    Native,
+    Cw20 { address: Addr },
+}
+
+pub const RESERVE_MODE: Item<ReserveTokenBacking> = Item::new("reserve_mode");
+
+/// A secondary reserve denom accepted for buys alongside the canonical
+/// [`ReserveToken`], normalized into the canonical unit at buy time via
+/// `oracle`. Lets e.g. both USDC and axlUSDC fund the same curve.
+#[cw_serde]
+pub struct SecondaryReserve {
+    pub oracle: Addr,
+}
+pub const SECONDARY_RESERVES: Map<&str, SecondaryReserve> = Map::new("secondary_reserves");
+
+/// The resolved IBC denom trace for the reserve token, when it's an
+/// `ibc/<hash>` denom. Resolved once at instantiation via a query to the
+/// transfer module and stored so it doesn't need to be re-queried.
+#[cw_serde]
+pub struct DenomTrace {
+    /// The channel(s) the denom was transferred over, e.g.
+    /// `transfer/channel-0`.
+    pub path: String,
+    /// The original denom on its source chain.
+    pub base_denom: String,
+}
+pub const RESERVE_IBC_TRACE: Item<Option<DenomTrace>> = Item::new("reserve_ibc_trace");
+
+/// Remaining supply-token mint allowance for an auxiliary minter (e.g. a
+/// rewards contract) allowed to mint outside the curve. Minting through
+/// here still increases [`CurveState::supply`], so the curve's spot price
+/// reflects the true outstanding supply.
+pub const AUX_MINTER_ALLOWANCES: Map<&Addr, Uint128> = Map::new("aux_minter_allowances");
+
+/// Height-indexed snapshots of [`CurveState::supply`] and
+/// [`CurveState::reserve`], so voting modules and airdrop tooling can
+/// resolve historical totals without an external indexer.
+pub const SUPPLY_SNAPSHOT: SnapshotItem<Uint128> = SnapshotItem::new(
+    "supply_snapshot",
+    "supply_snapshot__checkpoints",
+    "supply_snapshot__changelog",
+    Strategy::EveryBlock,
+);
+pub const RESERVE_SNAPSHOT: SnapshotItem<Uint128> = SnapshotItem::new(
+    "reserve_snapshot",
+    "reserve_snapshot__checkpoints",
+    "reserve_snapshot__changelog",
+    Strategy::EveryBlock,
+);
+
+/// How the supply token is actually represented on-chain. Chains without
+/// a token-factory module can still use this contract via
+/// [`SupplyTokenBacking::Cw20`], which reuses the same curve and phase
+/// logic but mints/burns an ordinary cw20 instead of a token-factory
+/// denom.
+#[cw_serde]
+pub enum SupplyTokenBacking {
+    TokenFactory,
+    Cw20 { address: Addr },
+}
+
+pub const SUPPLY_MODE: Item<SupplyTokenBacking> = Item::new("supply_mode");
+/// Supply decimals stashed between instantiation and the cw20
+/// instantiate reply, since [`SupplyToken`] can't be saved until the
+/// cw20 contract's address is known.
+pub const PENDING_SUPPLY_DECIMALS: Item<u8> = Item::new("pending_supply_decimals");
+
+/// The running totals backing the curve, plus the decimal scaling needed
+/// to convert them into the whole-unit math [`crate::curves::Curve`]
+/// expects.
+#[cw_serde]
+pub struct CurveState {
+    /// Reserve tokens held against the outstanding supply.
+    pub reserve: Uint128,
+    /// Outstanding supply tokens.
+    pub supply: Uint128,
+    /// Reserve tokens collected as fees, spendable by the DAO.
+    pub funding: Uint128,
+    pub decimals: DecimalPlaces,
+}
+
+impl CurveState {
+    pub fn new(decimals: DecimalPlaces) -> Self {
+        CurveState {
+            reserve: Uint128::zero(),
+            supply: Uint128::zero(),
+            funding: Uint128::zero(),
+            decimals,
+        }
+    }
+}
+
+/// The DAO this commons belongs to, if any. Distinct from the `cw_ownable`
+/// owner: the DAO is the beneficiary of the funding pool, while the owner
+/// is whoever may reconfigure the curve.
+pub const DAO: Item<Addr> = Item::new("dao");
+pub const PHASE: Item<Phase> = Item::new("phase");
+pub const PHASE_CONFIG: Item<PhaseConfig> = Item::new("phase_config");
+pub const CURVE_TYPE: Item<CurveType> = Item::new("curve_type");
+pub const CURVE_STATE: Item<CurveState> = Item::new("curve_state");
+pub const SUPPLY_DENOM: Item<SupplyToken> = Item::new("supply_denom");
+pub const RESERVE: Item<ReserveToken> = Item::new("reserve_denom");
+/// Cumulative reserve tokens contributed by each address during the hatch
+/// phase, used to enforce [`HatchConfig::contribution_limits`].
+pub const HATCHERS: Map<&Addr, Uint128> = Map::new("hatchers");
+
+/// The address, if any, allowed to issue
+/// [`crate::msg::ExecuteMsg::ForceTransfer`] compliance clawbacks.
+/// Disabled by default (`None`).
+pub const CLAWBACK_ROLE: Item<Option<Addr>> = Item::new("clawback_role");
+
+/// Once set, clawback can never be re-enabled, even by the owner. Lets a
+/// community permanently commit to giving up compliance clawback powers.
+pub const CLAWBACK_PERMANENTLY_DISABLED: Item<bool> = Item::new("clawback_permanently_disabled");
+
+/// See [`crate::msg::InstantiateMsg::import_legacy_curve`].
+#[cw_serde]
+pub struct LegacyCurve {
+    pub legacy_cw20: Addr,
+    /// Decremented on every
+    /// [`crate::msg::ReceiveMsg::ClaimLegacyBalance`]; the amount of
+    /// `supply_snapshot` still available to be claimed.
+    pub claimable_supply: Uint128,
+}
+pub const LEGACY_CURVE: Item<LegacyCurve> = Item::new("legacy_curve");
+
+/// A single [`ExecuteMsg::UpdatePhase`] transition, recorded so
+/// `QueryMsg::PhaseTransitions` can answer without an external indexer
+/// replaying the `wasm-abc-phase-transition` event history.
+#[cw_serde]
+pub struct PhaseTransition {
+    pub from: Phase,
+    pub to: Phase,
+    /// The address that issued the `UpdatePhase` execute.
+    pub trigger: Addr,
+    pub height: u64,
+    pub time: cosmwasm_std::Timestamp,
+}
+
+/// Keyed by an incrementing counter rather than height, since
+/// [`PHASE_TRANSITIONS`] is a short, append-only log (phases only ever
+/// move forward through at most two transitions) and a counter avoids
+/// collisions if a chain ever allowed more than one transition per
+/// block.
+pub const PHASE_TRANSITIONS: Map<u64, PhaseTransition> = Map::new("phase_transitions");
+pub const PHASE_TRANSITION_COUNT: Item<u64> = Item::new("phase_transition_count");
+
+/// A Uniswap-v2-style cumulative price accumulator, weighted by blocks
+/// rather than wall-clock time (this contract has no way to look up a
+/// past block's timestamp, only its height): `cumulative_price` is
+/// `sum(spot_price * blocks_since_previous_update)`, advanced on every
+/// buy/sell. [`crate::msg::QueryMsg::Twap`] diffs [`CUMULATIVE_PRICE_SNAPSHOT`]
+/// across `window` blocks and divides by `window` for a
+/// manipulation-resistant average spot price.
+#[cw_serde]
+pub struct PriceAccumulator {
+    pub cumulative_price: Decimal,
+    pub last_spot_price: Decimal,
+    pub last_update_height: u64,
+}
+pub const PRICE_ACCUMULATOR: Item<PriceAccumulator> = Item::new("price_accumulator");
+/// Height-indexed snapshots of [`PriceAccumulator::cumulative_price`], so
+/// `QueryMsg::Twap` can diff against an arbitrary past height without
+/// replaying every trade since instantiation.
+pub const CUMULATIVE_PRICE_SNAPSHOT: SnapshotItem<Decimal> = SnapshotItem::new(
+    "cumulative_price_snapshot",
+    "cumulative_price_snapshot__checkpoints",
+    "cumulative_price_snapshot__changelog",
+    Strategy::EveryBlock,
+);
+
+/// The contract registered as the supply denom's token-factory
+/// before-send hook, if any. Set via
+/// [`crate::msg::ExecuteMsg::SetBeforeSendHook`] and consulted by
+/// [`crate::contract::sudo`] to block transfers during the hatch phase.
+pub const BEFORE_SEND_HOOK: Item<Option<Addr>> = Item::new("before_send_hook");
+
+/// The current token-factory admin of the supply denom, as last set by
+/// this contract. Tracked locally rather than re-derived from a chain
+/// query since the admin only ever changes via
+/// [`crate::msg::ExecuteMsg::UpdateDenomAdmin`].
+pub const DENOM_ADMIN: Item<Addr> = Item::new("denom_admin");
+
+/// Set to `true` once [`crate::msg::ExecuteMsg::Freeze`] has been called.
+/// While frozen, every parameter-changing execute is rejected, even if
+/// somehow re-issued by a former owner.
+pub const FROZEN: Item<bool> = Item::new("frozen");
+
+/// Contracts registered via [`crate::msg::ExecuteMsg::AddHook`] to receive
+/// fire-and-forget [`abc_hooks::AbcHookMsg`] callbacks on every buy, sell,
+/// and phase change.
+pub const ABC_HOOKS: Hooks = Hooks::new("abc_hooks");
+
+/// The DAO proposal module, if any, trusted to notify this contract of
+/// proposal lifecycle events via [`crate::msg::ExecuteMsg::ProposalHook`].
+/// Set via [`crate::msg::ExecuteMsg::SetCloseProposalModule`], and
+/// registered as a proposal hook receiver on that module in turn.
+pub const CLOSE_PROPOSAL_MODULE: Item<Option<Addr>> = Item::new("close_proposal_module");
+
+/// Routes a percentage of every buy's net payment directly to a DAO
+/// treasury via [`crate::contract::process_buy`], on top of the
+/// [`PhaseConfig`] entry fee that flows into [`CurveState::funding`].
+/// Unlike the funding pool, this amount never backs the curve.
+#[cw_serde]
+pub struct TreasuryConfig {
+    pub treasury: Addr,
+    pub percent: cosmwasm_std::Decimal,
+}
+
+/// Set (or cleared) via
+/// [`crate::msg::ExecuteMsg::SetTreasuryConfig`].
+pub const TREASURY_CONFIG: Item<Option<TreasuryConfig>> = Item::new("treasury_config");
+
+/// The sell amount, if any, above which [`crate::msg::ExecuteMsg::Sell`]
+/// and its `Receive` equivalent are rejected while a proposal from
+/// [`CLOSE_PROPOSAL_MODULE`] is open for voting. Guards against a large
+/// front-running sell while a close-the-commons proposal might pass.
+pub const PROPOSAL_SELL_LIMIT: Item<Option<Uint128>> = Item::new("proposal_sell_limit");
+
+/// Proposal IDs from [`CLOSE_PROPOSAL_MODULE`] currently open for voting,
+/// as reported by [`crate::msg::ExecuteMsg::ProposalHook`].
+pub const ACTIVE_PROPOSALS: Map<u64, Empty> = Map::new("active_proposals");
+
+/// The staking contract, if any, that a buy's minted supply is routed to
+/// instead of the buyer, when the buy opts into (or
+/// `default_stake` defaults it into) auto-staking. The staking contract
+/// must implement [`crate::msg::StakeExecuteMsg`].
+#[cw_serde]
+pub struct StakingConfig {
+    pub staking_contract: Addr,
+    /// Used when a buy's `stake` field is left unset.
+    pub default_stake: bool,
+}
+
+/// Set (or cleared) via [`crate::msg::ExecuteMsg::SetStakingConfig`].
+pub const STAKING_CONFIG: Item<Option<StakingConfig>> = Item::new("staking_config");
+
+/// An external price oracle pushed the curve's spot price after a trade,
+/// so this contract's supply token can be listed as collateral
+/// elsewhere. Set via [`crate::msg::ExecuteMsg::SetPriceOracle`].
+#[cw_serde]
+pub struct PriceOracleConfig {
+    pub oracle: Addr,
+    /// Minimum number of blocks between pushes.
+    pub push_interval: u64,
+    pub last_push_height: u64,
+}
+
+/// Set (or cleared) via [`crate::msg::ExecuteMsg::SetPriceOracle`].
+pub const PRICE_ORACLE_CONFIG: Item<Option<PriceOracleConfig>> = Item::new("price_oracle_config");
+
+/// Buy/sell volume accrued on a single epoch day (`env.block.time.seconds()
+/// / 86400`), so `QueryMsg::Volume` can sum a fixed number of recent
+/// buckets for a rough 24h/7d activity figure without an external indexer.
+#[cw_serde]
+#[derive(Default)]
+pub struct VolumeBucket {
+    pub buy_volume: Uint128,
+    pub sell_volume: Uint128,
+}
+
+/// Keyed by epoch day rather than block height, since volume is naturally
+/// a wall-clock notion (a "day") and the bucket boundaries need to be
+/// stable regardless of the chain's block time.
+pub const VOLUME_BY_DAY: Map<u64, VolumeBucket> = Map::new("volume_by_day");
+
+/// A day's open/high/low/close spot price and traded volume, updated
+/// incrementally on every trade so `QueryMsg::Candles` can answer without
+/// a front-end replaying trade history itself.
+#[cw_serde]
+pub struct DailyCandle {
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Uint128,
+}
+
+/// Keyed by the same epoch day as [`VOLUME_BY_DAY`].
+pub const CANDLES_BY_DAY: Map<u64, DailyCandle> = Map::new("candles_by_day");
+
+/// An address's lifetime trading activity, so a DAO can pull up
+/// contributor recognition or retroactive airdrop lists via
+/// `QueryMsg::AccountStats` without an external indexer replaying
+/// `abc-trade` events.
+#[cw_serde]
+#[derive(Default)]
+pub struct AccountStats {
+    pub reserve_contributed: Uint128,
+    pub tokens_bought: Uint128,
+    pub tokens_sold: Uint128,
+}
+pub const ACCOUNT_STATS: Map<&Addr, AccountStats> = Map::new("account_stats");
+
+/// A single post-trade `(height, reserve, supply, spot_price)` snapshot,
+/// recorded so `QueryMsg::HistoricalCurveInfo` can page through curve
+/// history for on-chain charting without replaying `abc-trade` events.
+#[cw_serde]
+pub struct CurveCheckpoint {
+    pub height: u64,
+    pub reserve: Uint128,
+    pub supply: Uint128,
+    pub spot_price: Decimal,
+}
+
+/// Keyed by height, one checkpoint per trade (a later trade in the same
+/// block overwrites the earlier one, which is fine since only the
+/// block's final state matters for charting).
+pub const CURVE_CHECKPOINTS: Map<u64, CurveCheckpoint> = Map::new("curve_checkpoints");
+
+/// A single buy or sell, recorded so `QueryMsg::ListTrades` can answer for
+/// small-chain explorers without their own indexer. `kind` mirrors the
+/// `abc-trade` event's own attribute (`"buy"` or `"sell"`), and
+/// `amount`/`minted_or_burned` carry the same values as that event.
+#[cw_serde]
+pub struct TradeRecord {
+    pub trader: Addr,
+    pub kind: String,
+    pub amount: Uint128,
+    pub minted_or_burned: Uint128,
+    pub fee: Uint128,
+    pub height: u64,
+    pub time: cosmwasm_std::Timestamp,
+}
+
+/// Keyed by an incrementing counter rather than height, for the same
+/// reason as [`PHASE_TRANSITIONS`]: more than one trade can land in a
+/// single block, and a counter can't collide where a height key could.
+pub const TRADES: Map<u64, TradeRecord> = Map::new("trades");
+pub const TRADE_COUNT: Item<u64> = Item::new("trade_count");
+
+/// Owner-configured policy for delegating idle native reserve to a
+/// validator to earn staking rewards. Only meaningful when the reserve
+/// token is the chain's staking-bonded native denom.
+#[cw_serde]
+pub struct ReserveStakingConfig {
+    pub validator: Addr,
+    /// Sells and funding-pool spends that would take the reserve's
+    /// liquid (unstaked) balance below this amount trigger an
+    /// undelegation to top it back up ahead of future liquidity needs.
+    pub min_liquid_reserve: Uint128,
+}
+pub const RESERVE_STAKING_CONFIG: Item<Option<ReserveStakingConfig>> =
+    Item::new("reserve_staking_config");
+
+/// How much of the reserve is currently delegated to
+/// [`ReserveStakingConfig::validator`] versus unbonding back toward the
+/// liquid balance. Tracked here since the contract has no way to query
+/// its own unbonding delegations directly.
+#[cw_serde]
+#[derive(Default)]
+pub struct ReserveStakingState {
+    pub bonded: Uint128,
+    pub unbonding: Uint128,
+}
+pub const RESERVE_STAKING_STATE: Item<ReserveStakingState> = Item::new("reserve_staking_state");
+
+/// The contract's reserve-denom balance stashed ahead of a
+/// `WithdrawDelegatorReward` submessage, so the reply can diff the
+/// balance before and after to learn the reward amount actually paid
+/// out (the staking module doesn't return it directly).
+pub const PENDING_REWARD_WITHDRAWAL_BALANCE: Item<Uint128> =
+    Item::new("pending_reward_withdrawal_balance");
+
+/// Owner-configured whitelisted yield vault the reserve may be deployed
+/// into. The vault must implement
+/// [`crate::msg::VaultAdapterExecuteMsg`]/[`crate::msg::VaultAdapterQueryMsg`].
+#[cw_serde]
+pub struct ReserveVaultConfig {
+    pub vault: Addr,
+    /// The maximum share of [`CurveState::reserve`] that may be deployed
+    /// into `vault` at once.
+    pub max_percent: Decimal,
+}
+pub const RESERVE_VAULT_CONFIG: Item<Option<ReserveVaultConfig>> = Item::new("reserve_vault_config");
+
+/// How much of the reserve is currently deployed in
+/// [`ReserveVaultConfig::vault`]. Still counted in [`CurveState::reserve`]
+/// (deploying it doesn't change how much backs the curve, only where it's
+/// held), so solvency checks stay correct while capital is out.
+pub const RESERVE_VAULT_DEPOSITED: Item<Uint128> = Item::new("reserve_vault_deposited");
+
+/// Lifetime reserve-yield (staking rewards, vault yield, or any future
+/// strategy) credited to [`CurveState::funding`] via
+/// [`crate::msg::ExecuteMsg::Harvest`]. Never counts principal moved by
+/// [`crate::msg::ExecuteMsg::WithdrawReserveFromVault`], only the amount
+/// earned on top of it.
+pub const HARVESTED_TOTAL: Item<Uint128> = Item::new("harvested_total");
+
+/// Owner-configured policy for periodically swapping a capped slice of
+/// incoming reserve into a second, native `target_denom` via a DEX
+/// router, to reduce the commons's exposure to holding only the reserve
+/// token. Unlike staking/vault deployment, swapped reserve leaves
+/// [`CurveState::reserve`] entirely: it no longer backs the curve.
+#[cw_serde]
+pub struct DiversificationConfig {
+    pub router: Addr,
+    pub target_denom: String,
+    /// Share of each buy's net payment set aside for diversification, on
+    /// top of the entry fee and any [`TreasuryConfig`] split.
+    pub percent: Decimal,
+    /// Minimum number of blocks between swaps, so a burst of small buys
+    /// doesn't trigger a router swap each.
+    pub swap_interval: u64,
+    /// The most reserve a single swap may move, so a large accrued
+    /// balance is diversified gradually instead of in one trade against
+    /// the router.
+    pub max_swap_amount: Uint128,
+    pub last_swap_height: u64,
+}
+pub const DIVERSIFICATION_CONFIG: Item<Option<DiversificationConfig>> =
+    Item::new("diversification_config");
+
+/// Reserve set aside for diversification via [`DiversificationConfig`]
+/// but not yet swapped, either because `swap_interval` hasn't elapsed
+/// since the last swap or the accrued amount already exceeds
+/// `max_swap_amount` for a single swap.
+pub const PENDING_DIVERSIFICATION: Item<Uint128> = Item::new("pending_diversification");
+
+/// How much of [`DiversificationConfig::target_denom`] the diversified
+/// sub-account holds, from the lifetime total of prior swaps. The router
+/// doesn't report a swap's output directly, so this is credited from a
+/// balance diff in [`crate::contract::reply`].
+pub const DIVERSIFIED_BALANCE: Item<Uint128> = Item::new("diversified_balance");
+
+/// Stashed ahead of a diversification swap submessage, so the reply can
+/// diff [`PendingDiversificationSwap::denom`]'s balance before and after
+/// to learn how much the router actually paid out.
+#[cw_serde]
+pub struct PendingDiversificationSwap {
+    pub denom: String,
+    pub balance_before: Uint128,
+}
+pub const PENDING_DIVERSIFICATION_SWAP: Item<PendingDiversificationSwap> =
+    Item::new("pending_diversification_swap");
+
+/// Owner-configured router that [`crate::msg::ExecuteMsg::ZapBuy`] swaps
+/// whatever denom the buyer attached into the reserve denom through,
+/// via the same [`crate::msg::DexRouterExecuteMsg::Swap`] payload
+/// [`DiversificationConfig`] uses. `None` (the default) means zap-in
+/// buys aren't accepted.
+#[cw_serde]
+pub struct ZapConfig {
+    pub router: Addr,
+}
+pub const ZAP_CONFIG: Item<Option<ZapConfig>> = Item::new("zap_config");
+
+/// Stashed ahead of a zap-in swap submessage, so the reply can diff the
+/// reserve balance before and after to learn how much the router paid
+/// out, check it against `min_reserve_out`, and run the buy for `buyer`
+/// with it. Mirrors [`PendingDiversificationSwap`], plus what the reply
+/// needs to finish the buy.
+#[cw_serde]
+pub struct PendingZapBuy {
+    pub buyer: Addr,
+    pub reserve_balance_before: Uint128,
+    pub min_reserve_out: Uint128,
+}
+pub const PENDING_ZAP_BUY: Item<PendingZapBuy> = Item::new("pending_zap_buy");
+
+/// Stashed ahead of a zap-out swap submessage, so the reply can either
+/// forward the swap's output to `seller` (if it met `min_output`) or, if
+/// the swap submessage itself failed, refund the original `payout` of
+/// reserve back to `seller` instead of losing it. Mirrors
+/// [`PendingZapBuy`] for the sell direction.
+#[cw_serde]
+pub struct PendingZapSell {
+    pub seller: Addr,
+    pub payout: Uint128,
+    pub output_denom: String,
+    pub output_balance_before: Uint128,
+    pub min_output: Uint128,
+}
+pub const PENDING_ZAP_SELL: Item<PendingZapSell> = Item::new("pending_zap_sell");
+
+/// Owner-configured policy for seeding a DEX pool at the Hatch->Open
+/// transition, so secondary-market liquidity exists from day one instead
+/// of only through this contract's own curve. Only supported when both
+/// the supply and reserve tokens are native denoms, since `pool_creator`
+/// needs both attached to a single bank-funded message.
+#[cw_serde]
+pub struct LiquiditySeedConfig {
+    pub pool_creator: Addr,
+    /// Freshly minted supply to pair with `reserve_amount`, on top of
+    /// whatever was minted to hatch contributors.
+    pub supply_amount: Uint128,
+    /// Reserve to pair with `supply_amount`, taken out of
+    /// [`CurveState::reserve`] the same way a [`TreasuryConfig`] split is:
+    /// it no longer backs the curve once it's seeded the pool.
+    pub reserve_amount: Uint128,
+}
+pub const LIQUIDITY_SEED_CONFIG: Item<Option<LiquiditySeedConfig>> =
+    Item::new("liquidity_seed_config");
+
+/// The minimum ratio of [`CurveState::reserve`] to the curve's own
+/// required reserve for the current supply that
+/// [`crate::msg::ExecuteMsg::SpendReserve`] must leave behind, enforced in
+/// code rather than relying on social consensus not to drain the reserve.
+pub const RESERVE_HEALTH_THRESHOLD: Item<Option<Decimal>> = Item::new("reserve_health_threshold");
+
+/// Set via [`crate::msg::ExecuteMsg::SetReserveExchangeRateSource`] for
+/// reserves that are a yield-bearing derivative (e.g. stATOM) rather than
+/// their plain underlying asset, so reserve-backed calculations like
+/// [`crate::msg::QueryMsg::ReserveHealthFactor`] operate on underlying
+/// value instead of the face amount of derivative tokens held.
+#[cw_serde]
+pub struct ReserveExchangeRateConfig {
+    pub oracle: Addr,
+    /// Maximum age, in seconds, of the oracle's reported rate before it's
+    /// considered too stale to use.
+    pub max_staleness: u64,
+}
+pub const RESERVE_EXCHANGE_RATE_CONFIG: Item<Option<ReserveExchangeRateConfig>> =
+    Item::new("reserve_exchange_rate_config");
+
+/// Set via [`crate::msg::ExecuteMsg::SetBackstopConfig`]. `percent` must
+/// be greater than zero and no more than one.
+#[cw_serde]
+pub struct BackstopConfig {
+    pub percent: Decimal,
+}
+pub const BACKSTOP_CONFIG: Item<Option<BackstopConfig>> = Item::new("backstop_config");
+
+/// Reserve-denominated funds accumulated via [`BackstopConfig`] out of
+/// entry/exit fees that would otherwise go to [`CurveState::funding`].
+/// Not counted in [`CurveState::reserve`], and spendable only through
+/// [`crate::msg::ExecuteMsg::TopUpReserveFromBackstop`], so it survives
+/// as a solvency reserve independent of ordinary treasury spending.
+pub const BACKSTOP_BALANCE: Item<Uint128> = Item::new("backstop_balance");
+
+/// Set via [`crate::msg::ExecuteMsg::SetReferralConfig`]. `percent` must
+/// be greater than zero and no more than one.
+#[cw_serde]
+pub struct ReferralConfig {
+    pub percent: Decimal,
+}
+pub const REFERRAL_CONFIG: Item<Option<ReferralConfig>> = Item::new("referral_config");
+
+/// Lifetime reserve-denominated amount credited to each referrer named in
+/// a [`crate::msg::ExecuteMsg::Buy`], paid out immediately alongside the
+/// buy and tracked here purely for reporting via
+/// [`crate::msg::QueryMsg::ReferralEarned`].
+pub const REFERRAL_EARNED: Map<&Addr, Uint128> = Map::new("referral_earned");
+
+/// How long a [`crate::msg::ExecuteMsg::Buy`] `idempotency_key` is
+/// remembered for. An interchain account controller retrying a buy after
+/// seeing a timeout is expected to do so well within this window; past
+/// it, the key is treated as unseen and can be reused (or replayed a
+/// second time for a fresh buy) without erroring.
+pub const IDEMPOTENCY_KEY_TTL_SECONDS: u64 = 86_400;
+
+/// The recorded outcome of a [`crate::msg::ExecuteMsg::Buy`] made with an
+/// `idempotency_key`, keyed by that key. A retried buy with the same key
+/// inside [`IDEMPOTENCY_KEY_TTL_SECONDS`] of `recorded_at` short-circuits
+/// into this result (refunding whatever was attached) instead of buying
+/// a second time.
+#[cw_serde]
+pub struct IdempotencyRecord {
+    pub minted: Uint128,
+    pub recorded_at: cosmwasm_std::Timestamp,
+}
+pub const IDEMPOTENCY_KEYS: Map<&str, IdempotencyRecord> = Map::new("idempotency_keys");
+
+/// Set via [`crate::msg::ExecuteMsg::SetPolytoneProxyConfig`]. `proxy` is
+/// the address of the Polytone proxy contract instantiated on this chain
+/// on behalf of the remote (owner) DAO for `connection_id`; once
+/// configured, calls from `proxy` are treated as owner calls by
+/// `assert_owner_or_polytone_proxy` (see `src/contract.rs`) the same way
+/// calls from the literal cw-ownable owner are. `connection_id` isn't
+/// itself checked (a plain `execute()` call carries no IBC provenance to
+/// check it against) — it's recorded here purely so the config is
+/// self-documenting about which counterparty chain `proxy` belongs to.
+#[cw_serde]
+pub struct PolytoneProxyConfig {
+    pub connection_id: String,
+    pub proxy: Addr,
+}
+pub const POLYTONE_PROXY_CONFIG: Item<Option<PolytoneProxyConfig>> =
+    Item::new("polytone_proxy_config");
+
+/// Channels that have completed the state-sync handshake in
+/// `src/ibc.rs`'s `ibc_channel_connect`, and so are pushed a
+/// [`crate::msg::StateSyncPacket`] after every trade. A set, same idiom
+/// as [`ACTIVE_PROPOSALS`]: presence is all that matters, not the value.
+pub const MIRROR_CHANNELS: Map<&str, Empty> = Map::new("mirror_channels");
+
+/// Reserve and supply-token balances a user has pre-deposited into this
+/// contract via [`crate::msg::ExecuteMsg::DepositForOperator`], so a
+/// granted operator (see [`OPERATOR_GRANTS`]) can trade on the
+/// depositor's behalf without holding the tokens directly, since
+/// token-factory denoms have no cw20-style allowance a third party could
+/// otherwise be authorized against.
+#[cw_serde]
+#[derive(Default)]
+pub struct OperatorDeposit {
+    pub reserve: Uint128,
+    pub supply: Uint128,
+}
+pub const OPERATOR_DEPOSITS: Map<&Addr, OperatorDeposit> = Map::new("operator_deposits");
+
+/// An allowance granted via [`crate::msg::ExecuteMsg::Grant`], keyed by
+/// `(owner, operator)`, authorizing `operator` to spend `owner`'s
+/// [`OPERATOR_DEPOSITS`] balance via [`crate::msg::ExecuteMsg::SellFor`]/
+/// [`crate::msg::ExecuteMsg::BuyFor`]. `sell_limit`/`buy_limit` (in
+/// supply/reserve tokens respectively) are decremented as they're spent;
+/// `None` means unlimited. Also bounded by `expires_at`, if set.
+#[cw_serde]
+pub struct OperatorGrant {
+    pub sell_limit: Option<Uint128>,
+    pub buy_limit: Option<Uint128>,
+    pub expires_at: Option<cosmwasm_std::Timestamp>,
+}
+pub const OPERATOR_GRANTS: Map<(&Addr, &Addr), OperatorGrant> = Map::new("operator_grants");
+
+/// An off-chain key trusted to sign [`crate::msg::BuyPermit`] vouchers
+/// via [`crate::msg::ExecuteMsg::SetPermitAuthorizerConfig`], letting a
+/// hatch-phase buy satisfy [`HatchConfig::allowlist`] with a signed
+/// voucher instead of the buyer's address being stored on-chain ahead of
+/// time.
+#[cw_serde]
+pub struct PermitAuthorizerConfig {
+    /// A compressed secp256k1 public key (33 bytes).
+    pub pubkey: cosmwasm_std::Binary,
+}
+pub const PERMIT_AUTHORIZER_CONFIG: Item<Option<PermitAuthorizerConfig>> =
+    Item::new("permit_authorizer_config");
+
+/// A recurring dollar-cost-average purchase set up via
+/// [`crate::msg::ExecuteMsg::SetRecurringPurchase`], keyed by the
+/// depositor's address. `deposited` is reserve funds pre-paid into this
+/// order, spent down as [`crate::msg::ExecuteMsg::CrankRecurringPurchases`]
+/// buys `amount_per_interval` of it every `interval_seconds`; `next_due`
+/// advances by `interval_seconds` each time it fires. The crank is a
+/// no-op once `deposited` drops below `amount_per_interval`.
+#[cw_serde]
+pub struct RecurringOrder {
+    pub amount_per_interval: Uint128,
+    pub interval_seconds: u64,
+    pub deposited: Uint128,
+    pub next_due: cosmwasm_std::Timestamp,
+}
+pub const RECURRING_ORDERS: Map<&Addr, RecurringOrder> = Map::new("recurring_orders");
+
+/// Which side of the curve a [`LimitOrder`] triggers on.
+#[cw_serde]
+pub enum LimitOrderSide {
+    /// Buy `amount` of the reserve once the spot price drops to or below
+    /// `threshold_price`.
+    Buy,
+    /// Sell `amount` of the supply token once the spot price rises to or
+    /// above `threshold_price`.
+    Sell,
+}
+
+/// A resting order placed via
+/// [`crate::msg::ExecuteMsg::PlaceLimitOrder`], escrowing `amount` of the
+/// reserve (`Buy`) or supply (`Sell`) denom until it's matched by
+/// [`crate::msg::ExecuteMsg::CrankLimitOrders`], cancelled, or past
+/// `expires_at`.
+#[cw_serde]
+pub struct LimitOrder {
+    pub owner: Addr,
+    pub side: LimitOrderSide,
+    pub threshold_price: Decimal,
+    pub amount: Uint128,
+    pub expires_at: cosmwasm_std::Timestamp,
+}
+/// Keyed by an incrementing counter, the same way [`TRADES`] is, since
+/// orders are placed and removed in no particular order and a counter
+/// gives every order a stable id to cancel or query by.
+pub const LIMIT_ORDERS: Map<u64, LimitOrder> = Map::new("limit_orders");
+pub const LIMIT_ORDER_COUNT: Item<u64> = Item::new("limit_order_count");
+
+/// Batch-auction accounting for a hatch phase configured with
+/// [`HatchConfig::batch_auction`]: hatch-phase contributions accrue
+/// here instead of minting against the curve immediately, so
+/// [`crate::msg::ExecuteMsg::SettleBatchAuction`] can clear the whole
+/// pool at a single price once the phase moves past `Hatch`.
+#[cw_serde]
+#[derive(Default)]
+pub struct BatchAuctionState {
+    /// Net reserve pooled so far (after the hatch entry fee and any
+    /// treasury/diversification/referral splits, the same net amount an
+    /// ordinary buy would pass to the curve).
+    pub total_pool: Uint128,
+    /// Set the first time `SettleBatchAuction` runs: the total supply
+    /// minted for `total_pool`, split pro-rata across
+    /// [`BATCH_CONTRIBUTIONS`] as settlement is cranked to completion.
+    pub total_minted: Option<Uint128>,
+}
+pub const BATCH_AUCTION_STATE: Item<BatchAuctionState> = Item::new("batch_auction_state");
+/// Each contributor's net share of `BATCH_AUCTION_STATE.total_pool`,
+/// removed as `SettleBatchAuction` mints their pro-rata share.
+pub const BATCH_CONTRIBUTIONS: Map<&Addr, Uint128> = Map::new("batch_contributions");
+
+/// A streaming buy set up via [`crate::msg::ExecuteMsg::SetStreamingBuy`],
+/// keyed by the depositor's address. `total_amount` of reserve funds
+/// vest linearly over `duration_seconds` starting at `start_time`;
+/// [`crate::msg::ExecuteMsg::CrankStreamingBuys`] buys whatever portion
+/// has vested since `converted` was last updated, pro-rated by elapsed
+/// time. Removed once `converted` reaches `total_amount`.
+#[cw_serde]
+pub struct StreamingBuy {
+    pub total_amount: Uint128,
+    pub start_time: cosmwasm_std::Timestamp,
+    pub duration_seconds: u64,
+    pub converted: Uint128,
+}
+pub const STREAMING_BUYS: Map<&Addr, StreamingBuy> = Map::new("streaming_buys");
+
+/// A crowdfunded buy pool opened via
+/// [`crate::msg::ExecuteMsg::OpenBuyPool`], keyed by an incrementing
+/// counter the same way [`LIMIT_ORDERS`] is. Contributors join via
+/// [`crate::msg::ExecuteMsg::JoinBuyPool`] until `total_pooled` reaches
+/// `target`, at which point [`crate::msg::ExecuteMsg::SettleBuyPool`]
+/// executes a single curve buy for the whole pool and mints each
+/// contributor's pro-rata share, the same way [`BatchAuctionState`]
+/// settles a batch-auction hatch.
+#[cw_serde]
+pub struct BuyPool {
+    pub initiator: Addr,
+    pub target: Uint128,
+    pub total_pooled: Uint128,
+    /// Set the first time `SettleBuyPool` runs: the total supply minted
+    /// for `total_pooled`, split pro-rata across
+    /// [`BUY_POOL_CONTRIBUTIONS`] as settlement is cranked to completion.
+    pub total_minted: Option<Uint128>,
+}
+pub const BUY_POOLS: Map<u64, BuyPool> = Map::new("buy_pools");
+pub const BUY_POOL_COUNT: Item<u64> = Item::new("buy_pool_count");
+/// Each contributor's not-yet-settled contribution to a [`BuyPool`],
+/// keyed by `(pool_id, contributor)`.
+pub const BUY_POOL_CONTRIBUTIONS: Map<(u64, &Addr), Uint128> = Map::new("buy_pool_contributions");
+
+/// An OTC block trade committed via
+/// [`crate::msg::ExecuteMsg::CommitBlockTrade`], keyed by an incrementing
+/// counter the same way [`LIMIT_ORDERS`] is. `amount` of the reserve
+/// (`Buy`) or supply (`Sell`) denom is escrowed at commit time, then
+/// [`crate::msg::ExecuteMsg::CrankBlockTrades`] executes it once
+/// `delay_blocks` have passed, at the [`crate::msg::QueryMsg::Twap`] over
+/// the last `delay_blocks` blocks up to the crank's height (the blocks
+/// right after the commit only if it's cranked as soon as it comes due)
+/// rather than the curve's marginal price, so a single large
+/// trade can't be priced off of its own impact.
+#[cw_serde]
+pub struct BlockTrade {
+    pub owner: Addr,
+    pub side: LimitOrderSide,
+    pub amount: Uint128,
+    pub committed_at_height: u64,
+    pub delay_blocks: u64,
+}
+pub const BLOCK_TRADES: Map<u64, BlockTrade> = Map::new("block_trades");
+pub const BLOCK_TRADE_COUNT: Item<u64> = Item::new("block_trade_count");
+
+/// Configures the optional bonus on
+/// [`crate::msg::ExecuteMsg::BuyWithLockup`]. Set via
+/// [`crate::msg::ExecuteMsg::SetLockupConfig`].
+#[cw_serde]
+pub struct LockupConfig {
+    pub min_duration_seconds: u64,
+    /// Applied to the buy's curve-minted amount, on top of it: a buy
+    /// minting `minted` locks up `minted + minted * bonus_percent`.
+    pub bonus_percent: Decimal,
+    /// Supply tokens still available to mint as bonuses, decremented by
+    /// each `BuyWithLockup`. Bonuses stop, with `BuyWithLockup` erroring
+    /// rather than silently paying no bonus, once this reaches zero --
+    /// so lockup bonuses can never dilute the curve beyond a fixed
+    /// budget the owner set aside up front.
+    pub remaining_bonus_budget: Uint128,
+}
+pub const LOCKUP_CONFIG: Item<Option<LockupConfig>> = Item::new("lockup_config");
+
+/// A buy locked up via [`crate::msg::ExecuteMsg::BuyWithLockup`], held by
+/// the contract until `unlocks_at` and then released in full by
+/// [`crate::msg::ExecuteMsg::ClaimLockup`]. `amount` already includes
+/// the bonus minted on top of the curve-quoted buy. Keyed by an
+/// incrementing counter the same way [`LIMIT_ORDERS`] is, since one
+/// owner may hold several locks opened at different times with
+/// different `unlocks_at`.
+#[cw_serde]
+pub struct Lockup {
+    pub owner: Addr,
+    pub amount: Uint128,
+    pub unlocks_at: cosmwasm_std::Timestamp,
+}
+pub const LOCKUPS: Map<u64, Lockup> = Map::new("lockups");
+pub const LOCKUP_COUNT: Item<u64> = Item::new("lockup_count");
+
+/// The block time at which each address first bought into the curve,
+/// recorded the first time it appears as the trader on an `abc-trade`
+/// buy event and never updated after. Backs
+/// [`crate::msg::QueryMsg::EffectiveExitFee`]'s holder-tenure discount;
+/// an address with no entry here has never bought and gets no discount.
+pub const FIRST_ACQUIRED: Map<&Addr, cosmwasm_std::Timestamp> = Map::new("first_acquired");
+
+/// One rung of a [`HolderDiscountConfig`] schedule: an address that has
+/// held since at least `min_tenure_seconds` before the current trade
+/// gets `exit_fee_discount` shaved off the phase's exit fee. Where more
+/// than one tier qualifies, the largest `exit_fee_discount` among them
+/// applies.
+#[cw_serde]
+pub struct HolderDiscountTier {
+    pub min_tenure_seconds: u64,
+    pub exit_fee_discount: Decimal,
+}
+
+/// Set via [`crate::msg::ExecuteMsg::SetHolderDiscountConfig`], rewarding
+/// patient capital with a smaller exit fee the longer
+/// [`FIRST_ACQUIRED`] shows an address has held. Every
+/// `exit_fee_discount` must be greater than zero and no more than one.
+#[cw_serde]
+pub struct HolderDiscountConfig {
+    pub tiers: Vec<HolderDiscountTier>,
+}
+pub const HOLDER_DISCOUNT_CONFIG: Item<Option<HolderDiscountConfig>> =
+    Item::new("holder_discount_config");
+
+/// A narrowly-scoped administrative duty grantable via
+/// [`crate::msg::ExecuteMsg::GrantRole`]/[`crate::msg::ExecuteMsg::RevokeRole`]
+/// so it doesn't have to sit behind the owner's key, the way
+/// [`CLAWBACK_ROLE`] already separates out compliance clawback. The
+/// owner implicitly holds every role. See each variant's execute for
+/// exactly what it authorizes.
+#[cw_serde]
+pub enum Role {
+    /// May call [`crate::msg::ExecuteMsg::SetTradingPaused`].
+    Pauser,
+    /// May call [`crate::msg::ExecuteMsg::UpdateFees`].
+    FeeAdmin,
+    /// May call [`crate::msg::ExecuteMsg::UpdateHatchAllowlist`].
+    AllowlistManager,
+    /// May call [`crate::msg::ExecuteMsg::UpdatePhaseConfig`], same as
+    /// the owner -- the closest thing this contract has to a dedicated
+    /// curve-parameters update, since [`CurveType`] itself is fixed at
+    /// instantiation.
+    CurveAdmin,
+}
+
+impl Role {
+    /// The [`ROLES`] map's first key component. Stable across releases
+    /// since it's persisted on-chain; new variants must only ever be
+    /// appended.
+    pub fn discriminant(&self) -> u8 {
+        match self {
+            Role::Pauser => 0,
+            Role::FeeAdmin => 1,
+            Role::AllowlistManager => 2,
+            Role::CurveAdmin => 3,
+        }
+    }
+}
+
+/// Addresses holding each [`Role`], granted/revoked by the owner via
+/// [`crate::msg::ExecuteMsg::GrantRole`]/[`crate::msg::ExecuteMsg::RevokeRole`].
+/// A set keyed by `(role.discriminant(), address)`, same idiom as
+/// [`MIRROR_CHANNELS`]: presence is all that matters, not the value.
+pub const ROLES: Map<(u8, &Addr), Empty> = Map::new("roles");
+
+/// Whether trading is currently halted by [`Role::Pauser`]. Checked by
+/// every path that mints or burns supply against the curve -- enforced
+/// in the shared `process_buy`/`process_sell` internals, and by a direct
+/// check in the execute handlers for block trades, batch auction
+/// settlement, and buy pool settlement, which settle outside them. The
+/// covered entry points: the two primary [`crate::msg::ExecuteMsg::Buy`]/
+/// [`crate::msg::ExecuteMsg::Sell`] entry points, cw20-reserve buys,
+/// `BuyAndDistribute`, `BuyWithLockup`, gmp/ibc-hooks buys, zaps, limit
+/// order matching, streaming buys, recurring purchases, block trades,
+/// batch auction settlement, and buy pool settlement. Placing/cancelling
+/// a limit order, streaming buy, buy pool, or recurring order is not
+/// blocked -- those only escrow funds and don't move the curve until
+/// they're matched or settled, which is where the check actually runs.
+pub const TRADING_PAUSED: Item<bool> = Item::new("trading_paused");
+
+/// A parameter change contentious enough that holders should get advance
+/// notice before it lands, gated by [`TimelockConfig`]. Mirrors the
+/// direct executes they'd otherwise run immediately.
+#[cw_serde]
+pub enum TimelockedAction {
+    /// See [`crate::msg::ExecuteMsg::UpdateFees`].
+    UpdateFees {
+        hatch_entry_fee: Option<Decimal>,
+        open_entry_fee: Option<Decimal>,
+        open_exit_fee: Option<Decimal>,
+    },
+    /// See [`crate::msg::ExecuteMsg::UpdatePhaseConfig`].
+    UpdatePhaseConfig { phase_config: PhaseConfig },
+    /// Transitions to [`Phase::Closed`]. See
+    /// [`crate::msg::ExecuteMsg::UpdatePhase`].
+    Close,
+}
+
+/// Set via [`crate::msg::ExecuteMsg::SetTimelockConfig`]. While set,
+/// [`TimelockedAction`]s can no longer be issued directly and must go
+/// through [`crate::msg::ExecuteMsg::QueueTimelockedAction`], then
+/// [`crate::msg::ExecuteMsg::ExecuteTimelockedAction`] once
+/// `delay_seconds` has elapsed. `None` (the default) leaves those
+/// actions immediate, same as before this feature existed.
+#[cw_serde]
+pub struct TimelockConfig {
+    pub delay_seconds: u64,
+}
+pub const TIMELOCK_CONFIG: Item<Option<TimelockConfig>> = Item::new("timelock_config");
+
+/// A [`TimelockedAction`] queued via
+/// [`crate::msg::ExecuteMsg::QueueTimelockedAction`], executable once the
+/// block time reaches `execute_after`. May be cancelled by whoever could
+/// have issued `action` directly, any time before it's executed -- that
+/// whole window is the "cancel window" holders get to react in.
+#[cw_serde]
+pub struct PendingTimelock {
+    pub action: TimelockedAction,
+    pub queued_by: Addr,
+    pub queued_at: cosmwasm_std::Timestamp,
+    pub execute_after: cosmwasm_std::Timestamp,
+}
+/// Keyed by an incrementing counter, the same way [`LIMIT_ORDERS`] is.
+pub const PENDING_TIMELOCKS: Map<u64, PendingTimelock> = Map::new("pending_timelocks");
+pub const TIMELOCK_COUNT: Item<u64> = Item::new("timelock_count");
+
+/// Bit for [`MaintenanceOperator::permissions`] granting the operator
+/// everyday (non-`Closed`) [`crate::msg::ExecuteMsg::UpdatePhase`] calls.
+/// Closing the commons always requires actual ownership, regardless of
+/// this bit -- see [`crate::contract::execute_update_phase`].
+pub const OPERATOR_PERM_UPDATE_PHASE: u8 = 1 << 0;
+/// Bit for [`MaintenanceOperator::permissions`] granting
+/// [`crate::msg::ExecuteMsg::UpdateDenomMetadata`].
+pub const OPERATOR_PERM_UPDATE_DENOM_METADATA: u8 = 1 << 1;
+
+/// A narrow delegate for routine maintenance, set via
+/// [`crate::msg::ExecuteMsg::SetMaintenanceOperator`], without granting
+/// full ownership. Unlike [`ROLES`], which grants a whole named duty,
+/// `permissions` is a caller-chosen bitmask of the individual
+/// `OPERATOR_PERM_*` flags, so an operator can be scoped to exactly the
+/// executes it needs. [`crate::msg::ExecuteMsg::Harvest`] has no
+/// corresponding bit since it's already permissionless in this fork.
+#[cw_serde]
+pub struct MaintenanceOperator {
+    pub operator: Addr,
+    pub permissions: u8,
+}
+pub const MAINTENANCE_OPERATOR: Item<Option<MaintenanceOperator>> =
+    Item::new("maintenance_operator");
+
+/// Set via [`crate::msg::ExecuteMsg::SetVetoAddress`]. Lets a security
+/// council or similar cancel a queued [`TimelockedAction::Close`] --
+/// the only queued phase transition this fork has, since phases only
+/// ever move forward and a `Closed` -> `Open` re-open has no equivalent
+/// here -- without being able to touch anything else about the
+/// contract. `window_seconds` bounds how long after queuing the veto
+/// stays valid; past it, only the ordinary
+/// [`crate::msg::ExecuteMsg::CancelTimelockedAction`] path (the
+/// queuer's own role or the owner) still applies.
+#[cw_serde]
+pub struct VetoConfig {
+    pub veto: Addr,
+    pub window_seconds: u64,
+}
+pub const VETO_CONFIG: Item<Option<VetoConfig>> = Item::new("veto_config");
+
+/// The block time of the last successful
+/// [`crate::contract::apply_update_fees`], gating
+/// `MIN_FEE_UPDATE_INTERVAL_SECONDS`. Unset until the first fee update.
+pub const LAST_FEE_UPDATE: Item<cosmwasm_std::Timestamp> = Item::new("last_fee_update");
+
+/// Set via [`crate::msg::ExecuteMsg::SetEmergencyCloseConfig`] (owner-only,
+/// `None` disables the feature). While set,
+/// [`crate::msg::ExecuteMsg::SignalEmergencyClose`] lets any holder
+/// deposit supply tokens to signal for closure; once the total signaled
+/// in the current round reaches `quorum_ratio` of
+/// [`CurveState::supply`] within `window_seconds` of the round's first
+/// signal, the commons transitions to [`Phase::Closed`] automatically --
+/// a backstop that needs no owner or DAO action at all.
+#[cw_serde]
+pub struct EmergencyCloseConfig {
+    pub quorum_ratio: Decimal,
+    pub window_seconds: u64,
+}
+pub const EMERGENCY_CLOSE_CONFIG: Item<Option<EmergencyCloseConfig>> =
+    Item::new("emergency_close_config");
+
+/// The current signaling round. Advanced whenever a round's
+/// `window_seconds` elapses without reaching quorum, so a stale signal
+/// from an expired round doesn't count toward a fresh one -- though
+/// it's still refundable any time via
+/// [`crate::msg::ExecuteMsg::WithdrawEmergencyCloseSignal`].
+pub const EMERGENCY_CLOSE_ROUND: Item<u64> = Item::new("emergency_close_round");
+/// The block time the current round's first signal arrived.
+pub const EMERGENCY_CLOSE_WINDOW_START: Item<cosmwasm_std::Timestamp> =
+    Item::new("emergency_close_window_start");
+/// Total supply tokens signaled in the current round.
+pub const EMERGENCY_CLOSE_TOTAL: Item<Uint128> = Item::new("emergency_close_total");
+/// Each signaler's deposit for a given round, keyed by
+/// `(round, signaler)`.
+pub const EMERGENCY_CLOSE_SIGNALS: Map<(u64, &Addr), Uint128> =
+    Map::new("emergency_close_signals");
+
+/// Set via [`crate::msg::ExecuteMsg::SetRecoveryGuardians`] (owner-only,
+/// `None` disables the feature). `threshold` of `guardians` approving
+/// the same [`RecoveryProposal::new_owner`] can force an ownership
+/// rotation after `delay_seconds`, bypassing the current owner's own
+/// signature entirely -- the whole point of a recovery path for a lost
+/// or compromised owner key.
+#[cw_serde]
+pub struct RecoveryConfig {
+    pub guardians: Vec<Addr>,
+    pub threshold: u32,
+    pub delay_seconds: u64,
+}
+pub const RECOVERY_CONFIG: Item<Option<RecoveryConfig>> = Item::new("recovery_config");
+
+/// A pending ownership recovery started by the first guardian to call
+/// [`crate::msg::ExecuteMsg::ProposeRecovery`], tallying subsequent
+/// [`crate::msg::ExecuteMsg::ApproveRecovery`] calls for the same
+/// `new_owner`. Executable via
+/// [`crate::msg::ExecuteMsg::ExecuteRecovery`] once `approvals.len()`
+/// reaches [`RecoveryConfig::threshold`] and `execute_after` has passed
+/// -- the delay window starts at proposal creation, the same as
+/// [`PendingTimelock::execute_after`].
+#[cw_serde]
+pub struct RecoveryProposal {
+    pub new_owner: Addr,
+    pub approvals: Vec<Addr>,
+    pub execute_after: cosmwasm_std::Timestamp,
+}
+pub const PENDING_RECOVERY: Item<Option<RecoveryProposal>> = Item::new("pending_recovery");
+
+/// A single admin parameter change, recorded so `QueryMsg::History` can
+/// answer for auditors and members without an external indexer
+/// replaying execute messages. `old_value`/`new_value` are the JSON
+/// encoding of whatever config type `what` names (e.g. the config's own
+/// `cw_serde` struct, or `None` for a value that didn't exist yet or was
+/// cleared), since the changes being logged span many unrelated types.
+///
+/// Covers the contract's core governance surface -- fees, phase config,
+/// trading pause, and the timelock/maintenance-operator/veto/emergency-
+/// close/recovery configs -- rather than literally every `Set*`/
+/// `Update*` execute in the contract; the long tail of narrower knobs
+/// (diversification, liquidity seeding, reserve staking, and so on)
+/// isn't wired into this log.
+#[cw_serde]
+pub struct ParamChange {
+    pub what: String,
+    pub old_value: Option<Binary>,
+    pub new_value: Option<Binary>,
+    pub changed_by: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// Keyed by an incrementing counter for the same reason as [`TRADES`]:
+/// more than one change can land in a single block.
+pub const PARAM_CHANGES: Map<u64, ParamChange> = Map::new("param_changes");
+pub const PARAM_CHANGE_COUNT: Item<u64> = Item::new("param_change_count");
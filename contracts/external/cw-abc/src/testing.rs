@@ -0,0 +1,147 @@
+//! A [`cw_multi_test`] custom module standing in for the token-factory
+//! module, so this crate (and downstream integrators) can drive a full
+//! hatch -> open lifecycle in-process instead of only asserting that the
+//! right [`TokenMsg`] got returned.
+//!
+//! `CreateDenom` records the creating sender as the denom's admin
+//! (rejecting a second `CreateDenom` for the same denom, like a real
+//! chain would). `MintTokens` and `BurnTokens` check that sender against
+//! that record and are turned into [`BankSudo::Mint`]/`BankMsg::Burn`
+//! against the same bank keeper, so balance/supply queries stay
+//! consistent with what's actually been minted and burned. Every other
+//! token-factory operation (change-admin, set-metadata,
+//! before-send-hook, force-transfer) is unimplemented, so a test that
+//! drives the contract into issuing one of them fails here. This contract
+//! never issues a
+//! `TokenFactoryQuery` of its own (curve supply is tracked in its own
+//! state, not queried back from the chain), so `QueryT` stays `Empty`.
+use cosmwasm_std::{Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Empty, Querier, Storage};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cw_multi_test::{App, AppBuilder, BankKeeper, BankSudo, CosmosRouter, Module, SudoMsg, WasmKeeper};
+use cw_storage_plus::Map;
+use serde::de::DeserializeOwned;
+
+use crate::bindings::{TokenFactoryMsg, TokenMsg};
+
+/// Denom -> the address that created it via `CreateDenom`, the only
+/// address allowed to `MintTokens`/`BurnTokens` against it.
+const ADMINS: Map<&str, Addr> = Map::new("token_factory_admins");
+
+/// A ready-to-use [`App`] whose custom module understands this
+/// contract's [`TokenFactoryMsg`]s.
+pub type TokenFactoryApp = App<BankKeeper, MockApi, MockStorage, TokenFactoryModule, WasmKeeper<TokenFactoryMsg, Empty>>;
+
+/// Builds a [`TokenFactoryApp`], otherwise using the same defaults as
+/// [`cw_multi_test::App::default`].
+pub fn mock_app() -> TokenFactoryApp {
+    AppBuilder::new_custom()
+        .with_custom(TokenFactoryModule {})
+        .build(|_router, _api, _storage| {})
+}
+
+#[derive(Default)]
+pub struct TokenFactoryModule {}
+
+impl Module for TokenFactoryModule {
+    type ExecT = TokenFactoryMsg;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: Self::ExecT,
+    ) -> anyhow::Result<cw_multi_test::AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg {
+            TokenMsg::CreateDenom { metadata, .. } => {
+                let denom = metadata.and_then(|m| m.base).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "TokenFactoryModule: CreateDenom needs metadata.base to record the denom's admin"
+                    )
+                })?;
+                if ADMINS.has(storage, &denom) {
+                    anyhow::bail!("TokenFactoryModule: denom {denom} already exists");
+                }
+                ADMINS.save(storage, &denom, &sender)?;
+                Ok(cw_multi_test::AppResponse::default())
+            }
+            TokenMsg::MintTokens {
+                denom,
+                amount,
+                mint_to_address,
+            } => {
+                assert_sender_is_admin(storage, &denom, &sender)?;
+                router.sudo(
+                    api,
+                    storage,
+                    block,
+                    SudoMsg::Bank(BankSudo::Mint {
+                        to_address: mint_to_address,
+                        amount: vec![cosmwasm_std::coin(amount.u128(), denom)],
+                    }),
+                )
+            }
+            TokenMsg::BurnTokens {
+                denom,
+                amount,
+                burn_from_address,
+            } => {
+                assert_sender_is_admin(storage, &denom, &sender)?;
+                router.execute(
+                    api,
+                    storage,
+                    block,
+                    Addr::unchecked(burn_from_address),
+                    cosmwasm_std::BankMsg::Burn {
+                        amount: vec![cosmwasm_std::coin(amount.u128(), denom)],
+                    }
+                    .into(),
+                )
+            }
+            other => anyhow::bail!("TokenFactoryModule: unsupported message {other:?}"),
+        }
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Self::SudoT,
+    ) -> anyhow::Result<cw_multi_test::AppResponse> {
+        anyhow::bail!("TokenFactoryModule: no sudo messages are supported")
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        _request: Self::QueryT,
+    ) -> anyhow::Result<Binary> {
+        anyhow::bail!("TokenFactoryModule: no queries are supported")
+    }
+}
+
+/// Mirrors a real token-factory module rejecting `MintTokens`/`BurnTokens`
+/// from anyone but the denom's creator, so a bug that mints/burns as the
+/// wrong sender fails a test here instead of silently succeeding.
+fn assert_sender_is_admin(storage: &dyn Storage, denom: &str, sender: &Addr) -> anyhow::Result<()> {
+    match ADMINS.may_load(storage, denom)? {
+        Some(admin) if admin == *sender => Ok(()),
+        Some(admin) => {
+            anyhow::bail!("TokenFactoryModule: {sender} is not the admin of {denom} ({admin} is)")
+        }
+        None => anyhow::bail!("TokenFactoryModule: {denom} was never created via CreateDenom"),
+    }
+}
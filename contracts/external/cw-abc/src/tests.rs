@@ -0,0 +1,1477 @@
+//! End-to-end lifecycle coverage (hatch -> open -> closed) driven through
+//! [`crate::testing::mock_app`], so the curve math, fee/phase gating, and
+//! actual bank balances are all exercised together instead of only this
+//! contract's own return values.
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+use cw_multi_test::{BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
+
+use crate::contract::{execute, instantiate, query};
+use crate::curves::{Curve, CurveType, DecimalPlaces};
+use crate::msg::{
+    CurveInfoResponse, ExecuteMsg, HolderDiscountConfigMsg, InstantiateMsg, PhaseConfigResponse,
+    QueryMsg, ReserveTokenMode, SupplyTokenMode,
+};
+use crate::state::{
+    ClosedConfig, HatchConfig, HolderDiscountTier, MinMax, OpenConfig, Phase, PhaseConfig, Role,
+    TimelockedAction,
+};
+use crate::testing::mock_app;
+use crate::ContractError;
+
+const OWNER: &str = "owner";
+const HATCHER1: &str = "hatcher1";
+const HATCHER2: &str = "hatcher2";
+const RESERVE_DENOM: &str = "ureserve";
+
+fn abc_contract() -> Box<dyn Contract<crate::bindings::TokenFactoryMsg>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn instantiate_msg() -> InstantiateMsg {
+    curve_instantiate_msg(CurveType::Linear { slope: Decimal::percent(1), scale: 6 })
+}
+
+fn curve_instantiate_msg(curve_type: CurveType) -> InstantiateMsg {
+    InstantiateMsg {
+        owner: Some(OWNER.to_string()),
+        dao: None,
+        supply_token_mode: SupplyTokenMode::TokenFactory { subdenom: "abc".to_string() },
+        supply_decimals: 6,
+        reserve_token_mode: ReserveTokenMode::Native { denom: RESERVE_DENOM.to_string() },
+        reserve_decimals: 6,
+        curve_type,
+        phase_config: PhaseConfig {
+            hatch: HatchConfig {
+                contribution_limits: MinMax { min: Uint128::zero(), max: Uint128::new(1_000_000) },
+                initial_raise: MinMax { min: Uint128::zero(), max: Uint128::new(1_500_000) },
+                entry_fee: Decimal::percent(5),
+                allowlist: None,
+                batch_auction: false,
+            },
+            open: OpenConfig { entry_fee: Decimal::percent(2), exit_fee: Decimal::percent(2) },
+            closed: ClosedConfig {},
+        },
+        import_legacy_curve: None,
+        initial_curve_state: None,
+        allocations: vec![],
+        denom_metadata: None,
+    }
+}
+
+fn mint(app: &mut crate::testing::TokenFactoryApp, to: &str, amount: u128) {
+    app.sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: to.to_string(),
+        amount: vec![coin(amount, RESERVE_DENOM)],
+    }))
+    .unwrap();
+}
+
+#[test]
+fn full_lifecycle() {
+    let mut app = mock_app();
+    mint(&mut app, HATCHER1, 2_000_000);
+    mint(&mut app, HATCHER2, 2_000_000);
+
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &instantiate_msg(),
+            &[],
+            "abc",
+            None,
+        )
+        .unwrap();
+
+    // Hatcher 1 buys its full contribution limit.
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        },
+        &[coin(1_000_000, RESERVE_DENOM)],
+    )
+    .unwrap();
+
+    let curve_info: CurveInfoResponse =
+        app.wrap().query_wasm_smart(&abc, &QueryMsg::CurveInfo {}).unwrap();
+    assert_eq!(curve_info.reserve, Uint128::new(950_000)); // net of the 5% hatch entry fee
+
+    let supply_denom = curve_info.supply_denom.clone();
+    let minted_to_hatcher1 = app.wrap().query_balance(HATCHER1, &supply_denom).unwrap();
+    assert!(!minted_to_hatcher1.amount.is_zero());
+
+    // Hatcher 2's buy would cross the raise cap and is rejected.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(HATCHER2),
+            abc.clone(),
+            &ExecuteMsg::Buy {
+                stake: None,
+                referrer: None,
+                ibc_forward: None,
+                idempotency_key: None,
+                permit: None,
+            },
+            &[coin(1_000_000, RESERVE_DENOM)],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::HatchCapExceeded { remaining: Uint128::new(550_000) });
+
+    // A smaller buy that stays under the cap succeeds instead.
+    app.execute_contract(
+        Addr::unchecked(HATCHER2),
+        abc.clone(),
+        &ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        },
+        &[coin(500_000, RESERVE_DENOM)],
+    )
+    .unwrap();
+
+    // Selling isn't allowed until the hatch phase ends.
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Sell {},
+        &[coin(1, &supply_denom)],
+    )
+    .unwrap_err();
+
+    // Owner opens trading.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhase { new_phase: Phase::Open },
+        &[],
+    )
+    .unwrap();
+    let phase: PhaseConfigResponse =
+        app.wrap().query_wasm_smart(&abc, &QueryMsg::PhaseConfig {}).unwrap();
+    assert_eq!(phase.phase, Phase::Open);
+
+    // Open-phase buy and sell both move real bank balances.
+    mint(&mut app, HATCHER1, 100_000);
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        },
+        &[coin(100_000, RESERVE_DENOM)],
+    )
+    .unwrap();
+    let supply_before_sell = app.wrap().query_balance(HATCHER1, &supply_denom).unwrap().amount;
+
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Sell {},
+        &[coin(supply_before_sell.u128(), &supply_denom)],
+    )
+    .unwrap();
+    let reserve_after_sell = app.wrap().query_balance(HATCHER1, RESERVE_DENOM).unwrap().amount;
+    assert!(!reserve_after_sell.is_zero());
+    assert!(app.wrap().query_balance(HATCHER1, &supply_denom).unwrap().amount.is_zero());
+
+    // Owner closes the commons; trading is rejected from then on.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhase { new_phase: Phase::Closed },
+        &[],
+    )
+    .unwrap();
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(HATCHER2),
+            abc.clone(),
+            &ExecuteMsg::Buy {
+                stake: None,
+                referrer: None,
+                ibc_forward: None,
+                idempotency_key: None,
+                permit: None,
+            },
+            &[coin(1_000, RESERVE_DENOM)],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::CommonsClosed {});
+}
+
+/// A small xorshift generator so this test explores many random operation
+/// sequences deterministically, without a `rand`/`proptest` dependency
+/// this workspace doesn't otherwise have.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A uniform-ish value in `0..=max`, or `0` if `max` is `0`.
+    fn next_range(&mut self, max: u128) -> u128 {
+        if max == 0 {
+            0
+        } else {
+            (self.next_u64() as u128) % (max + 1)
+        }
+    }
+}
+
+/// Fires random buys and sells (with one hatch -> open transition partway
+/// through, to exercise both phases) against freshly instantiated commons
+/// across a few curve types, asserting after every operation that the
+/// curve stays solvent: the reserve its own math says `supply` requires
+/// never exceeds what [`QueryMsg::CurveInfo`] and the contract's real
+/// bank balance report. Buys/sells that fail on legitimate constraints
+/// (hatch cap, phase gating, insufficient balance) are expected and
+/// their errors ignored; the solvency checks run after them all the same.
+#[test]
+fn fuzz_buy_sell_sequences_stay_solvent() {
+    let curve_types = [
+        CurveType::Constant { value: Decimal::percent(150), scale: 6 },
+        CurveType::Linear { slope: Decimal::percent(2), scale: 6 },
+        CurveType::SquareRoot { slope: Decimal::percent(300), scale: 6 },
+    ];
+    let decimals = DecimalPlaces::new(6, 6);
+
+    for (round, curve_type) in curve_types.into_iter().enumerate() {
+        let mut rng = Lcg(0x9E3779B97F4A7C15 ^ (round as u64 + 1));
+        let curve = curve_type.to_curve_fn()(decimals);
+
+        let mut app = mock_app();
+        mint(&mut app, HATCHER1, 50_000_000);
+        mint(&mut app, HATCHER2, 50_000_000);
+
+        let code_id = app.store_code(abc_contract());
+        let abc = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &curve_instantiate_msg(curve_type),
+                &[],
+                "abc",
+                None,
+            )
+            .unwrap();
+        let supply_denom = app
+            .wrap()
+            .query_wasm_smart::<CurveInfoResponse>(&abc, &QueryMsg::CurveInfo {})
+            .unwrap()
+            .supply_denom;
+
+        for step in 0..200 {
+            if step == 100 {
+                // Ignore the result: the solvency checks below hold in
+                // either phase.
+                let _ = app.execute_contract(
+                    Addr::unchecked(OWNER),
+                    abc.clone(),
+                    &ExecuteMsg::UpdatePhase { new_phase: Phase::Open },
+                    &[],
+                );
+            }
+
+            let buyer = if rng.next_range(1) == 0 { HATCHER1 } else { HATCHER2 };
+            if rng.next_range(1) == 0 {
+                let amount = rng.next_range(2_000_000);
+                let _ = app.execute_contract(
+                    Addr::unchecked(buyer),
+                    abc.clone(),
+                    &ExecuteMsg::Buy {
+                        stake: None,
+                        referrer: None,
+                        ibc_forward: None,
+                        idempotency_key: None,
+                        permit: None,
+                    },
+                    &[coin(amount, RESERVE_DENOM)],
+                );
+            } else {
+                let balance = app.wrap().query_balance(buyer, &supply_denom).unwrap().amount;
+                let sell_amount = rng.next_range(balance.u128());
+                if sell_amount > 0 {
+                    let _ = app.execute_contract(
+                        Addr::unchecked(buyer),
+                        abc.clone(),
+                        &ExecuteMsg::Sell {},
+                        &[coin(sell_amount, &supply_denom)],
+                    );
+                }
+            }
+
+            let curve_info: CurveInfoResponse =
+                app.wrap().query_wasm_smart(&abc, &QueryMsg::CurveInfo {}).unwrap();
+            let required_reserve = curve.reserve(curve_info.supply);
+            assert!(
+                required_reserve <= curve_info.reserve,
+                "curve insolvent at step {step}: supply {} requires {} reserve but only {} is held",
+                curve_info.supply,
+                required_reserve,
+                curve_info.reserve,
+            );
+            let held = app.wrap().query_balance(abc.as_str(), RESERVE_DENOM).unwrap().amount;
+            assert!(
+                curve_info.reserve <= held,
+                "step {step}: contract claims {} of reserve but only holds {held}",
+                curve_info.reserve,
+            );
+        }
+    }
+}
+
+/// A holder-tenure discount tier only applies once the holder has bought
+/// (so [`crate::state::FIRST_ACQUIRED`] has an entry) and only once
+/// configured; an address that never bought always sees the plain fee.
+#[test]
+fn holder_discount_reduces_effective_exit_fee_only_for_holders() {
+    let mut app = mock_app();
+    mint(&mut app, HATCHER1, 2_000_000);
+
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        },
+        &[coin(1_000_000, RESERVE_DENOM)],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhase { new_phase: Phase::Open },
+        &[],
+    )
+    .unwrap();
+
+    let base_fee: Decimal = app
+        .wrap()
+        .query_wasm_smart(&abc, &QueryMsg::EffectiveExitFee { address: HATCHER1.to_string() })
+        .unwrap();
+    assert_eq!(base_fee, Decimal::percent(2)); // the instantiated open.exit_fee
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetHolderDiscountConfig {
+            config: Some(HolderDiscountConfigMsg {
+                tiers: vec![HolderDiscountTier {
+                    min_tenure_seconds: 0,
+                    exit_fee_discount: Decimal::percent(50),
+                }],
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let discounted_fee: Decimal = app
+        .wrap()
+        .query_wasm_smart(&abc, &QueryMsg::EffectiveExitFee { address: HATCHER1.to_string() })
+        .unwrap();
+    assert_eq!(discounted_fee, Decimal::percent(1)); // 2% halved
+
+    let never_bought_fee: Decimal = app
+        .wrap()
+        .query_wasm_smart(&abc, &QueryMsg::EffectiveExitFee { address: HATCHER2.to_string() })
+        .unwrap();
+    assert_eq!(never_bought_fee, Decimal::percent(2)); // no FIRST_ACQUIRED entry, no discount
+}
+
+/// [`ExecuteMsg::GrantRole`]/[`ExecuteMsg::RevokeRole`] are owner-only,
+/// and a granted role only authorizes the one execute it names.
+#[test]
+fn role_delegation_grants_and_revokes_scoped_permission() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+    let pauser = "pauser";
+
+    // Not yet granted: acting as Pauser is rejected.
+    app.execute_contract(
+        Addr::unchecked(pauser),
+        abc.clone(),
+        &ExecuteMsg::SetTradingPaused { paused: true },
+        &[],
+    )
+    .unwrap_err();
+
+    // Only the owner can grant.
+    app.execute_contract(
+        Addr::unchecked(pauser),
+        abc.clone(),
+        &ExecuteMsg::GrantRole { role: Role::Pauser, address: pauser.to_string() },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::GrantRole { role: Role::Pauser, address: pauser.to_string() },
+        &[],
+    )
+    .unwrap();
+    assert!(app
+        .wrap()
+        .query_wasm_smart::<bool>(
+            &abc,
+            &QueryMsg::HasRole { role: Role::Pauser, address: pauser.to_string() }
+        )
+        .unwrap());
+
+    // Granted: the pauser can pause, but still can't act as FeeAdmin.
+    app.execute_contract(
+        Addr::unchecked(pauser),
+        abc.clone(),
+        &ExecuteMsg::SetTradingPaused { paused: true },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(pauser),
+        abc.clone(),
+        &ExecuteMsg::UpdateFees {
+            hatch_entry_fee: None,
+            open_entry_fee: None,
+            open_exit_fee: Some(Decimal::percent(1)),
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    // Revoked: the pauser loses the ability again.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::RevokeRole { role: Role::Pauser, address: pauser.to_string() },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(pauser),
+        abc.clone(),
+        &ExecuteMsg::SetTradingPaused { paused: false },
+        &[],
+    )
+    .unwrap_err();
+}
+
+/// [`ExecuteMsg::SetTradingPaused`] must actually stop supply from
+/// moving against the curve through both primary entry points,
+/// [`ExecuteMsg::Buy`] and [`ExecuteMsg::Sell`], and unpausing must
+/// restore buys.
+#[test]
+fn trading_pause_blocks_buy_and_sell() {
+    let mut app = mock_app();
+    mint(&mut app, HATCHER1, 2_000_000);
+
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    // Buy some supply tokens to sell back later, while trading still works.
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        },
+        &[coin(500_000, RESERVE_DENOM)],
+    )
+    .unwrap();
+    let supply_denom = app
+        .wrap()
+        .query_wasm_smart::<CurveInfoResponse>(&abc, &QueryMsg::CurveInfo {})
+        .unwrap()
+        .supply_denom;
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetTradingPaused { paused: true },
+        &[],
+    )
+    .unwrap();
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(HATCHER1),
+            abc.clone(),
+            &ExecuteMsg::Buy {
+                stake: None,
+                referrer: None,
+                ibc_forward: None,
+                idempotency_key: None,
+                permit: None,
+            },
+            &[coin(1_000_000, RESERVE_DENOM)],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::TradingPaused {});
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(HATCHER1),
+            abc.clone(),
+            &ExecuteMsg::Sell {},
+            &[coin(1, &supply_denom)],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::TradingPaused {});
+
+    // Unpausing restores the buy path. Hatcher 1 already contributed
+    // 500_000, so the rest of its 1_000_000 contribution limit is 500_000.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetTradingPaused { paused: false },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        },
+        &[coin(500_000, RESERVE_DENOM)],
+    )
+    .unwrap();
+}
+
+/// Regression test: [`ExecuteMsg::BuyAndDistribute`] and
+/// [`ExecuteMsg::BuyWithLockup`] both mint against the curve through
+/// [`process_buy`] directly rather than `buy_impl`, and must be stopped
+/// by [`ExecuteMsg::SetTradingPaused`] just like the primary `Buy` entry
+/// point.
+#[test]
+fn trading_pause_blocks_buy_and_distribute_and_buy_with_lockup() {
+    let mut app = mock_app();
+    mint(&mut app, HATCHER1, 2_000_000);
+
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetLockupConfig {
+            config: Some(crate::msg::LockupConfigMsg {
+                min_duration_seconds: 100,
+                bonus_percent: Decimal::percent(10),
+                remaining_bonus_budget: Uint128::new(1_000_000),
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetTradingPaused { paused: true },
+        &[],
+    )
+    .unwrap();
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(HATCHER1),
+            abc.clone(),
+            &ExecuteMsg::BuyAndDistribute {
+                recipients: vec![(HATCHER1.to_string(), Uint128::one())],
+            },
+            &[coin(1_000_000, RESERVE_DENOM)],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::TradingPaused {});
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(HATCHER1),
+            abc.clone(),
+            &ExecuteMsg::BuyWithLockup { duration_seconds: 100 },
+            &[coin(1_000_000, RESERVE_DENOM)],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::TradingPaused {});
+}
+
+/// [`QueryMsg::History`] records the covered admin parameter changes in
+/// order, oldest first, with `changed_by` and pagination via
+/// `start_after`.
+#[test]
+fn history_records_covered_param_changes_in_order() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdateFees {
+            hatch_entry_fee: None,
+            open_entry_fee: None,
+            open_exit_fee: Some(Decimal::percent(3)),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetTradingPaused { paused: true },
+        &[],
+    )
+    .unwrap();
+
+    let history: Vec<crate::state::ParamChange> = app
+        .wrap()
+        .query_wasm_smart(&abc, &QueryMsg::History { start_after: None, limit: None })
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].what, "fees");
+    assert_eq!(history[1].what, "trading_paused");
+    assert!(history.iter().all(|c| c.changed_by == Addr::unchecked(OWNER)));
+
+    let paginated: Vec<crate::state::ParamChange> = app
+        .wrap()
+        .query_wasm_smart(&abc, &QueryMsg::History { start_after: Some(0), limit: None })
+        .unwrap();
+    assert_eq!(paginated.len(), 1);
+    assert_eq!(paginated[0].what, "trading_paused");
+}
+
+/// The full guardian recovery lifecycle: a lone guardian can only
+/// propose, [`RecoveryConfig::threshold`] approvals are required before
+/// [`ExecuteMsg::ExecuteRecovery`] can run, and it still waits out
+/// `delay_seconds` even once threshold is met.
+#[test]
+fn guardian_recovery_requires_threshold_and_delay() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+    let guardian1 = "guardian1";
+    let guardian2 = "guardian2";
+    let new_owner = "new_owner";
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetRecoveryGuardians {
+            config: Some(crate::msg::RecoveryConfigMsg {
+                guardians: vec![guardian1.to_string(), guardian2.to_string()],
+                threshold: 2,
+                delay_seconds: 1_000,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(guardian1),
+        abc.clone(),
+        &ExecuteMsg::ProposeRecovery { new_owner: new_owner.to_string() },
+        &[],
+    )
+    .unwrap();
+
+    // One approval isn't enough to meet threshold.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(guardian1),
+            abc.clone(),
+            &ExecuteMsg::ExecuteRecovery {},
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::RecoveryThresholdNotMet { .. }));
+
+    app.execute_contract(
+        Addr::unchecked(guardian2),
+        abc.clone(),
+        &ExecuteMsg::ApproveRecovery {},
+        &[],
+    )
+    .unwrap();
+
+    // Threshold met, but the delay hasn't elapsed yet.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(guardian1),
+            abc.clone(),
+            &ExecuteMsg::ExecuteRecovery {},
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::RecoveryNotReady { .. }));
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(1_000);
+    });
+    app.execute_contract(
+        Addr::unchecked(guardian1),
+        abc.clone(),
+        &ExecuteMsg::ExecuteRecovery {},
+        &[],
+    )
+    .unwrap();
+
+    let ownership: cw_ownable::Ownership<Addr> =
+        app.wrap().query_wasm_smart(&abc, &QueryMsg::Ownership {}).unwrap();
+    assert_eq!(ownership.owner, Some(Addr::unchecked(new_owner)));
+}
+
+/// The maintainer-flagged freeze bypass: guardians can't use a recovery
+/// set up before [`ExecuteMsg::Freeze`] to reverse it afterward, and
+/// freezing clears any pending proposal so it can't just sit there
+/// waiting for a future unfreeze that doesn't exist.
+#[test]
+fn freeze_blocks_recovery_and_clears_pending_proposal() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+    let guardian = "guardian1";
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetRecoveryGuardians {
+            config: Some(crate::msg::RecoveryConfigMsg {
+                guardians: vec![guardian.to_string()],
+                threshold: 1,
+                delay_seconds: 1_000,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(guardian),
+        abc.clone(),
+        &ExecuteMsg::ProposeRecovery { new_owner: "new_owner".to_string() },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(Addr::unchecked(OWNER), abc.clone(), &ExecuteMsg::Freeze {}, &[]).unwrap();
+
+    // The pending proposal is gone -- freezing clears it.
+    let pending: Option<crate::state::RecoveryProposal> =
+        app.wrap().query_wasm_smart(&abc, &QueryMsg::PendingRecovery {}).unwrap();
+    assert!(pending.is_none());
+    let config: Option<crate::state::RecoveryConfig> =
+        app.wrap().query_wasm_smart(&abc, &QueryMsg::RecoveryConfig {}).unwrap();
+    assert!(config.is_none());
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(1_000);
+    });
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(guardian),
+            abc.clone(),
+            &ExecuteMsg::ProposeRecovery { new_owner: "new_owner".to_string() },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Frozen {});
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(guardian),
+            abc.clone(),
+            &ExecuteMsg::ExecuteRecovery {},
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Frozen {});
+}
+
+/// A signal below quorum is withdrawable and doesn't close the commons;
+/// reaching [`crate::state::EmergencyCloseConfig::quorum_ratio`] of the
+/// outstanding supply closes it automatically, with no owner or DAO
+/// action needed.
+#[test]
+fn emergency_close_signal_withdraws_below_quorum_and_closes_at_quorum() {
+    let mut app = mock_app();
+    mint(&mut app, HATCHER1, 2_000_000);
+
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    // Buy supply tokens to signal with, then move to Open so a signaled
+    // amount of them is meaningful against total supply.
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::Buy {
+            stake: None,
+            referrer: None,
+            ibc_forward: None,
+            idempotency_key: None,
+            permit: None,
+        },
+        &[coin(1_000_000, RESERVE_DENOM)],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhase { new_phase: Phase::Open },
+        &[],
+    )
+    .unwrap();
+
+    let curve_info: CurveInfoResponse =
+        app.wrap().query_wasm_smart(&abc, &QueryMsg::CurveInfo {}).unwrap();
+    let supply_denom = curve_info.supply_denom.clone();
+    let holder_balance = app.wrap().query_balance(HATCHER1, &supply_denom).unwrap().amount;
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetEmergencyCloseConfig {
+            config: Some(crate::msg::EmergencyCloseConfigMsg {
+                quorum_ratio: Decimal::percent(50),
+                window_seconds: 10_000,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Signal with less than quorum, then withdraw it back out.
+    let below_quorum = holder_balance.multiply_ratio(1u128, 10u128);
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::SignalEmergencyClose {},
+        &[coin(below_quorum.u128(), &supply_denom)],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap()
+            .query_wasm_smart::<PhaseConfigResponse>(&abc, &QueryMsg::PhaseConfig {})
+            .unwrap()
+            .phase,
+        Phase::Open
+    );
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::WithdrawEmergencyCloseSignal { round: 0 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(app.wrap().query_balance(HATCHER1, &supply_denom).unwrap().amount, holder_balance);
+
+    // Signal with enough to reach quorum: the commons closes itself.
+    let at_quorum = curve_info.supply.multiply_ratio(6u128, 10u128);
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::SignalEmergencyClose {},
+        &[coin(at_quorum.u128(), &supply_denom)],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap()
+            .query_wasm_smart::<PhaseConfigResponse>(&abc, &QueryMsg::PhaseConfig {})
+            .unwrap()
+            .phase,
+        Phase::Closed
+    );
+}
+
+/// [`ExecuteMsg::UpdateFees`] rejects a fee above the cap outright, and
+/// rate-limits legitimate updates to at most one per
+/// `MIN_FEE_UPDATE_INTERVAL_SECONDS` regardless of who calls it.
+#[test]
+fn update_fees_enforces_cap_and_cooldown() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            abc.clone(),
+            &ExecuteMsg::UpdateFees {
+                hatch_entry_fee: None,
+                open_entry_fee: None,
+                open_exit_fee: Some(Decimal::percent(21)),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::FeeExceedsMax { .. }));
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdateFees {
+            hatch_entry_fee: None,
+            open_entry_fee: None,
+            open_exit_fee: Some(Decimal::percent(5)),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // A second update too soon after the first is rejected...
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            abc.clone(),
+            &ExecuteMsg::UpdateFees {
+                hatch_entry_fee: None,
+                open_entry_fee: None,
+                open_exit_fee: Some(Decimal::percent(6)),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::FeeUpdateTooSoon { .. }));
+
+    // ... but succeeds once the cooldown has elapsed.
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(86_400);
+    });
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdateFees {
+            hatch_entry_fee: None,
+            open_entry_fee: None,
+            open_exit_fee: Some(Decimal::percent(6)),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+/// [`ExecuteMsg::UpdatePhaseConfig`] replaces the fee fields too, so it
+/// must be bound by the exact same cap and cooldown as
+/// [`ExecuteMsg::UpdateFees`] -- including sharing the cooldown clock
+/// with it, so a `CurveAdmin` can't dodge a just-triggered cooldown by
+/// switching to this execute instead. A config change that leaves the
+/// fees untouched isn't rate-limited at all.
+#[test]
+fn update_phase_config_enforces_fee_cap_and_shares_cooldown_with_update_fees() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    let base_config: PhaseConfigResponse =
+        app.wrap().query_wasm_smart(&abc, &QueryMsg::PhaseConfig {}).unwrap();
+    let base_config = base_config.phase_config;
+
+    // A fee above the cap is rejected even via the whole-config path.
+    let mut over_cap = base_config.clone();
+    over_cap.open.exit_fee = Decimal::percent(99);
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            abc.clone(),
+            &ExecuteMsg::UpdatePhaseConfig { phase_config: over_cap },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::FeeExceedsMax { .. }));
+
+    // Changing only the allowlist (no fee change) isn't rate-limited.
+    let mut allowlist_only = base_config.clone();
+    allowlist_only.hatch.allowlist = Some(vec![Addr::unchecked(HATCHER1)]);
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhaseConfig { phase_config: allowlist_only.clone() },
+        &[],
+    )
+    .unwrap();
+
+    // UpdateFees starts the cooldown clock...
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdateFees {
+            hatch_entry_fee: None,
+            open_entry_fee: None,
+            open_exit_fee: Some(Decimal::percent(3)),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // ...which UpdatePhaseConfig can't dodge by changing a fee itself.
+    let mut fee_change = allowlist_only.clone();
+    fee_change.open.exit_fee = Decimal::percent(4);
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            abc.clone(),
+            &ExecuteMsg::UpdatePhaseConfig { phase_config: fee_change.clone() },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::FeeUpdateTooSoon { .. }));
+
+    // Once the cooldown elapses, the fee change goes through.
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(86_400);
+    });
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhaseConfig { phase_config: fee_change },
+        &[],
+    )
+    .unwrap();
+}
+
+/// [`ExecuteMsg::VetoTimelockedAction`] can cancel a queued
+/// [`TimelockedAction::Close`] within the configured window, but not
+/// after it expires -- the timelock's own delay still has to be long
+/// enough to cover the veto window for that to matter in practice.
+#[test]
+fn veto_cancels_queued_close_within_window_but_not_after() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+    let veto = "veto";
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetTimelockConfig {
+            config: Some(crate::msg::TimelockConfigMsg { delay_seconds: 10_000 }),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetVetoAddress {
+            config: Some(crate::msg::VetoConfigMsg {
+                veto: veto.to_string(),
+                window_seconds: 1_000,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::QueueTimelockedAction { action: TimelockedAction::Close },
+        &[],
+    )
+    .unwrap();
+
+    // A stranger can't veto.
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::VetoTimelockedAction { id: 0, reason: "no".to_string() },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        Addr::unchecked(veto),
+        abc.clone(),
+        &ExecuteMsg::VetoTimelockedAction { id: 0, reason: "not yet".to_string() },
+        &[],
+    )
+    .unwrap();
+
+    // Queue a second Close and let the veto window lapse before trying.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::QueueTimelockedAction { action: TimelockedAction::Close },
+        &[],
+    )
+    .unwrap();
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(1_001);
+    });
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(veto),
+            abc.clone(),
+            &ExecuteMsg::VetoTimelockedAction { id: 1, reason: "too late".to_string() },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::VetoWindowExpired { id: 1 }));
+}
+
+/// A [`ExecuteMsg::SetMaintenanceOperator`] grant is scoped to exactly
+/// the permission bits it sets, and closing the commons always requires
+/// actual ownership regardless of the operator's bitmask.
+#[test]
+fn maintenance_operator_permission_is_scoped_and_close_stays_owner_only() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+    let operator = "operator";
+
+    // Not yet delegated: the operator can't advance the phase.
+    app.execute_contract(
+        Addr::unchecked(operator),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhase { new_phase: Phase::Open },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetMaintenanceOperator {
+            operator: Some(crate::msg::MaintenanceOperatorMsg {
+                operator: operator.to_string(),
+                permissions: crate::state::OPERATOR_PERM_UPDATE_PHASE,
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Delegated: the operator can advance Hatch -> Open ...
+    app.execute_contract(
+        Addr::unchecked(operator),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhase { new_phase: Phase::Open },
+        &[],
+    )
+    .unwrap();
+
+    // ... but not close the commons, even with a phase-update grant.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(operator),
+            abc.clone(),
+            &ExecuteMsg::UpdatePhase { new_phase: Phase::Closed },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::Ownable(_)));
+
+    // The owner can still close it directly.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::UpdatePhase { new_phase: Phase::Closed },
+        &[],
+    )
+    .unwrap();
+}
+
+/// The [`ExecuteMsg::QueueTimelockedAction`] lifecycle for a fee update
+/// queued by the owner: running it early fails, once the delay elapses it
+/// runs, and running it a second time finds it gone from the queue.
+#[test]
+fn timelocked_fee_update_runs_only_after_delay_elapses() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetTimelockConfig {
+            config: Some(crate::msg::TimelockConfigMsg { delay_seconds: 1_000 }),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Direct updates are no longer allowed once a timelock is configured.
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::QueueTimelockedAction {
+            action: TimelockedAction::UpdateFees {
+                hatch_entry_fee: None,
+                open_entry_fee: None,
+                open_exit_fee: Some(Decimal::percent(5)),
+            },
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Too early: the delay hasn't elapsed yet.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            abc.clone(),
+            &ExecuteMsg::ExecuteTimelockedAction { id: 0 },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::TimelockNotReady { .. }));
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(1_000);
+    });
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::ExecuteTimelockedAction { id: 0 },
+        &[],
+    )
+    .unwrap();
+
+    // Running it again fails: it was removed from the queue once applied.
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            abc.clone(),
+            &ExecuteMsg::ExecuteTimelockedAction { id: 0 },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::TimelockNotFound { id: 0 }));
+}
+
+/// [`ExecuteMsg::CancelTimelockedAction`] can withdraw a queued action any
+/// time before it runs, and requires the same authorization the action
+/// itself would.
+#[test]
+fn cancel_timelocked_action_withdraws_before_it_runs() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::SetTimelockConfig {
+            config: Some(crate::msg::TimelockConfigMsg { delay_seconds: 1_000 }),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::QueueTimelockedAction { action: TimelockedAction::Close },
+        &[],
+    )
+    .unwrap();
+
+    // A non-owner can't cancel a Close timelock.
+    app.execute_contract(
+        Addr::unchecked(HATCHER1),
+        abc.clone(),
+        &ExecuteMsg::CancelTimelockedAction { id: 0 },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::CancelTimelockedAction { id: 0 },
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(1_000);
+    });
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            abc.clone(),
+            &ExecuteMsg::ExecuteTimelockedAction { id: 0 },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert!(matches!(err, ContractError::TimelockNotFound { id: 0 }));
+}
+
+/// The maintainer-flagged freeze bypass: a role granted before
+/// [`ExecuteMsg::Freeze`] must stop working after it, exactly like the
+/// owner itself does. Regression test for the fee/allowlist executes
+/// missing `assert_not_frozen`.
+#[test]
+fn freeze_blocks_fee_and_allowlist_updates_despite_granted_roles() {
+    let mut app = mock_app();
+    let code_id = app.store_code(abc_contract());
+    let abc = app
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &instantiate_msg(), &[], "abc", None)
+        .unwrap();
+    let fee_admin = "fee_admin";
+    let allowlist_manager = "allowlist_manager";
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::GrantRole { role: Role::FeeAdmin, address: fee_admin.to_string() },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abc.clone(),
+        &ExecuteMsg::GrantRole {
+            role: Role::AllowlistManager,
+            address: allowlist_manager.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Both roles work before the freeze.
+    app.execute_contract(
+        Addr::unchecked(fee_admin),
+        abc.clone(),
+        &ExecuteMsg::UpdateFees {
+            hatch_entry_fee: None,
+            open_entry_fee: None,
+            open_exit_fee: Some(Decimal::percent(1)),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(Addr::unchecked(OWNER), abc.clone(), &ExecuteMsg::Freeze {}, &[]).unwrap();
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(fee_admin),
+            abc.clone(),
+            &ExecuteMsg::UpdateFees {
+                hatch_entry_fee: None,
+                open_entry_fee: None,
+                open_exit_fee: Some(Decimal::percent(2)),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Frozen {});
+
+    let err: ContractError = app
+        .execute_contract(
+            Addr::unchecked(allowlist_manager),
+            abc.clone(),
+            &ExecuteMsg::UpdateHatchAllowlist { add: vec![HATCHER1.to_string()], remove: vec![] },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Frozen {});
+}
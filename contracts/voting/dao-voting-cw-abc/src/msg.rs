@@ -0,0 +1,93 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
+use cw_utils::Duration;
+use dao_interface::Admin;
+use dao_macros::{active_query, voting_module_query};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    // Owner can update all configs including changing the owner. This will generally be a DAO.
+    pub owner: Option<Admin>,
+    // Manager can update all configs except changing the owner. This will generally be an operations multisig for a DAO.
+    pub manager: Option<String>,
+    /// The cw-abc commons whose supply token backs voting power here.
+    /// Must be instantiated with `SupplyTokenMode::TokenFactory`.
+    pub abc_contract: String,
+    // How long until the tokens become liquid again
+    pub unstaking_duration: Option<Duration>,
+    /// The amount of tokens that must be staked for this voting module to
+    /// be considered active. If not reached, `IsActive` reports `false`
+    /// and proposal modules that respect it will refuse new proposals.
+    pub active_threshold: Option<ActiveThreshold>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Stake {},
+    Unstake {
+        amount: Uint128,
+    },
+    UpdateConfig {
+        owner: Option<String>,
+        manager: Option<String>,
+        duration: Option<Duration>,
+    },
+    Claim {},
+    /// Sets the active threshold to a new value. Only the DAO that
+    /// instantiated this contract may call this method.
+    UpdateActiveThreshold {
+        new_threshold: Option<ActiveThreshold>,
+    },
+}
+
+/// The threshold of supply tokens that must be staked in order for this
+/// voting module to be active. If this is not reached, this module will
+/// respond to `IsActive` queries with `false`, and proposal modules which
+/// respect active thresholds will not allow the creation of proposals.
+#[cw_serde]
+pub enum ActiveThreshold {
+    /// The absolute number of supply tokens that must be staked for the
+    /// module to be active.
+    AbsoluteCount { count: Uint128 },
+    /// The percentage of the cw-abc commons' outstanding supply that must
+    /// be staked for the module to be active. Computed as `staked /
+    /// supply`.
+    Percentage { percent: Decimal },
+}
+
+#[voting_module_query]
+#[active_query]
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(crate::state::Config)]
+    GetConfig {},
+    #[returns(cw_controllers::ClaimsResponse)]
+    Claims { address: String },
+    #[returns(ListStakersResponse)]
+    ListStakers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(ActiveThresholdResponse)]
+    ActiveThreshold {},
+}
+
+#[cw_serde]
+pub struct ActiveThresholdResponse {
+    pub active_threshold: Option<ActiveThreshold>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_serde]
+pub struct ListStakersResponse {
+    pub stakers: Vec<StakerBalanceResponse>,
+}
+
+#[cw_serde]
+pub struct StakerBalanceResponse {
+    pub address: String,
+    pub balance: Uint128,
+}
@@ -0,0 +1,44 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_controllers::Claims;
+use cw_storage_plus::{Item, SnapshotItem, SnapshotMap, Strategy};
+use cw_utils::Duration;
+
+use crate::msg::ActiveThreshold;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Option<Addr>,
+    pub manager: Option<Addr>,
+    /// The cw-abc commons whose supply token is staked here for voting
+    /// power.
+    pub abc_contract: Addr,
+    /// The supply denom of `abc_contract`, resolved once at instantiation.
+    pub denom: String,
+    pub unstaking_duration: Option<Duration>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const DAO: Item<Addr> = Item::new("dao");
+pub const STAKED_BALANCES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "staked_balances",
+    "staked_balance__checkpoints",
+    "staked_balance__changelog",
+    Strategy::EveryBlock,
+);
+
+pub const STAKED_TOTAL: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_staked",
+    "total_staked__checkpoints",
+    "total_staked__changelog",
+    Strategy::EveryBlock,
+);
+
+/// The maximum number of claims that may be outstanding.
+pub const MAX_CLAIMS: u64 = 100;
+
+pub const CLAIMS: Claims = Claims::new("claims");
+
+/// The threshold of staked supply tokens below which `IsActive` reports
+/// `false`. Unset (the default) means the module is always active.
+pub const ACTIVE_THRESHOLD: Item<ActiveThreshold> = Item::new("active_threshold");
@@ -0,0 +1,79 @@
+#![doc = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md"))]
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_binary, Addr, StdResult, Storage, SubMsg, Uint128, WasmMsg};
+use cw_hooks::Hooks;
+
+#[cw_serde]
+pub enum AbcHookMsg {
+    Buy {
+        buyer: Addr,
+        amount: Uint128,
+        fee: Uint128,
+    },
+    Sell {
+        seller: Addr,
+        amount: Uint128,
+        fee: Uint128,
+    },
+    PhaseChanged {
+        old_phase: String,
+        new_phase: String,
+    },
+}
+
+// This is just a helper to properly serialize the above message
+#[cw_serde]
+enum AbcHookExecuteMsg {
+    AbcHook(AbcHookMsg),
+}
+
+/// Fire-and-forget: subscribers are notified via plain `SubMsg`s, so a
+/// failing subscriber never rolls back or blocks the triggering buy,
+/// sell, or phase change.
+fn hook_msgs(hooks: Hooks, storage: &dyn Storage, msg: AbcHookMsg) -> StdResult<Vec<SubMsg>> {
+    let msg = to_binary(&AbcHookExecuteMsg::AbcHook(msg))?;
+    hooks.prepare_hooks(storage, |a| {
+        Ok(SubMsg::new(WasmMsg::Execute {
+            contract_addr: a.to_string(),
+            msg: msg.clone(),
+            funds: vec![],
+        }))
+    })
+}
+
+pub fn buy_hook_msgs(
+    hooks: Hooks,
+    storage: &dyn Storage,
+    buyer: Addr,
+    amount: Uint128,
+    fee: Uint128,
+) -> StdResult<Vec<SubMsg>> {
+    hook_msgs(hooks, storage, AbcHookMsg::Buy { buyer, amount, fee })
+}
+
+pub fn sell_hook_msgs(
+    hooks: Hooks,
+    storage: &dyn Storage,
+    seller: Addr,
+    amount: Uint128,
+    fee: Uint128,
+) -> StdResult<Vec<SubMsg>> {
+    hook_msgs(hooks, storage, AbcHookMsg::Sell { seller, amount, fee })
+}
+
+pub fn phase_changed_hook_msgs(
+    hooks: Hooks,
+    storage: &dyn Storage,
+    old_phase: String,
+    new_phase: String,
+) -> StdResult<Vec<SubMsg>> {
+    hook_msgs(
+        hooks,
+        storage,
+        AbcHookMsg::PhaseChanged {
+            old_phase,
+            new_phase,
+        },
+    )
+}
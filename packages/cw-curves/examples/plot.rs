@@ -0,0 +1,103 @@
+//! Prints a CSV of `(supply, spot_price, reserve)` sample points for a
+//! curve, in whole tokens, using the exact same [`Curve`] impls the
+//! on-chain contract prices buys and sells against -- so a token
+//! engineer can sanity-check a curve's shape before instantiating it,
+//! without spinning up a chain or a multi-test harness.
+//!
+//! No CLI-argument crate is a workspace dependency, so args are parsed
+//! by hand rather than pulling one in just for this example.
+//!
+//! ```text
+//! cargo run -p cw-curves --example plot -- linear --slope 0.02 --max-supply 1000
+//! cargo run -p cw-curves --example plot -- square-root --slope 3 --max-supply 500 --steps 50
+//! cargo run -p cw-curves --example plot -- constant --value 1.5
+//! ```
+use cw_curves::{CurveType, DecimalPlaces};
+
+use cosmwasm_std::{Decimal, Uint128};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut args = args.into_iter();
+
+    let Some(kind) = args.next() else {
+        print_usage_and_exit();
+    };
+
+    let mut param: Option<Decimal> = None;
+    let mut supply_decimals: u8 = 6;
+    let mut reserve_decimals: u8 = 6;
+    let mut max_supply: u128 = 1_000;
+    let mut steps: u128 = 20;
+
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_else(|| print_usage_and_exit());
+        match flag.as_str() {
+            "--value" | "--slope" => param = Some(parse_decimal(&value)),
+            "--supply-decimals" => supply_decimals = parse_u8(&value),
+            "--reserve-decimals" => reserve_decimals = parse_u8(&value),
+            "--max-supply" => max_supply = parse_u128(&value),
+            "--steps" => steps = parse_u128(&value),
+            other => {
+                eprintln!("unknown flag {other}");
+                print_usage_and_exit();
+            }
+        }
+    }
+    let param = param.unwrap_or_else(|| {
+        eprintln!("{kind} requires --value or --slope");
+        print_usage_and_exit()
+    });
+
+    let curve_type = match kind.as_str() {
+        "constant" => CurveType::Constant { value: param, scale: supply_decimals as u32 },
+        "linear" => CurveType::Linear { slope: param, scale: supply_decimals as u32 },
+        "square-root" => CurveType::SquareRoot { slope: param, scale: supply_decimals as u32 },
+        other => {
+            eprintln!("unknown curve type {other}");
+            print_usage_and_exit()
+        }
+    };
+
+    let decimals = DecimalPlaces::new(supply_decimals, reserve_decimals);
+    let curve = curve_type.to_curve_fn()(decimals);
+    let reserve_scale = Uint128::new(10u128.pow(reserve_decimals as u32));
+
+    println!("supply,spot_price,reserve");
+    for step in 0..=steps {
+        let supply_tokens = Decimal::from_ratio(max_supply * step, steps);
+        let supply = decimals.to_supply(supply_tokens);
+        let spot_price = curve.spot_price(supply);
+        let reserve = Decimal::from_ratio(curve.reserve(supply), reserve_scale);
+        println!("{supply_tokens},{spot_price},{reserve}");
+    }
+}
+
+fn parse_decimal(s: &str) -> Decimal {
+    s.parse().unwrap_or_else(|_| {
+        eprintln!("invalid decimal: {s}");
+        print_usage_and_exit()
+    })
+}
+
+fn parse_u8(s: &str) -> u8 {
+    s.parse().unwrap_or_else(|_| {
+        eprintln!("invalid integer: {s}");
+        print_usage_and_exit()
+    })
+}
+
+fn parse_u128(s: &str) -> u128 {
+    s.parse().unwrap_or_else(|_| {
+        eprintln!("invalid integer: {s}");
+        print_usage_and_exit()
+    })
+}
+
+fn print_usage_and_exit() -> ! {
+    eprintln!(
+        "usage: plot <constant|linear|square-root> (--value|--slope) <decimal> \
+         [--supply-decimals N] [--reserve-decimals N] [--max-supply N] [--steps N]"
+    );
+    std::process::exit(1);
+}
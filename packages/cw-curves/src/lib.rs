@@ -0,0 +1,435 @@
+//! Bonding curve math for pricing a supply of minted tokens against a
+//! reserve. Kept free of any cosmwasm entry-point or contract-state
+//! dependencies, so it's reusable outside of
+//! [`cw-abc`](https://docs.rs/cw-abc), the contract it was extracted
+//! from.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, OverflowError, Uint128};
+
+/// Maps a supply of the token being minted to the reserve balance backing
+/// it, and vice versa.
+pub trait Curve {
+    /// Returns the price of the next unit of supply, in reserve tokens.
+    fn spot_price(&self, supply: Uint128) -> Decimal;
+
+    /// Returns the total reserve that must have been paid in for `supply`
+    /// tokens to have been minted off this curve.
+    fn reserve(&self, supply: Uint128) -> Uint128;
+
+    /// Inverse of `reserve`: how much supply would `reserve` tokens have
+    /// minted.
+    fn supply(&self, reserve: Uint128) -> Uint128;
+}
+
+/// The result of pricing a buy or sell against a [`Curve`]: where its
+/// supply/reserve land afterwards, and how much supply or reserve changed
+/// hands. Pure curve math only — a caller pricing a real buy/sell still
+/// has its own fees, treasury splits, or other deductions to apply to the
+/// payment/proceeds before or after calling [`quote_buy`]/[`quote_sell`].
+#[cw_serde]
+pub struct Quote {
+    pub new_supply: Uint128,
+    pub new_reserve: Uint128,
+    /// Supply minted (for a buy) or reserve released (for a sell).
+    pub delta: Uint128,
+}
+
+/// Quotes a buy of `payment` reserve tokens against `curve`, currently at
+/// `supply`/`reserve`. `delta` is the supply that would be minted.
+pub fn quote_buy(
+    curve: &dyn Curve,
+    supply: Uint128,
+    reserve: Uint128,
+    payment: Uint128,
+) -> Result<Quote, OverflowError> {
+    let new_reserve = reserve.checked_add(payment)?;
+    let new_supply = curve.supply(new_reserve);
+    let minted = new_supply.checked_sub(supply)?;
+    Ok(Quote { new_supply, new_reserve, delta: minted })
+}
+
+/// Quotes a sell of `amount` of supply against `curve`, currently at
+/// `supply`/`reserve`. `delta` is the reserve that would be released.
+pub fn quote_sell(
+    curve: &dyn Curve,
+    supply: Uint128,
+    reserve: Uint128,
+    amount: Uint128,
+) -> Result<Quote, OverflowError> {
+    let new_supply = supply.checked_sub(amount)?;
+    let new_reserve = curve.reserve(new_supply);
+    let released = reserve.checked_sub(new_reserve)?;
+    Ok(Quote { new_supply, new_reserve, delta: released })
+}
+
+/// The number of decimal places the supply and reserve tokens are
+/// denominated in. Needed because the curve math above is performed in
+/// whole units, not the micro-denominations tokens are transferred in.
+#[cw_serde]
+#[derive(Copy)]
+pub struct DecimalPlaces {
+    pub supply: u32,
+    pub reserve: u32,
+}
+
+impl DecimalPlaces {
+    pub fn new(supply: u8, reserve: u8) -> Self {
+        DecimalPlaces {
+            supply: supply as u32,
+            reserve: reserve as u32,
+        }
+    }
+
+    pub fn to_supply(&self, base: Decimal) -> Uint128 {
+        decimal_to_atomics(base, self.supply)
+    }
+
+    pub fn to_reserve(&self, base: Decimal) -> Uint128 {
+        decimal_to_atomics(base, self.reserve)
+    }
+}
+
+/// Converts a whole-unit `Decimal` (e.g. `4.5` tokens) into atomics at
+/// `places` decimal places (e.g. `4_500_000` at 6 places). `Decimal`
+/// itself is always fixed-point at `x.decimal_places()` (18) places, so
+/// this scales its raw atomics down to `places` by dividing out the
+/// difference, rather than multiplying by it -- multiplying would leave
+/// every result at the fixed 18-decimal scale regardless of `places`.
+fn decimal_to_atomics(x: Decimal, places: u32) -> Uint128 {
+    x.atomics() / Uint128::new(10u128.pow(x.decimal_places().saturating_sub(places)))
+}
+
+/// A curve whose spot price is constant, regardless of supply.
+#[cw_serde]
+pub struct ConstantCurve {
+    pub value: Decimal,
+    pub decimals: DecimalPlaces,
+}
+
+impl Curve for ConstantCurve {
+    fn spot_price(&self, _supply: Uint128) -> Decimal {
+        self.value
+    }
+
+    fn reserve(&self, supply: Uint128) -> Uint128 {
+        let normalized = Decimal::new(supply) / Decimal::new(self.decimals.to_supply(Decimal::one()));
+        self.decimals.to_reserve(normalized * self.value)
+    }
+
+    fn supply(&self, reserve: Uint128) -> Uint128 {
+        let normalized = Decimal::new(reserve) / Decimal::new(self.decimals.to_reserve(self.value));
+        self.decimals.to_supply(normalized)
+    }
+}
+
+/// A curve whose spot price grows linearly with supply: `price = slope *
+/// supply`.
+#[cw_serde]
+pub struct LinearCurve {
+    pub slope: Decimal,
+    pub decimals: DecimalPlaces,
+}
+
+impl Curve for LinearCurve {
+    fn spot_price(&self, supply: Uint128) -> Decimal {
+        let normalized_supply = Decimal::new(supply) / Decimal::new(self.decimals.to_supply(Decimal::one()));
+        self.slope * normalized_supply
+    }
+
+    fn reserve(&self, supply: Uint128) -> Uint128 {
+        // integral of slope * s ds from 0 to supply == slope * supply^2 / 2
+        let normalized_supply = Decimal::new(supply) / Decimal::new(self.decimals.to_supply(Decimal::one()));
+        let reserve = self.slope * normalized_supply * normalized_supply
+            / Decimal::from_ratio(2u128, 1u128);
+        self.decimals.to_reserve(reserve)
+    }
+
+    fn supply(&self, reserve: Uint128) -> Uint128 {
+        // invert reserve = slope * supply^2 / 2 => supply = sqrt(2 * reserve / slope)
+        let normalized_reserve = Decimal::new(reserve) / Decimal::new(self.decimals.to_reserve(Decimal::one()));
+        let squared = normalized_reserve * Decimal::from_ratio(2u128, 1u128) / self.slope;
+        self.decimals.to_supply(squared.sqrt())
+    }
+}
+
+/// A curve whose spot price grows with the square root of supply.
+#[cw_serde]
+pub struct SquareRootCurve {
+    pub slope: Decimal,
+    pub decimals: DecimalPlaces,
+}
+
+impl Curve for SquareRootCurve {
+    fn spot_price(&self, supply: Uint128) -> Decimal {
+        let normalized_supply = Decimal::new(supply) / Decimal::new(self.decimals.to_supply(Decimal::one()));
+        self.slope * normalized_supply.sqrt()
+    }
+
+    fn reserve(&self, supply: Uint128) -> Uint128 {
+        // integral of slope * sqrt(s) ds from 0 to supply == slope * (2/3) * supply^1.5
+        let normalized_supply = Decimal::new(supply) / Decimal::new(self.decimals.to_supply(Decimal::one()));
+        let reserve = self.slope * normalized_supply * normalized_supply.sqrt()
+            * Decimal::from_ratio(2u128, 1u128)
+            / Decimal::from_ratio(3u128, 1u128);
+        self.decimals.to_reserve(reserve)
+    }
+
+    fn supply(&self, reserve: Uint128) -> Uint128 {
+        // there is no closed form inverse for the cubic above; narrow it
+        // down with a handful of Newton's method iterations instead.
+        let normalized_reserve = Decimal::new(reserve) / Decimal::new(self.decimals.to_reserve(Decimal::one()));
+        let mut supply = normalized_reserve;
+        for _ in 0..32 {
+            if supply.is_zero() {
+                break;
+            }
+            let f = self.slope * supply * supply.sqrt() * Decimal::from_ratio(2u128, 3u128)
+                - normalized_reserve;
+            let f_prime = self.slope * supply.sqrt();
+            if f_prime.is_zero() {
+                break;
+            }
+            supply = supply - f / f_prime;
+        }
+        self.decimals.to_supply(supply)
+    }
+}
+
+/// The curve configuration stored on-chain. Kept serializable so it can be
+/// set at instantiation and inflated into a boxed [`Curve`] at execution
+/// time via [`CurveType::to_curve_fn`].
+#[cw_serde]
+pub enum CurveType {
+    Constant { value: Decimal, scale: u32 },
+    Linear { slope: Decimal, scale: u32 },
+    SquareRoot { slope: Decimal, scale: u32 },
+}
+
+/// A closure that, given the supply/reserve decimal places, produces the
+/// boxed curve implementation. Deferred like this so `CurveType` can stay
+/// `Serialize`/`Deserialize` while `Curve` itself does not need to be.
+pub type CurveFn = Box<dyn Fn(DecimalPlaces) -> Box<dyn Curve>>;
+
+impl CurveType {
+    pub fn to_curve_fn(&self) -> CurveFn {
+        match self.clone() {
+            CurveType::Constant { value, .. } => {
+                Box::new(move |decimals| -> Box<dyn Curve> {
+                    Box::new(ConstantCurve { value, decimals })
+                })
+            }
+            CurveType::Linear { slope, .. } => Box::new(move |decimals| -> Box<dyn Curve> {
+                Box::new(LinearCurve { slope, decimals })
+            }),
+            CurveType::SquareRoot { slope, .. } => Box::new(move |decimals| -> Box<dyn Curve> {
+                Box::new(SquareRootCurve { slope, decimals })
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A curve/scale/point combination pinned by `golden_values_across_curves_and_scales`.
+    /// `expected_spot_price`/`expected_reserve_tokens` are derived by hand from each
+    /// curve's own doc-comment formula, expressed in whole tokens so the same numbers
+    /// hold at every `decimals` scale below.
+    struct Case {
+        name: &'static str,
+        curve: CurveType,
+        decimals: DecimalPlaces,
+        supply_tokens: u128,
+        expected_spot_price: Decimal,
+        expected_reserve_tokens: Decimal,
+    }
+
+    #[test]
+    fn golden_values_across_curves_and_scales() {
+        let cases = [
+            Case {
+                name: "constant @ 6/6, supply 4",
+                curve: CurveType::Constant { value: Decimal::percent(150), scale: 6 },
+                decimals: DecimalPlaces::new(6, 6),
+                supply_tokens: 4,
+                expected_spot_price: Decimal::percent(150),
+                expected_reserve_tokens: Decimal::percent(600), // 4 * 1.5
+            },
+            Case {
+                name: "constant @ 18/2, supply 4",
+                curve: CurveType::Constant { value: Decimal::percent(150), scale: 18 },
+                decimals: DecimalPlaces::new(18, 2),
+                supply_tokens: 4,
+                expected_spot_price: Decimal::percent(150),
+                expected_reserve_tokens: Decimal::percent(600),
+            },
+            Case {
+                name: "linear @ 6/6, supply 3",
+                curve: CurveType::Linear { slope: Decimal::percent(200), scale: 6 },
+                decimals: DecimalPlaces::new(6, 6),
+                supply_tokens: 3,
+                expected_spot_price: Decimal::percent(600), // slope * supply == 2 * 3
+                expected_reserve_tokens: Decimal::percent(900), // slope * supply^2 / 2 == 2 * 9 / 2
+            },
+            Case {
+                name: "linear @ 8/2, supply 3",
+                curve: CurveType::Linear { slope: Decimal::percent(200), scale: 8 },
+                decimals: DecimalPlaces::new(8, 2),
+                supply_tokens: 3,
+                expected_spot_price: Decimal::percent(600),
+                expected_reserve_tokens: Decimal::percent(900),
+            },
+            Case {
+                name: "square_root @ 6/6, supply 4",
+                curve: CurveType::SquareRoot { slope: Decimal::percent(300), scale: 6 },
+                decimals: DecimalPlaces::new(6, 6),
+                supply_tokens: 4,
+                expected_spot_price: Decimal::percent(600), // slope * sqrt(supply) == 3 * 2
+                // slope * supply * sqrt(supply) * 2/3 == 3 * 4 * 2 * 2/3
+                expected_reserve_tokens: Decimal::percent(1600),
+            },
+        ];
+
+        for case in cases {
+            let curve = case.curve.to_curve_fn()(case.decimals);
+            let supply = case.decimals.to_supply(Decimal::from_ratio(case.supply_tokens, 1u128));
+
+            assert_eq!(
+                curve.spot_price(supply),
+                case.expected_spot_price,
+                "{}: spot_price",
+                case.name
+            );
+
+            let expected_reserve = case.decimals.to_reserve(case.expected_reserve_tokens);
+            assert_eq!(curve.reserve(supply), expected_reserve, "{}: reserve", case.name);
+        }
+    }
+
+    #[test]
+    fn golden_supply_from_reserve_round_trip() {
+        // Constant and Linear invert `reserve` with a closed-form formula, so their
+        // `supply()` is exact. SquareRoot's is Newton's method instead, and gets its
+        // own tolerance-checked test below rather than an exact literal.
+        let decimals = DecimalPlaces::new(6, 6);
+
+        let constant = CurveType::Constant { value: Decimal::percent(150), scale: 6 };
+        let constant = constant.to_curve_fn()(decimals);
+        let reserve = decimals.to_reserve(Decimal::percent(600)); // 4 tokens * 1.5
+        let expected_supply = decimals.to_supply(Decimal::from_ratio(4u128, 1u128));
+        assert_eq!(constant.supply(reserve), expected_supply);
+
+        let linear = CurveType::Linear { slope: Decimal::percent(200), scale: 6 };
+        let linear = linear.to_curve_fn()(decimals);
+        let reserve = decimals.to_reserve(Decimal::percent(900)); // slope * 3^2 / 2 == 9
+        let expected_supply = decimals.to_supply(Decimal::from_ratio(3u128, 1u128));
+        assert_eq!(linear.supply(reserve), expected_supply);
+    }
+
+    #[test]
+    fn square_root_supply_converges_to_the_forward_formula() {
+        let decimals = DecimalPlaces::new(6, 6);
+        let curve_type = CurveType::SquareRoot { slope: Decimal::percent(300), scale: 6 };
+        let curve = curve_type.to_curve_fn()(decimals);
+
+        let reserve = decimals.to_reserve(Decimal::percent(1600)); // slope*4*sqrt(4)*2/3 == 16
+        let supply = curve.supply(reserve);
+        let expected = decimals.to_supply(Decimal::from_ratio(4u128, 1u128));
+        let diff = if supply > expected { supply - expected } else { expected - supply };
+        assert!(
+            diff <= Uint128::new(1),
+            "Newton's method should land within 1 atomic unit of the forward formula's \
+             inverse, got {supply} vs {expected}"
+        );
+    }
+
+    fn decimal_to_f64(d: Decimal) -> f64 {
+        d.atomics().u128() as f64 / 10f64.powi(d.decimal_places() as i32)
+    }
+
+    fn atomics_to_f64(amount: Uint128, decimals: u32) -> f64 {
+        amount.u128() as f64 / 10f64.powi(decimals as i32)
+    }
+
+    fn tokens_to_atomics(tokens: u128, decimals: u32) -> Uint128 {
+        Uint128::new(tokens * 10u128.pow(decimals))
+    }
+
+    fn assert_close(actual: f64, expected: f64, label: &str) {
+        const RELATIVE_TOLERANCE: f64 = 1e-6;
+        let scale = expected.abs().max(1.0);
+        assert!(
+            (actual - expected).abs() <= RELATIVE_TOLERANCE * scale,
+            "{label}: expected {expected}, got {actual}"
+        );
+    }
+
+    /// Compares each curve's on-chain, `DecimalPlaces`-scaled integer math
+    /// against a straightforward f64 reference model of the same formula,
+    /// across a range of supply points and `DecimalPlaces` scales. Uses
+    /// real per-token-decimals atomics as input (`tokens_to_atomics`,
+    /// matching how `cw-abc`'s contract calls these methods against
+    /// `CurveState`), not `DecimalPlaces::to_supply`, so a systematic
+    /// scaling bug in the `DecimalPlaces` conversions themselves -- not
+    /// just a bug in a curve's own formula -- shows up here too.
+    #[test]
+    fn differential_test_against_f64_reference() {
+        let supply_points = [1u128, 3, 7, 42, 1_000, 250_000];
+        let scales = [(6u32, 6u32), (8, 2), (18, 18)];
+
+        for (supply_decimals, reserve_decimals) in scales {
+            let decimals = DecimalPlaces::new(supply_decimals as u8, reserve_decimals as u8);
+
+            let constant_value = 1.5;
+            let constant_type =
+                CurveType::Constant { value: Decimal::percent(150), scale: supply_decimals };
+            let constant = constant_type.to_curve_fn()(decimals);
+            let linear_slope = 0.02;
+            let linear = CurveType::Linear { slope: Decimal::percent(2), scale: supply_decimals }
+                .to_curve_fn()(decimals);
+            let sqrt_slope = 3.0;
+            let square_root =
+                CurveType::SquareRoot { slope: Decimal::percent(300), scale: supply_decimals }
+                    .to_curve_fn()(decimals);
+
+            for supply_tokens in supply_points {
+                let supply = tokens_to_atomics(supply_tokens, supply_decimals);
+                let supply_f = supply_tokens as f64;
+
+                assert_close(
+                    decimal_to_f64(constant.spot_price(supply)),
+                    constant_value,
+                    "constant spot_price",
+                );
+                assert_close(
+                    atomics_to_f64(constant.reserve(supply), reserve_decimals),
+                    constant_value * supply_f,
+                    "constant reserve",
+                );
+
+                assert_close(
+                    decimal_to_f64(linear.spot_price(supply)),
+                    linear_slope * supply_f,
+                    "linear spot_price",
+                );
+                assert_close(
+                    atomics_to_f64(linear.reserve(supply), reserve_decimals),
+                    linear_slope * supply_f * supply_f / 2.0,
+                    "linear reserve",
+                );
+
+                assert_close(
+                    decimal_to_f64(square_root.spot_price(supply)),
+                    sqrt_slope * supply_f.sqrt(),
+                    "square_root spot_price",
+                );
+                assert_close(
+                    atomics_to_f64(square_root.reserve(supply), reserve_decimals),
+                    sqrt_slope * supply_f * supply_f.sqrt() * 2.0 / 3.0,
+                    "square_root reserve",
+                );
+            }
+        }
+    }
+}